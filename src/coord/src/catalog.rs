@@ -7,7 +7,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::mem;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::SystemTime;
@@ -21,19 +22,26 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use dataflow_types::{SinkConnector, SinkConnectorBuilder, SourceConnector};
+use dataflow_types::{
+    Consistency, KafkaSinkConnectorBuilder, SinkConnector, SinkConnectorBuilder, SourceConnector,
+};
 use expr::{GlobalId, Id, IdHumanizer, OptimizedRelationExpr, ScalarExpr};
-use repr::RelationDesc;
+use repr::{ColumnName, RelationDesc, ScalarType};
 use sql::ast::display::AstDisplay;
+use sql::ast::{
+    CommentObject, CommentStatement, CreateDatabaseStatement, CreateSchemaStatement, Ident,
+    ObjectName, ObjectType, Statement,
+};
 use sql::catalog::CatalogError as SqlCatalogError;
 use sql::names::{DatabaseSpecifier, FullName, PartialName, SchemaSpecifier};
-use sql::plan::{Params, Plan, PlanContext};
+use sql::plan::{FunctionReturns, Params, Plan, PlanContext};
 use transform::Optimizer;
 
 use crate::catalog::builtin::{
-    Builtin, BUILTINS, MZ_CATALOG_SCHEMA, MZ_TEMP_SCHEMA, PG_CATALOG_SCHEMA,
+    Builtin, BUILTINS, INFORMATION_SCHEMA, MZ_CATALOG_SCHEMA, MZ_TEMP_SCHEMA, PG_CATALOG_SCHEMA,
 };
 use crate::catalog::error::{Error, ErrorKind};
+use crate::coord::{prep_relation_expr, ExprPrepStyle};
 use crate::session::Session;
 
 mod config;
@@ -77,6 +85,9 @@ pub struct Catalog {
     by_name: BTreeMap<String, Database>,
     by_id: BTreeMap<GlobalId, CatalogEntry>,
     indexes: HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>>,
+    /// The comments on each item, keyed by the index of the commented column,
+    /// or by `None` for the comment on the item itself.
+    comments: HashMap<GlobalId, BTreeMap<Option<usize>, String>>,
     ambient_schemas: BTreeMap<String, Schema>,
     temporary_schemas: HashMap<u32, Schema>,
     storage: Arc<Mutex<storage::Connection>>,
@@ -86,6 +97,9 @@ pub struct Catalog {
     cluster_id: Uuid,
     /// Used to assign a PostgreSQL object ID (OID) to each object in the catalog.
     oid_counter: u32,
+    /// Incremented whenever the catalog changes, so that state derived from
+    /// the catalog, like cached plans, can detect that it is out of date.
+    transient_revision: u64,
     persistence_directory: Option<PathBuf>,
 }
 
@@ -140,6 +154,7 @@ pub enum CatalogItem {
     Sink(Sink),
     Index(Index),
     Type(Type),
+    Func(Func),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +162,7 @@ pub struct Table {
     pub create_sql: String,
     pub plan_cx: PlanContext,
     pub desc: RelationDesc,
+    pub depends_on: Vec<GlobalId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,12 +177,34 @@ pub struct Source {
 pub struct Sink {
     pub create_sql: String,
     pub plan_cx: PlanContext,
-    pub from: GlobalId,
+    pub from: SinkFrom,
     pub connector: SinkConnectorState,
     pub with_snapshot: bool,
     pub as_of: Option<u64>,
 }
 
+/// The relation that a sink reads from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SinkFrom {
+    /// A named source, table, or view.
+    Id(GlobalId),
+    /// An anonymous query, which is built as part of the sink's dataflow.
+    Query {
+        optimized_expr: OptimizedRelationExpr,
+        desc: RelationDesc,
+    },
+}
+
+impl SinkFrom {
+    /// Returns the identifiers of the objects that the relation depends upon.
+    pub fn uses(&self) -> Vec<GlobalId> {
+        match self {
+            SinkFrom::Id(id) => vec![*id],
+            SinkFrom::Query { optimized_expr, .. } => optimized_expr.as_ref().global_uses(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SinkConnectorState {
     Pending(SinkConnectorBuilder),
@@ -180,6 +218,7 @@ pub struct View {
     pub optimized_expr: OptimizedRelationExpr,
     pub desc: RelationDesc,
     pub conn_id: Option<u32>,
+    pub depends_on: Vec<GlobalId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -197,6 +236,15 @@ pub struct Type {
     pub inner: TypeInner,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Func {
+    pub create_sql: String,
+    pub plan_cx: PlanContext,
+    pub params: Vec<(Option<ColumnName>, ScalarType)>,
+    pub returns: FunctionReturns,
+    pub depends_on: Vec<GlobalId>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeInner {
     Base,
@@ -204,6 +252,13 @@ pub enum TypeInner {
         key_id: GlobalId,
         value_id: GlobalId,
     },
+    Enum {
+        labels: Vec<String>,
+    },
+    Record {
+        fields: Vec<(ColumnName, ScalarType)>,
+        depends_on: Vec<GlobalId>,
+    },
 }
 
 impl CatalogItem {
@@ -216,6 +271,7 @@ impl CatalogItem {
             CatalogItem::View(_) => "view",
             CatalogItem::Index(_) => "index",
             CatalogItem::Type(_) => "type",
+            CatalogItem::Func(_) => "function",
         }
     }
 
@@ -227,6 +283,9 @@ impl CatalogItem {
             CatalogItem::View(view) => Ok(&view.desc),
             CatalogItem::Index(_) => Err(SqlCatalogError::InvalidIndexDependency(name.to_string())),
             CatalogItem::Type(_) => Err(SqlCatalogError::InvalidTypeDependency(name.to_string())),
+            CatalogItem::Func(_) => {
+                Err(SqlCatalogError::InvalidFunctionDependency(name.to_string()))
+            }
         }
     }
 
@@ -234,15 +293,21 @@ impl CatalogItem {
     /// upon.
     pub fn uses(&self) -> Vec<GlobalId> {
         match self {
-            CatalogItem::Table(_) => vec![],
+            CatalogItem::Table(table) => table.depends_on.clone(),
             CatalogItem::Source(_) => vec![],
-            CatalogItem::Sink(sink) => vec![sink.from],
-            CatalogItem::View(view) => view.optimized_expr.as_ref().global_uses(),
+            CatalogItem::Sink(sink) => sink.from.uses(),
+            CatalogItem::View(view) => {
+                let mut uses = view.optimized_expr.as_ref().global_uses();
+                uses.extend(view.depends_on.iter().copied());
+                uses
+            }
             CatalogItem::Index(idx) => vec![idx.on],
             CatalogItem::Type(typ) => match &typ.inner {
-                TypeInner::Base { .. } => vec![],
+                TypeInner::Base { .. } | TypeInner::Enum { .. } => vec![],
                 TypeInner::Map { key_id, value_id } => vec![*key_id, *value_id],
+                TypeInner::Record { depends_on, .. } => depends_on.clone(),
             },
+            CatalogItem::Func(func) => func.depends_on.clone(),
         }
     }
 
@@ -254,7 +319,8 @@ impl CatalogItem {
             | CatalogItem::Source(_)
             | CatalogItem::View(_)
             | CatalogItem::Index(_)
-            | CatalogItem::Type(_) => false,
+            | CatalogItem::Type(_)
+            | CatalogItem::Func(_) => false,
             CatalogItem::Sink(s) => match s.connector {
                 SinkConnectorState::Pending(_) => true,
                 SinkConnectorState::Ready(_) => false,
@@ -322,6 +388,11 @@ impl CatalogItem {
                 Ok(CatalogItem::Index(i))
             }
             CatalogItem::Type(_) => unreachable!("types cannot be renamed"),
+            CatalogItem::Func(i) => {
+                let mut i = i.clone();
+                i.create_sql = do_rewrite(i.create_sql)?;
+                Ok(CatalogItem::Func(i))
+            }
         }
     }
 }
@@ -381,6 +452,7 @@ impl Catalog {
             by_name: BTreeMap::new(),
             by_id: BTreeMap::new(),
             indexes: HashMap::new(),
+            comments: HashMap::new(),
             ambient_schemas: BTreeMap::new(),
             temporary_schemas: HashMap::new(),
             storage: Arc::new(Mutex::new(storage)),
@@ -389,6 +461,7 @@ impl Catalog {
             experimental_mode,
             cluster_id,
             oid_counter: FIRST_USER_OID,
+            transient_revision: 0,
             persistence_directory: config.persistence_directory,
         };
         let mut events = vec![];
@@ -505,6 +578,7 @@ impl Catalog {
                             create_sql: "TODO".to_string(),
                             plan_cx: PlanContext::default(),
                             desc: table.desc.clone(),
+                            depends_on: vec![],
                         }),
                     ));
                     let oid = catalog.allocate_oid()?;
@@ -594,6 +668,21 @@ impl Catalog {
             events.push(catalog.insert_item(id, oid, name, item));
         }
 
+        let comments = catalog.storage().load_comments()?;
+        for (id, column, comment) in comments {
+            catalog
+                .comments
+                .entry(id)
+                .or_default()
+                .insert(column, comment.clone());
+            events.push(Event::UpdatedComment {
+                id,
+                column,
+                from: None,
+                to: Some(comment),
+            });
+        }
+
         Ok((catalog, events))
     }
 
@@ -872,7 +961,7 @@ impl Catalog {
                     .unwrap()
                     .push((id, index.keys.clone()));
             }
-            CatalogItem::Sink(_) | CatalogItem::Type(_) => (),
+            CatalogItem::Sink(_) | CatalogItem::Type(_) | CatalogItem::Func(_) => (),
         }
 
         let conn_id = entry.item().conn_id().unwrap_or(SYSTEM_CONN_ID);
@@ -892,37 +981,37 @@ impl Catalog {
         }
     }
 
-    pub fn drop_database_ops(&mut self, name: String) -> Vec<Op> {
+    pub fn drop_databases_ops(&mut self, names: Vec<String>) -> Vec<Op> {
         let mut ops = vec![];
         let mut seen = HashSet::new();
-        if let Some(database) = self.by_name.get(&name) {
-            for (schema_name, schema) in &database.schemas {
-                Self::drop_schema_items(schema, &self.by_id, &mut ops, &mut seen);
-                ops.push(Op::DropSchema {
-                    database_name: DatabaseSpecifier::Name(name.clone()),
-                    schema_name: schema_name.clone(),
-                });
+        for name in names {
+            if let Some(database) = self.by_name.get(&name) {
+                for (schema_name, schema) in &database.schemas {
+                    Self::drop_schema_items(schema, &self.by_id, &mut ops, &mut seen);
+                    ops.push(Op::DropSchema {
+                        database_name: DatabaseSpecifier::Name(name.clone()),
+                        schema_name: schema_name.clone(),
+                    });
+                }
+                ops.push(Op::DropDatabase { name });
             }
-            ops.push(Op::DropDatabase { name });
         }
         ops
     }
 
-    pub fn drop_schema_ops(
-        &mut self,
-        database_spec: DatabaseSpecifier,
-        schema_name: String,
-    ) -> Vec<Op> {
+    pub fn drop_schemas_ops(&mut self, schemas: Vec<(DatabaseSpecifier, String)>) -> Vec<Op> {
         let mut ops = vec![];
         let mut seen = HashSet::new();
-        if let DatabaseSpecifier::Name(database_name) = database_spec {
-            if let Some(database) = self.by_name.get(&database_name) {
-                if let Some(schema) = database.schemas.get(&schema_name) {
-                    Self::drop_schema_items(schema, &self.by_id, &mut ops, &mut seen);
-                    ops.push(Op::DropSchema {
-                        database_name: DatabaseSpecifier::Name(database_name),
-                        schema_name,
-                    })
+        for (database_spec, schema_name) in schemas {
+            if let DatabaseSpecifier::Name(database_name) = database_spec {
+                if let Some(database) = self.by_name.get(&database_name) {
+                    if let Some(schema) = database.schemas.get(&schema_name) {
+                        Self::drop_schema_items(schema, &self.by_id, &mut ops, &mut seen);
+                        ops.push(Op::DropSchema {
+                            database_name: DatabaseSpecifier::Name(database_name),
+                            schema_name,
+                        })
+                    }
                 }
             }
         }
@@ -931,8 +1020,9 @@ impl Catalog {
 
     pub fn drop_items_ops(&mut self, ids: &[GlobalId]) -> Vec<Op> {
         let mut ops = vec![];
+        let mut seen = HashSet::new();
         for &id in ids {
-            Self::drop_item_cascade(id, &self.by_id, &mut ops, &mut HashSet::new());
+            Self::drop_item_cascade(id, &self.by_id, &mut ops, &mut seen);
         }
         ops
     }
@@ -1029,6 +1119,11 @@ impl Catalog {
                 to_name: FullName,
                 item: CatalogItem,
             },
+            SetComment {
+                id: GlobalId,
+                column: Option<usize>,
+                comment: Option<String>,
+            },
         }
 
         let temporary_ids = self.temporary_ids(&ops)?;
@@ -1054,7 +1149,10 @@ impl Catalog {
                     schema_name,
                     oid,
                 } => {
-                    if schema_name.starts_with("mz_") || schema_name.starts_with("pg_") {
+                    if schema_name.starts_with("mz_")
+                        || schema_name.starts_with("pg_")
+                        || schema_name == INFORMATION_SCHEMA
+                    {
                         return Err(Error::new(ErrorKind::UnacceptableSchemaName(schema_name)));
                     }
                     let (database_id, database_name) = match database_name {
@@ -1153,10 +1251,22 @@ impl Catalog {
                             )));
                         }
                     }
+                    let mut actions = vec![];
+                    for column in self.comments.get(&id).into_iter().flat_map(|c| c.keys()) {
+                        if !entry.item().is_temporary() {
+                            tx.set_comment(id, *column, None)?;
+                        }
+                        actions.push(Action::SetComment {
+                            id,
+                            column: *column,
+                            comment: None,
+                        });
+                    }
                     if !entry.item().is_temporary() {
                         tx.remove_item(id)?;
                     }
-                    vec![Action::DropItem(id)]
+                    actions.push(Action::DropItem(id));
+                    actions
                 }
                 Op::RenameItem { id, to_name } => {
                     let mut actions = Vec::new();
@@ -1214,10 +1324,84 @@ impl Catalog {
                     });
                     actions
                 }
+                Op::UpdateItem { id, item } => {
+                    let entry = self.get_by_id(&id);
+                    if !item.is_temporary() {
+                        let serialized_item = self.serialize_item(&item);
+                        tx.update_item(id, &entry.name.item, &serialized_item)?;
+                    }
+
+                    // Comments on columns follow the columns to their new
+                    // positions, and are removed along with dropped columns.
+                    let mut removed = vec![];
+                    let mut added = vec![];
+                    if let (Some(comments), Ok(old_desc), Ok(new_desc)) =
+                        (self.comments.get(&id), entry.desc(), item.desc(&entry.name))
+                    {
+                        for (column, comment) in comments {
+                            let old_column = match column {
+                                Some(column) => *column,
+                                None => continue,
+                            };
+                            let new_column = old_desc
+                                .get_name(old_column)
+                                .and_then(|name| new_desc.get_by_name(name))
+                                .map(|(i, _)| i);
+                            if new_column != Some(old_column) {
+                                removed.push(old_column);
+                                if let Some(new_column) = new_column {
+                                    added.push((new_column, comment.clone()));
+                                }
+                            }
+                        }
+                    }
+                    let mut actions = vec![Action::UpdateItem {
+                        id,
+                        from_name: None,
+                        to_name: entry.name.clone(),
+                        item,
+                    }];
+                    for column in removed {
+                        if !entry.item().is_temporary() {
+                            tx.set_comment(id, Some(column), None)?;
+                        }
+                        actions.push(Action::SetComment {
+                            id,
+                            column: Some(column),
+                            comment: None,
+                        });
+                    }
+                    for (column, comment) in added {
+                        if !entry.item().is_temporary() {
+                            tx.set_comment(id, Some(column), Some(&comment))?;
+                        }
+                        actions.push(Action::SetComment {
+                            id,
+                            column: Some(column),
+                            comment: Some(comment),
+                        });
+                    }
+                    actions
+                }
+                Op::SetComment {
+                    id,
+                    column,
+                    comment,
+                } => {
+                    if !self.get_by_id(&id).item().is_temporary() {
+                        tx.set_comment(id, column, comment.as_deref())?;
+                    }
+                    vec![Action::SetComment {
+                        id,
+                        column,
+                        comment,
+                    }]
+                }
             });
         }
         tx.commit()?;
         drop(storage); // release immutable borrow on `self` so we can borrow mutably below
+        self.transient_revision += 1;
 
         Ok(actions
             .into_iter()
@@ -1360,7 +1544,19 @@ impl Catalog {
                         entry.name,
                         id
                     );
-                    assert_eq!(entry.uses(), item.uses());
+                    // Only a replaced view can change what it depends upon.
+                    for u in entry.uses() {
+                        if let Some(dep_metadata) = self.by_id.get_mut(&u) {
+                            dep_metadata.used_by.retain(|u| *u != id)
+                        }
+                    }
+                    for u in item.uses() {
+                        self.by_id
+                            .get_mut(&u)
+                            .expect("catalog out of sync")
+                            .used_by
+                            .push(id);
+                    }
                     let conn_id = entry.item().conn_id().unwrap_or(SYSTEM_CONN_ID);
                     let schema = &mut self
                         .get_schema_mut(&entry.name.database, &entry.name.schema, conn_id)
@@ -1368,10 +1564,10 @@ impl Catalog {
                     let schema_id = schema.id;
                     schema.items.remove(&entry.name.item);
                     entry.name = to_name.clone();
-                    entry.item = item.clone();
                     schema.items.insert(entry.name.item.clone(), id);
                     let oid = entry.oid();
                     self.by_id.insert(id, entry);
+                    self.swap_item(id, item.clone());
 
                     match from_name {
                         Some(from_name) => Event::UpdatedItem {
@@ -1385,6 +1581,31 @@ impl Catalog {
                         None => Event::NoOp, // If name didn't change, don't update system tables.
                     }
                 }
+
+                Action::SetComment {
+                    id,
+                    column,
+                    comment,
+                } => {
+                    let comments = self.comments.entry(id).or_default();
+                    let from = match &comment {
+                        Some(comment) => comments.insert(column, comment.clone()),
+                        None => comments.remove(&column),
+                    };
+                    if comments.is_empty() {
+                        self.comments.remove(&id);
+                    }
+                    if from.is_none() && comment.is_none() {
+                        Event::NoOp
+                    } else {
+                        Event::UpdatedComment {
+                            id,
+                            column,
+                            from,
+                            to: comment,
+                        }
+                    }
+                }
             })
             .collect())
     }
@@ -1415,6 +1636,10 @@ impl Catalog {
                 create_sql: typ.create_sql.clone(),
                 eval_env: Some(typ.plan_cx.clone().into()),
             },
+            CatalogItem::Func(func) => SerializedCatalogItem::V1 {
+                create_sql: func.create_sql.clone(),
+                eval_env: Some(func.plan_cx.clone().into()),
+            },
         };
         serde_json::to_vec(&item).expect("catalog serialization cannot fail")
     }
@@ -1446,6 +1671,7 @@ impl Catalog {
                 create_sql: table.create_sql,
                 plan_cx: pcx,
                 desc: table.desc,
+                depends_on: table.depends_on,
             }),
             Plan::CreateSource { source, .. } => CatalogItem::Source(Source {
                 create_sql: source.create_sql,
@@ -1454,8 +1680,12 @@ impl Catalog {
                 desc: source.desc,
             }),
             Plan::CreateView { view, .. } => {
-                let mut optimizer = Optimizer::default();
-                let optimized_expr = optimizer.optimize(view.expr, self.indexes())?;
+                let optimized_expr = prep_relation_expr(
+                    &mut Optimizer::default(),
+                    self.indexes(),
+                    view.expr,
+                    ExprPrepStyle::Static,
+                )?;
                 let desc = RelationDesc::new(optimized_expr.as_ref().typ(), view.column_names);
                 CatalogItem::View(View {
                     create_sql: view.create_sql,
@@ -1463,6 +1693,7 @@ impl Catalog {
                     optimized_expr,
                     desc,
                     conn_id: None,
+                    depends_on: view.depends_on,
                 })
             }
             Plan::CreateIndex { index, .. } => CatalogItem::Index(Index {
@@ -1479,7 +1710,18 @@ impl Catalog {
             } => CatalogItem::Sink(Sink {
                 create_sql: sink.create_sql,
                 plan_cx: pcx,
-                from: sink.from,
+                from: match sink.from {
+                    sql::plan::SinkFrom::Id(id) => SinkFrom::Id(id),
+                    sql::plan::SinkFrom::Query { expr, desc } => SinkFrom::Query {
+                        optimized_expr: prep_relation_expr(
+                            &mut Optimizer::default(),
+                            self.indexes(),
+                            expr,
+                            ExprPrepStyle::Static,
+                        )?,
+                        desc,
+                    },
+                },
                 connector: SinkConnectorState::Pending(sink.connector_builder),
                 with_snapshot,
                 as_of,
@@ -1491,12 +1733,151 @@ impl Catalog {
                     sql::plan::TypeInner::Map { key_id, value_id } => {
                         TypeInner::Map { key_id, value_id }
                     }
+                    sql::plan::TypeInner::Enum { labels } => TypeInner::Enum { labels },
+                    sql::plan::TypeInner::Record { fields, depends_on } => {
+                        TypeInner::Record { fields, depends_on }
+                    }
                 },
             }),
+            Plan::CreateFunction { function, .. } => CatalogItem::Func(Func {
+                create_sql: function.create_sql,
+                plan_cx: pcx,
+                params: function.params,
+                returns: function.returns,
+                depends_on: function.depends_on,
+            }),
             _ => bail!("catalog entry generated inappropriate plan"),
         })
     }
 
+    /// Plans the redefinition of the items in `items`, and of every item that
+    /// transitively depends upon them.
+    ///
+    /// Dependent items are replanned from their `create_sql` against the new
+    /// definitions. If any dependent can no longer be planned, e.g. because
+    /// it refers to a column that no longer exists, or if its columns would
+    /// change, an error is returned. The
+    /// catalog is left unchanged either way.
+    ///
+    /// Returns the new definitions of all affected items, in an order in which
+    /// they can be applied with [`Op::UpdateItem`].
+    pub fn plan_item_update(
+        &mut self,
+        items: Vec<(GlobalId, CatalogItem)>,
+    ) -> Result<Vec<(GlobalId, CatalogItem)>, Error> {
+        let mut dependents = BTreeSet::new();
+        let mut queue: Vec<_> = items.iter().map(|(id, _)| *id).collect();
+        while let Some(id) = queue.pop() {
+            for dependent in self.get_by_id(&id).used_by() {
+                if dependents.insert(*dependent) {
+                    queue.push(*dependent);
+                }
+            }
+        }
+        for (id, _) in &items {
+            dependents.remove(id);
+        }
+
+        // Swap in each new definition as it is planned, so that the items
+        // that depend upon it are planned against it. Items are only ever
+        // used by items created after them, so planning dependents in ID
+        // order plans each item after everything that it uses.
+        let mut updates = vec![];
+        let mut old_items = vec![];
+        for (id, item) in items {
+            old_items.push((id, self.swap_item(id, item.clone())));
+            updates.push((id, item));
+        }
+        let mut res = Ok(());
+        for id in dependents {
+            match self.plan_dependent_update(id) {
+                Ok(item) => {
+                    old_items.push((id, self.swap_item(id, item.clone())));
+                    updates.push((id, item));
+                }
+                Err(message) => {
+                    let dependee = updates[0].0;
+                    res = Err(Error::new(ErrorKind::DependentUpdate {
+                        depender: self.get_by_id(&id).name.to_string(),
+                        dependee: self.get_by_id(&dependee).name.to_string(),
+                        message,
+                    }));
+                    break;
+                }
+            }
+        }
+        for (id, item) in old_items.into_iter().rev() {
+            self.swap_item(id, item);
+        }
+        res.map(|()| updates)
+    }
+
+    /// Replans the item `id` from its `create_sql`, for `plan_item_update`.
+    fn plan_dependent_update(&self, id: GlobalId) -> Result<CatalogItem, String> {
+        let entry = self.get_by_id(&id);
+        let (create_sql, pcx) = match entry.item() {
+            CatalogItem::View(View {
+                create_sql,
+                plan_cx,
+                ..
+            }) => (create_sql, plan_cx),
+            CatalogItem::Index(Index {
+                create_sql,
+                plan_cx,
+                ..
+            }) => (create_sql, plan_cx),
+            CatalogItem::Func(Func {
+                create_sql,
+                plan_cx,
+                ..
+            }) => (create_sql, plan_cx),
+            CatalogItem::Sink(_) => return Err("sinks cannot be redefined".into()),
+            CatalogItem::Table(_) | CatalogItem::Source(_) | CatalogItem::Type(_) => {
+                unreachable!("tables, sources, and types depend only upon immutable types")
+            }
+        };
+        let mut item = self
+            .parse_item(create_sql.clone(), pcx.clone())
+            .map_err(|e| e.to_string())?;
+        if let CatalogItem::View(view) = &mut item {
+            view.conn_id = entry.item().conn_id();
+        }
+        if item.uses() != entry.uses() {
+            return Err("its dependencies would change".into());
+        }
+        // The items that depend upon this one were planned against its
+        // columns, so they must not change, e.g. by a `SELECT *` picking up a
+        // column that was added.
+        if let (Ok(old_desc), Ok(new_desc)) = (entry.desc(), item.desc(&entry.name)) {
+            let changed = old_desc.arity() != new_desc.arity()
+                || old_desc.iter().zip(new_desc.iter()).any(
+                    |((old_name, old_typ), (new_name, new_typ))| {
+                        old_name != new_name
+                            || old_typ.scalar_type != new_typ.scalar_type
+                            || (new_typ.nullable && !old_typ.nullable)
+                    },
+                );
+            if changed {
+                return Err("its columns would change".into());
+            }
+        }
+        Ok(item)
+    }
+
+    /// Replaces the in-memory definition of the item `id`, returning the old
+    /// definition.
+    fn swap_item(&mut self, id: GlobalId, item: CatalogItem) -> CatalogItem {
+        if let CatalogItem::Index(index) = &item {
+            for (index_id, keys) in self.indexes.get_mut(&index.on).unwrap() {
+                if *index_id == id {
+                    *keys = index.keys.clone();
+                }
+            }
+        }
+        let entry = self.by_id.get_mut(&id).expect("catalog out of sync");
+        mem::replace(&mut entry.item, item)
+    }
+
     /// Returns a mapping that indicates all indices that are available for
     /// each item in the catalog.
     pub fn indexes(&self) -> &HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>> {
@@ -1527,6 +1908,13 @@ impl Catalog {
             indexes: &mut Vec<GlobalId>,
             complete: &mut bool,
         ) {
+            // Types and functions hold no data, so the views that refer to
+            // them need no index on them. The relations that a function's body
+            // uses are inlined into the views that call the function.
+            if let CatalogItem::Type(_) | CatalogItem::Func(_) = catalog.get_by_id(&id).item() {
+                return;
+            }
+
             // If an index exists for `id`, record it in the output set and stop
             // searching.
             if let Some((index_id, _)) = catalog.indexes[&id].first() {
@@ -1549,8 +1937,14 @@ impl Catalog {
                 CatalogItem::Table(_) => {
                     unreachable!("tables always have at least one index");
                 }
-                CatalogItem::Sink(_) | CatalogItem::Index(_) | CatalogItem::Type(_) => {
-                    unreachable!("sinks, indexes, and user-defined types cannot be depended upon");
+                CatalogItem::Sink(_)
+                | CatalogItem::Index(_)
+                | CatalogItem::Type(_)
+                | CatalogItem::Func(_) => {
+                    unreachable!(
+                        "sinks and indexes cannot be depended upon, and types and functions are \
+                         skipped above"
+                    );
                 }
             }
         }
@@ -1565,21 +1959,209 @@ impl Catalog {
         (indexes, complete)
     }
 
+    /// Finds the nearest indexes that can satisfy any relation in the schemas
+    /// that contain the items whose identifiers are listed in `ids`.
+    ///
+    /// This is the set of indexes that a transaction that has read `ids` might
+    /// go on to read. Relations that transitively depend on an unmaterialized
+    /// source contribute whichever indexes they can.
+    pub fn schema_indexes(&self, ids: &[GlobalId], conn_id: u32) -> Vec<GlobalId> {
+        let mut schemas = vec![];
+        for id in ids {
+            let name = self.get_by_id(id).name();
+            if let Ok(schema) = self.get_schema(&name.database, &name.schema, conn_id) {
+                if !schemas.iter().any(|s| std::ptr::eq(*s, schema)) {
+                    schemas.push(schema);
+                }
+            }
+        }
+        let relations = schemas
+            .into_iter()
+            .flat_map(|schema| schema.items.values())
+            .filter(|id| match self.get_by_id(id).item() {
+                CatalogItem::Table(_) | CatalogItem::Source(_) | CatalogItem::View(_) => true,
+                CatalogItem::Sink(_)
+                | CatalogItem::Index(_)
+                | CatalogItem::Type(_)
+                | CatalogItem::Func(_) => false,
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        self.nearest_indexes(&relations).0
+    }
+
     pub fn uses_tables(&self, id: GlobalId) -> bool {
         match self.get_by_id(&id).item() {
             CatalogItem::Table(_) => true,
-            CatalogItem::Source(_) => false,
+            // The relations that a function's body uses are inlined into the
+            // views that call the function, and so are checked there.
+            CatalogItem::Source(_) | CatalogItem::Type(_) | CatalogItem::Func(_) => false,
             item @ CatalogItem::View(_) => item.uses().into_iter().any(|id| self.uses_tables(id)),
-            CatalogItem::Sink(_) | CatalogItem::Index(_) | CatalogItem::Type(_) => {
-                unreachable!("sinks, indexes, and user-defined types cannot be depended upon");
+            CatalogItem::Sink(_) | CatalogItem::Index(_) => {
+                unreachable!("sinks and indexes cannot be depended upon");
+            }
+        }
+    }
+
+    /// Returns an input upstream of the item identified by `id` whose
+    /// timestamps are not read from a consistency topic, and so would not be
+    /// the same if the input were read again, if there is any such input.
+    pub fn inconsistent_input(&self, id: GlobalId) -> Option<GlobalId> {
+        match self.get_by_id(&id).item() {
+            CatalogItem::Source(Source {
+                connector:
+                    SourceConnector::External {
+                        consistency: Consistency::BringYourOwn(_),
+                        ..
+                    },
+                ..
+            }) => None,
+            CatalogItem::Source(_) | CatalogItem::Table(_) => Some(id),
+            CatalogItem::Type(_) | CatalogItem::Func(_) => None,
+            item @ CatalogItem::View(_) => item
+                .uses()
+                .into_iter()
+                .find_map(|id| self.inconsistent_input(id)),
+            CatalogItem::Sink(_) | CatalogItem::Index(_) => {
+                unreachable!("sinks and indexes cannot be depended upon");
             }
         }
     }
 
+    /// Returns the ID of a sink that writes to the topic that a Kafka sink
+    /// with the `reuse_topic` option, built by `builder`, would write to.
+    pub fn kafka_sink_writing_to(&self, builder: &KafkaSinkConnectorBuilder) -> Option<GlobalId> {
+        self.by_id.values().find_map(|entry| match entry.item() {
+            CatalogItem::Sink(Sink {
+                connector: SinkConnectorState::Pending(SinkConnectorBuilder::Kafka(other)),
+                ..
+            }) if other.reuse_topic
+                && other.broker_addrs == builder.broker_addrs
+                && other.topic_prefix == builder.topic_prefix =>
+            {
+                Some(entry.id)
+            }
+            CatalogItem::Sink(Sink {
+                connector: SinkConnectorState::Ready(SinkConnector::Kafka(other)),
+                ..
+            }) if other.addrs == builder.broker_addrs && other.topic == builder.topic_prefix => {
+                Some(entry.id)
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the sets of columns that are known to uniquely identify the
+    /// rows of the relation that a sink reads from.
+    pub fn sink_from_keys(&self, from: &SinkFrom) -> Vec<Vec<usize>> {
+        match from {
+            SinkFrom::Id(id) => match self.get_by_id(id).item() {
+                CatalogItem::View(view) => view.optimized_expr.as_ref().typ().keys,
+                CatalogItem::Source(source) => source.desc.typ().keys.clone(),
+                CatalogItem::Table(table) => table.desc.typ().keys.clone(),
+                CatalogItem::Sink(_)
+                | CatalogItem::Index(_)
+                | CatalogItem::Type(_)
+                | CatalogItem::Func(_) => unreachable!("sinks read from relations"),
+            },
+            SinkFrom::Query { optimized_expr, .. } => optimized_expr.as_ref().typ().keys,
+        }
+    }
+
     pub fn dump(&self) -> String {
         serde_json::to_string(&self.by_name).expect("serialization cannot fail")
     }
 
+    /// Returns the DDL statements that recreate the user-defined databases,
+    /// schemas, and items in the catalog, in an order in which they can be
+    /// replayed.
+    ///
+    /// Items are ordered by ID, since an item can only depend upon items that
+    /// were created before it. Temporary items, and the default indexes that
+    /// `CREATE TABLE` creates implicitly, are omitted.
+    pub fn dump_ddl(&self) -> Vec<String> {
+        let mut stmts = vec![];
+        for database_name in self.by_name.keys() {
+            stmts.push(Statement::CreateDatabase(CreateDatabaseStatement {
+                name: Ident::new(database_name),
+                if_not_exists: true,
+            }));
+        }
+        for (database_name, database) in &self.by_name {
+            for schema_name in database.schemas.keys() {
+                stmts.push(Statement::CreateSchema(CreateSchemaStatement {
+                    name: ObjectName(vec![Ident::new(database_name), Ident::new(schema_name)]),
+                    if_not_exists: true,
+                }));
+            }
+        }
+        let mut ddl: Vec<_> = stmts
+            .into_iter()
+            .map(|stmt| stmt.to_ast_string_stable())
+            .collect();
+        for (id, entry) in &self.by_id {
+            if id.is_system() || entry.item().is_temporary() {
+                continue;
+            }
+            if let CatalogItem::Index(index) = entry.item() {
+                if self.get_by_id(&index.on).is_table()
+                    && self.default_index_for(index.on) == Some(*id)
+                {
+                    continue;
+                }
+            }
+            ddl.push(sql::catalog::CatalogItem::create_sql(entry).into());
+        }
+        // Comments are emitted after all items, as the default indexes of
+        // tables are not emitted themselves but may still carry comments.
+        for (id, comments) in self
+            .by_id
+            .keys()
+            .filter_map(|id| Some((id, self.comments.get(id)?)))
+        {
+            let entry = self.get_by_id(id);
+            if id.is_system() || entry.item().is_temporary() {
+                continue;
+            }
+            let name = sql::normalize::unresolve(entry.name().clone());
+            for (column, comment) in comments {
+                let object = match column {
+                    Some(column) => {
+                        let column_name = entry
+                            .desc()
+                            .ok()
+                            .and_then(|desc| desc.get_name(*column))
+                            .expect("comments are only set on named columns");
+                        let mut name = name.clone();
+                        name.0.push(Ident::new(column_name.as_str()));
+                        CommentObject::Column { name }
+                    }
+                    None => CommentObject::Item {
+                        object_type: match entry.item() {
+                            CatalogItem::Table(_) => ObjectType::Table,
+                            CatalogItem::Source(_) => ObjectType::Source,
+                            CatalogItem::View(_) => ObjectType::View,
+                            CatalogItem::Sink(_) => ObjectType::Sink,
+                            CatalogItem::Index(_) => ObjectType::Index,
+                            CatalogItem::Type(_) => ObjectType::Type,
+                            CatalogItem::Func(_) => ObjectType::Function,
+                        },
+                        name: name.clone(),
+                    },
+                };
+                let stmt = Statement::Comment(CommentStatement {
+                    object,
+                    comment: Some(comment.clone()),
+                });
+                ddl.push(stmt.to_ast_string_stable());
+            }
+        }
+        for stmt in &mut ddl {
+            stmt.push(';');
+        }
+        ddl
+    }
+
     pub fn persistence_directory(&self) -> Option<&Path> {
         self.persistence_directory.as_deref()
     }
@@ -1587,6 +2169,14 @@ impl Catalog {
     pub fn cluster_id(&self) -> Uuid {
         self.cluster_id
     }
+
+    /// Returns a number that changes whenever the catalog changes.
+    ///
+    /// The revision is not persisted, and so is only meaningful within the
+    /// lifetime of this process.
+    pub fn transient_revision(&self) -> u64 {
+        self.transient_revision
+    }
 }
 
 impl IdHumanizer for Catalog {
@@ -1630,6 +2220,20 @@ pub enum Op {
         id: GlobalId,
         to_name: String,
     },
+    /// Replaces the definition of the identified item, keeping its name and
+    /// ID. The new definitions of altered items and their dependents should
+    /// come from the output of `plan_item_update`.
+    UpdateItem {
+        id: GlobalId,
+        item: CatalogItem,
+    },
+    /// Sets or, if `comment` is `None`, removes the comment on the identified
+    /// item, or on one of its columns if `column` is specified.
+    SetComment {
+        id: GlobalId,
+        column: Option<usize>,
+        comment: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -1679,6 +2283,12 @@ pub enum Event {
         to_name: FullName,
         item: CatalogItem,
     },
+    UpdatedComment {
+        id: GlobalId,
+        column: Option<usize>,
+        from: Option<String>,
+        to: Option<String>,
+    },
     NoOp,
 }
 
@@ -1856,6 +2466,7 @@ impl sql::catalog::CatalogItem for CatalogEntry {
             CatalogItem::View(View { create_sql, .. }) => create_sql,
             CatalogItem::Index(Index { create_sql, .. }) => create_sql,
             CatalogItem::Type(Type { create_sql, .. }) => create_sql,
+            CatalogItem::Func(Func { create_sql, .. }) => create_sql,
         }
     }
 
@@ -1867,6 +2478,7 @@ impl sql::catalog::CatalogItem for CatalogEntry {
             CatalogItem::View(View { plan_cx, .. }) => plan_cx,
             CatalogItem::Index(Index { plan_cx, .. }) => plan_cx,
             CatalogItem::Type(Type { plan_cx, .. }) => plan_cx,
+            CatalogItem::Func(Func { plan_cx, .. }) => plan_cx,
         }
     }
 
@@ -1878,6 +2490,7 @@ impl sql::catalog::CatalogItem for CatalogEntry {
             CatalogItem::View(_) => sql::catalog::CatalogItemType::View,
             CatalogItem::Index(_) => sql::catalog::CatalogItemType::Index,
             CatalogItem::Type(_) => sql::catalog::CatalogItemType::Type,
+            CatalogItem::Func(_) => sql::catalog::CatalogItemType::Func,
         }
     }
 
@@ -1896,6 +2509,37 @@ impl sql::catalog::CatalogItem for CatalogEntry {
     fn used_by(&self) -> &[GlobalId] {
         self.used_by()
     }
+
+    fn scalar_type(&self) -> Option<ScalarType> {
+        match self.item() {
+            CatalogItem::Type(Type {
+                inner: TypeInner::Enum { labels },
+                ..
+            }) => Some(ScalarType::Enum {
+                oid: self.oid,
+                name: self.name.item.clone(),
+                labels: labels.clone(),
+            }),
+            CatalogItem::Type(Type {
+                inner: TypeInner::Record { fields, .. },
+                ..
+            }) => Some(ScalarType::Record {
+                fields: fields.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn function_details(&self) -> Option<(&[(Option<ColumnName>, ScalarType)], &FunctionReturns)> {
+        if let CatalogItem::Func(Func {
+            params, returns, ..
+        }) = self.item()
+        {
+            Some((params, returns))
+        } else {
+            None
+        }
+    }
 }
 
 impl sql::catalog::Type for Type {}
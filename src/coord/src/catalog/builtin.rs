@@ -33,6 +33,7 @@ use repr::{RelationDesc, ScalarType};
 pub const MZ_TEMP_SCHEMA: &str = "mz_temp";
 pub const MZ_CATALOG_SCHEMA: &str = "mz_catalog";
 pub const PG_CATALOG_SCHEMA: &str = "pg_catalog";
+pub const INFORMATION_SCHEMA: &str = "information_schema";
 
 pub enum Builtin {
     Log(&'static BuiltinLog),
@@ -412,6 +413,53 @@ lazy_static! {
         id: GlobalId::System(2031),
         index_id: GlobalId::System(2032),
     };
+    pub static ref MZ_COMMENTS: BuiltinTable = BuiltinTable {
+        name: "mz_comments",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::String.nullable(false))
+            .with_column("position", ScalarType::Int64.nullable(true))
+            .with_column("comment", ScalarType::String.nullable(false)),
+        id: GlobalId::System(2033),
+        index_id: GlobalId::System(2034),
+    };
+    pub static ref MZ_ENUM_TYPES: BuiltinTable = BuiltinTable {
+        name: "mz_enum_types",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("type_id", ScalarType::String.nullable(false)),
+        id: GlobalId::System(2035),
+        index_id: GlobalId::System(2036),
+    };
+    pub static ref MZ_ENUM_LABELS: BuiltinTable = BuiltinTable {
+        name: "mz_enum_labels",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("type_id", ScalarType::String.nullable(false))
+            .with_column("position", ScalarType::Int64.nullable(false))
+            .with_column("label", ScalarType::String.nullable(false)),
+        id: GlobalId::System(2037),
+        index_id: GlobalId::System(2038),
+    };
+    pub static ref MZ_COMPOSITE_TYPES: BuiltinTable = BuiltinTable {
+        name: "mz_composite_types",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("type_id", ScalarType::String.nullable(false)),
+        id: GlobalId::System(2039),
+        index_id: GlobalId::System(2040),
+    };
+    pub static ref MZ_FUNCTIONS: BuiltinTable = BuiltinTable {
+        name: "mz_functions",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::String.nullable(false))
+            .with_column("oid", ScalarType::Oid.nullable(false))
+            .with_column("schema_id", ScalarType::Int64.nullable(false))
+            .with_column("name", ScalarType::String.nullable(false)),
+        id: GlobalId::System(2041),
+        index_id: GlobalId::System(2042),
+    };
 }
 
 pub const MZ_RELATIONS: BuiltinView = BuiltinView {
@@ -737,11 +785,13 @@ pub const PG_DESCRIPTION: BuiltinView = BuiltinView {
     name: "pg_description",
     schema: PG_CATALOG_SCHEMA,
     sql: "CREATE VIEW pg_description AS SELECT
-    oid as objoid,
-    NULL::oid as classoid,
-    0::int4 as objsubid,
-    NULL::text as description
-FROM pg_catalog.pg_class",
+    mz_objects.oid as objoid,
+    -- Every object that can be commented on is described by pg_class.
+    1259::oid as classoid,
+    coalesce(mz_comments.position, 0)::int4 as objsubid,
+    mz_comments.comment as description
+FROM mz_catalog.mz_comments
+JOIN mz_catalog.mz_objects ON mz_comments.id = mz_objects.id",
     id: GlobalId::System(3019),
     needs_logs: false,
 };
@@ -771,6 +821,8 @@ pub const PG_TYPE: BuiltinView = BuiltinView {
     CASE
         WHEN EXISTS (SELECT 1 FROM mz_catalog.mz_base_types WHERE type_id = mz_types.id) THEN 'b'
         WHEN EXISTS (SELECT 1 FROM mz_catalog.mz_map_types WHERE type_id = mz_types.id) THEN 'm'
+        WHEN EXISTS (SELECT 1 FROM mz_catalog.mz_enum_types WHERE type_id = mz_types.id) THEN 'e'
+        WHEN EXISTS (SELECT 1 FROM mz_catalog.mz_composite_types WHERE type_id = mz_types.id) THEN 'c'
     END AS typtype,
     0::oid AS typrelid,
     NULL::oid AS typelem,
@@ -787,9 +839,9 @@ pub const PG_PROC: BuiltinView = BuiltinView {
     name: "pg_proc",
     schema: PG_CATALOG_SCHEMA,
     sql: "CREATE VIEW pg_proc AS SELECT
-    NULL::oid AS oid,
-    NULL::text AS proname
-    WHERE false",
+    oid,
+    name AS proname
+FROM mz_catalog.mz_functions",
     id: GlobalId::System(3022),
     needs_logs: false,
 };
@@ -810,14 +862,86 @@ pub const PG_ENUM: BuiltinView = BuiltinView {
     schema: PG_CATALOG_SCHEMA,
     sql: "CREATE VIEW pg_enum AS SELECT
     NULL::oid AS oid,
-    NULL::oid AS enumtypid,
-    NULL::float AS enumsortorder,
-    NULL::text AS enumlabel
-    WHERE false",
+    mz_types.oid AS enumtypid,
+    mz_enum_labels.position::float AS enumsortorder,
+    mz_enum_labels.label AS enumlabel
+FROM mz_catalog.mz_enum_labels
+JOIN mz_catalog.mz_types ON mz_types.id = mz_enum_labels.type_id",
     id: GlobalId::System(3024),
     needs_logs: false,
 };
 
+pub const INFORMATION_SCHEMA_SCHEMATA: BuiltinView = BuiltinView {
+    name: "schemata",
+    schema: INFORMATION_SCHEMA,
+    sql: "CREATE VIEW schemata AS SELECT
+    mz_databases.name AS catalog_name,
+    mz_schemas.name AS schema_name
+FROM mz_catalog.mz_schemas
+LEFT JOIN mz_catalog.mz_databases ON mz_schemas.database_id = mz_databases.id",
+    id: GlobalId::System(3025),
+    needs_logs: false,
+};
+
+pub const INFORMATION_SCHEMA_TABLES: BuiltinView = BuiltinView {
+    name: "tables",
+    schema: INFORMATION_SCHEMA,
+    sql: "CREATE VIEW tables AS SELECT
+    mz_databases.name AS table_catalog,
+    mz_schemas.name AS table_schema,
+    mz_relations.name AS table_name,
+    CASE
+        WHEN mz_relations.type = 'table' THEN 'BASE TABLE'
+        WHEN mz_relations.type = 'source' THEN 'SOURCE'
+        WHEN mz_relations.type = 'view' THEN 'VIEW'
+    END AS table_type
+FROM mz_catalog.mz_relations
+JOIN mz_catalog.mz_schemas ON mz_relations.schema_id = mz_schemas.id
+LEFT JOIN mz_catalog.mz_databases ON mz_schemas.database_id = mz_databases.id",
+    id: GlobalId::System(3026),
+    needs_logs: false,
+};
+
+pub const INFORMATION_SCHEMA_COLUMNS: BuiltinView = BuiltinView {
+    name: "columns",
+    schema: INFORMATION_SCHEMA,
+    sql: "CREATE VIEW columns AS SELECT
+    mz_databases.name AS table_catalog,
+    mz_schemas.name AS table_schema,
+    mz_relations.name AS table_name,
+    mz_columns.name AS column_name,
+    mz_columns.position AS ordinal_position,
+    NULL::text AS column_default,
+    CASE WHEN mz_columns.nullable THEN 'YES' ELSE 'NO' END AS is_nullable,
+    mz_columns.type AS data_type,
+    NULL::int4 AS character_maximum_length,
+    NULL::int4 AS numeric_precision,
+    NULL::int4 AS numeric_scale
+FROM mz_catalog.mz_columns
+JOIN mz_catalog.mz_relations ON mz_columns.id = mz_relations.id
+JOIN mz_catalog.mz_schemas ON mz_relations.schema_id = mz_schemas.id
+LEFT JOIN mz_catalog.mz_databases ON mz_schemas.database_id = mz_databases.id",
+    id: GlobalId::System(3027),
+    needs_logs: false,
+};
+
+// The definitions of views are not exposed in `mz_catalog`, so
+// `view_definition` is always NULL.
+pub const INFORMATION_SCHEMA_VIEWS: BuiltinView = BuiltinView {
+    name: "views",
+    schema: INFORMATION_SCHEMA,
+    sql: "CREATE VIEW views AS SELECT
+    mz_databases.name AS table_catalog,
+    mz_schemas.name AS table_schema,
+    mz_views.name AS table_name,
+    NULL::text AS view_definition
+FROM mz_catalog.mz_views
+JOIN mz_catalog.mz_schemas ON mz_views.schema_id = mz_schemas.id
+LEFT JOIN mz_catalog.mz_databases ON mz_schemas.database_id = mz_databases.id",
+    id: GlobalId::System(3028),
+    needs_logs: false,
+};
+
 // The following types are the list of builtin data types available
 // in Materialize. This list is derived from the Type variants supported
 // in pgrepr.
@@ -1095,6 +1219,11 @@ lazy_static! {
             Builtin::Table(&MZ_TYPES),
             Builtin::Table(&MZ_BASE_TYPES),
             Builtin::Table(&MZ_MAP_TYPES),
+            Builtin::Table(&MZ_COMMENTS),
+            Builtin::Table(&MZ_ENUM_TYPES),
+            Builtin::Table(&MZ_ENUM_LABELS),
+            Builtin::Table(&MZ_COMPOSITE_TYPES),
+            Builtin::Table(&MZ_FUNCTIONS),
             Builtin::View(&MZ_RELATIONS),
             Builtin::View(&MZ_OBJECTS),
             Builtin::View(&MZ_CATALOG_NAMES),
@@ -1120,6 +1249,10 @@ lazy_static! {
             Builtin::View(&PG_PROC),
             Builtin::View(&PG_RANGE),
             Builtin::View(&PG_ENUM),
+            Builtin::View(&INFORMATION_SCHEMA_SCHEMATA),
+            Builtin::View(&INFORMATION_SCHEMA_TABLES),
+            Builtin::View(&INFORMATION_SCHEMA_COLUMNS),
+            Builtin::View(&INFORMATION_SCHEMA_VIEWS),
             Builtin::Type(&TYPE_BOOL),
             Builtin::Type(&TYPE_BOOL_ARRAY),
             Builtin::Type(&TYPE_BYTEA),
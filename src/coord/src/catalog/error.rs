@@ -47,6 +47,11 @@ pub(crate) enum ErrorKind {
         message: String,
     },
     TypeRename(String),
+    DependentUpdate {
+        depender: String,
+        dependee: String,
+        message: String,
+    },
     ExperimentalModeRequired,
     ExperimentalModeUnavailable,
 }
@@ -91,6 +96,7 @@ impl std::error::Error for Error {
             | ErrorKind::UnsatisfiableLoggingDependency { .. }
             | ErrorKind::AmbiguousRename { .. }
             | ErrorKind::TypeRename(_)
+            | ErrorKind::DependentUpdate { .. }
             | ErrorKind::ExperimentalModeRequired
             | ErrorKind::ExperimentalModeUnavailable => None,
             ErrorKind::Sql(e) => Some(e),
@@ -156,6 +162,15 @@ impl fmt::Display for Error {
                 }
             }
             ErrorKind::TypeRename(typ) => write!(f, "cannot rename type: {}", typ),
+            ErrorKind::DependentUpdate {
+                depender,
+                dependee,
+                message,
+            } => write!(
+                f,
+                "cannot alter {} because {} depends on it: {}",
+                dependee, depender, message
+            ),
             ErrorKind::ExperimentalModeRequired => write!(
                 f,
                 r#"Materialize previously started with --experimental to
@@ -90,6 +90,22 @@ const MIGRATIONS: &[&str] = &[
         name TEXT PRIMARY KEY,
         value TEXT
     );",
+    // Introduces comments table to support `COMMENT ON`.
+    //
+    // Introduced in v0.5.2.
+    //
+    // A position of zero refers to the item itself; any other position refers
+    // to the column of the item at that 1-based position.
+    "CREATE TABLE comments (
+        gid blob NOT NULL,
+        position integer NOT NULL,
+        comment text NOT NULL,
+        PRIMARY KEY (gid, position)
+    );",
+    // Introduces the ambient information_schema schema.
+    //
+    // Introduced in v0.5.2.
+    "INSERT INTO schemas (database_id, name) VALUES (NULL, 'information_schema');",
     // Add new migrations here.
     //
     // Migrations should be preceded with a comment of the following form:
@@ -307,6 +323,22 @@ impl Connection {
             .collect()
     }
 
+    pub fn load_comments(&self) -> Result<Vec<(GlobalId, Option<usize>, String)>, Error> {
+        self.inner
+            .prepare("SELECT gid, position, comment FROM comments")?
+            .query_and_then(params![], |row| -> Result<_, Error> {
+                let id: SqlVal<GlobalId> = row.get(0)?;
+                let position: i64 = row.get(1)?;
+                let comment: String = row.get(2)?;
+                let column = match position {
+                    0 => None,
+                    p => Some(usize::cast_from(p as u64) - 1),
+                };
+                Ok((id.0, column, comment))
+            })?
+            .collect()
+    }
+
     pub fn allocate_id(&mut self) -> Result<GlobalId, Error> {
         let tx = self.inner.transaction()?;
         // SQLite doesn't support u64s, so we constrain ourselves to the more
@@ -464,6 +496,28 @@ impl Transaction<'_> {
         }
     }
 
+    pub fn set_comment(
+        &self,
+        id: GlobalId,
+        column: Option<usize>,
+        comment: Option<&str>,
+    ) -> Result<(), Error> {
+        let position = column.map(|c| c as i64 + 1).unwrap_or(0);
+        match comment {
+            Some(comment) => self
+                .inner
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO comments (gid, position, comment) VALUES (?, ?, ?)",
+                )?
+                .execute(params![SqlVal(id), position, comment])?,
+            None => self
+                .inner
+                .prepare_cached("DELETE FROM comments WHERE gid = ? AND position = ?")?
+                .execute(params![SqlVal(id), position])?,
+        };
+        Ok(())
+    }
+
     pub fn commit(self) -> Result<(), rusqlite::Error> {
         self.inner.commit()
     }
@@ -127,6 +127,13 @@ impl SessionClient {
         .await
     }
 
+    /// Cancels the query, if any, that is currently running in this client
+    /// session.
+    pub async fn cancel(&mut self) {
+        let conn_id = self.session().conn_id();
+        self.inner.cancel_request(conn_id).await
+    }
+
     /// Terminates this client session.
     ///
     /// This both consumes this `SessionClient` and cleans up any state
@@ -9,12 +9,13 @@
 
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 
 use derivative::Derivative;
 
 use dataflow_types::PeekResponse;
 use repr::Row;
-use sql::ast::{ObjectType, Statement};
+use sql::ast::{FetchDirection, ObjectType, Statement};
 
 use crate::session::Session;
 
@@ -90,6 +91,10 @@ pub enum ExecuteResponse {
     AlteredObject(ObjectType),
     // The index was altered.
     AlteredIndexLogicalCompaction,
+    /// The requested cursor was closed.
+    ClosedCursor,
+    /// The comment on the requested object was changed.
+    Commented,
     /// The active transaction was committed.
     CommittedTransaction,
     CopyTo {
@@ -127,6 +132,10 @@ pub enum ExecuteResponse {
     },
     /// The requested type was created.
     CreatedType,
+    /// The requested function was created.
+    CreatedFunction,
+    /// The requested cursor was declared.
+    DeclaredCursor,
     /// The specified number of rows were deleted from the requested table.
     Deleted(usize),
     /// The temporary objects associated with the session have been discarded.
@@ -149,8 +158,17 @@ pub enum ExecuteResponse {
     DroppedSink,
     /// The requested type was dropped.
     DroppedType,
+    /// The requested function was dropped.
+    DroppedFunction,
     /// The provided query was empty.
     EmptyQuery,
+    /// Up to `count` rows should be fetched from the cursor named `name`,
+    /// waiting at most `timeout` for them to arrive.
+    Fetch {
+        name: String,
+        count: Option<FetchDirection>,
+        timeout: Option<Duration>,
+    },
     /// The specified number of rows were inserted into the requested table.
     Inserted(usize),
     /// Rows will be delivered via the specified future.
@@ -164,7 +182,7 @@ pub enum ExecuteResponse {
     /// Updates to the requested source or view will be streamed to the
     /// contained receiver.
     Tailing {
-        rx: comm::mpsc::Receiver<Vec<Row>>,
+        rx: comm::mpsc::Receiver<Result<Vec<Row>, String>>,
     },
     /// The specified number of rows were updated in the requested table.
     Updated(usize),
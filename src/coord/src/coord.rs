@@ -17,7 +17,7 @@
 //! must accumulate to the same value as would an un-compacted trace.
 
 use std::cmp;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::iter;
 use std::os::unix::ffi::OsStringExt;
@@ -25,11 +25,13 @@ use std::path::Path;
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, SystemTime};
 
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, Context};
 use differential_dataflow::lattice::Lattice;
 use futures::future::{self, TryFutureExt};
 use futures::sink::SinkExt;
 use futures::stream::{self, StreamExt, TryStreamExt};
+use lazy_static::lazy_static;
+use prometheus::{register_uint_counter, UIntCounter};
 use timely::progress::{Antichain, ChangeBatch, Timestamp as _};
 use tokio::runtime::Handle;
 use uuid::Uuid;
@@ -39,47 +41,65 @@ use dataflow::{PersistenceMessage, SequencedCommand, WorkerFeedback, WorkerFeedb
 use dataflow_types::logging::LoggingConfig as DataflowLoggingConfig;
 use dataflow_types::{
     AvroOcfSinkConnector, DataflowDesc, IndexDesc, KafkaSinkConnector, PeekResponse, SinkConnector,
-    SourceConnector, TailSinkConnector, TimestampSourceUpdate, Update,
+    SinkConnectorBuilder, SinkEnvelope, SourceConnector, TailSinkConnector, TimestampSourceUpdate,
+    Update,
 };
 use expr::{
-    GlobalId, Id, IdHumanizer, NullaryFunc, OptimizedRelationExpr, RelationExpr, RowSetFinishing,
-    ScalarExpr, SourceInstanceId,
+    GlobalId, Id, IdHumanizer, KeyRange, NullaryFunc, OptimizedRelationExpr, RelationExpr,
+    RowSetFinishing, ScalarExpr, SourceInstanceId,
 };
 use ore::collections::CollectionExt;
 use ore::thread::JoinHandleExt;
-use repr::{ColumnName, Datum, RelationDesc, RelationType, Row, RowPacker, Timestamp};
+use repr::{ColumnName, Datum, RelationDesc, RelationType, Row, RowArena, RowPacker, Timestamp};
 use sql::ast::display::AstDisplay;
 use sql::ast::{
     CreateIndexStatement, CreateTableStatement, DropObjectsStatement, ExplainOptions, ExplainStage,
-    ObjectType, Statement,
+    FetchStatement, ObjectType, Statement,
 };
 use sql::catalog::Catalog as _;
 use sql::names::{DatabaseSpecifier, FullName};
 use sql::plan::StatementDesc;
 use sql::plan::{
-    AlterIndexLogicalCompactionWindow, CopyFormat, LogicalCompactionWindow, MutationKind, Params,
-    PeekWhen, Plan, PlanContext,
+    AlterIndexLogicalCompactionWindow, AlterSource, AlterTable, CopyFormat,
+    LogicalCompactionWindow, MutationKind, Params, PeekWhen, Plan, PlanContext, TableMigration,
 };
+use transform::reduction::FoldConstants;
 use transform::Optimizer;
 
 use self::arrangement_state::{ArrangementFrontiers, Frontiers};
 use crate::catalog::builtin::{
-    BUILTINS, MZ_AVRO_OCF_SINKS, MZ_BASE_TYPES, MZ_COLUMNS, MZ_DATABASES, MZ_INDEXES,
-    MZ_INDEX_COLUMNS, MZ_KAFKA_SINKS, MZ_MAP_TYPES, MZ_SCHEMAS, MZ_SINKS, MZ_SOURCES, MZ_TABLES,
-    MZ_TYPES, MZ_VIEWS, MZ_VIEW_FOREIGN_KEYS, MZ_VIEW_KEYS,
+    BUILTINS, MZ_AVRO_OCF_SINKS, MZ_BASE_TYPES, MZ_COLUMNS, MZ_COMMENTS, MZ_COMPOSITE_TYPES,
+    MZ_DATABASES, MZ_ENUM_LABELS, MZ_ENUM_TYPES, MZ_FUNCTIONS, MZ_INDEXES, MZ_INDEX_COLUMNS,
+    MZ_KAFKA_SINKS, MZ_MAP_TYPES, MZ_SCHEMAS, MZ_SINKS, MZ_SOURCES, MZ_TABLES, MZ_TYPES, MZ_VIEWS,
+    MZ_VIEW_FOREIGN_KEYS, MZ_VIEW_KEYS,
 };
 use crate::catalog::{self, Catalog, CatalogItem, Index, SinkConnectorState, Type, TypeInner};
 use crate::command::{
     Command, ExecuteResponse, NoSessionExecuteResponse, Response, StartupMessage,
 };
 use crate::persistence::{PersistenceConfig, Persister};
-use crate::session::{PreparedStatement, Session};
+use crate::session::{
+    CachedPlan, IsolationLevel, PreparedStatement, Session, TransactionStatus, WriteData, WriteOp,
+};
 use crate::sink_connector;
 use crate::timestamp::{TimestampConfig, TimestampMessage, Timestamper};
 use crate::util::ClientTransmitter;
 
 mod arrangement_state;
 
+lazy_static! {
+    static ref PLAN_CACHE_HITS: UIntCounter = register_uint_counter!(
+        "mz_plan_cache_hits",
+        "number of peeks that reused the cached plan of their prepared statement"
+    )
+    .unwrap();
+    static ref PLAN_CACHE_MISSES: UIntCounter = register_uint_counter!(
+        "mz_plan_cache_misses",
+        "number of peeks of prepared statements that needed to be optimized"
+    )
+    .unwrap();
+}
+
 pub enum Message {
     Command(Command),
     Worker(WorkerFeedbackWithMeta),
@@ -92,6 +112,8 @@ pub enum Message {
         tx: ClientTransmitter<ExecuteResponse>,
         result: Result<sql::ast::Statement, anyhow::Error>,
         params: Params,
+        portal_name: String,
+        statement_name: Option<String>,
     },
     SinkConnectorReady {
         session: Session,
@@ -100,6 +122,17 @@ pub enum Message {
         oid: u32,
         result: Result<SinkConnector, anyhow::Error>,
     },
+    RestartSourceTimestamping {
+        id: GlobalId,
+        connector: SourceConnector,
+    },
+    AlterTableRowsReady {
+        session: Session,
+        tx: ClientTransmitter<ExecuteResponse>,
+        alter_table: AlterTable,
+        timestamp: Timestamp,
+        result: Result<Vec<Row>, anyhow::Error>,
+    },
     Shutdown,
 }
 
@@ -139,10 +172,23 @@ where
     /// Maps (global Id of arrangement) -> (frontier information)
     indexes: ArrangementFrontiers<Timestamp>,
     since_updates: Vec<(GlobalId, Antichain<Timestamp>)>,
-    /// For each connection running a TAIL command, the name of the dataflow
-    /// that is servicing the TAIL. A connection can only run one TAIL at a
-    /// time.
-    active_tails: HashMap<u32, GlobalId>,
+    /// For each connection running a TAIL command, the dataflows that are
+    /// servicing the TAIL, by the name of the portal that reads from it. A
+    /// connection can have several TAILs outstanding at once if it has
+    /// declared cursors over them.
+    active_tails: HashMap<u32, HashMap<String, ActiveTail>>,
+    /// For each connection, the transmitter that cancels a statement that is
+    /// waiting for results outside of the coordinator.
+    cancel_txs: HashMap<u32, futures::channel::mpsc::UnboundedSender<()>>,
+    /// For each connection in a transaction that has read data, the timestamp
+    /// at which the transaction reads and the indexes it has read from.
+    txn_reads: HashMap<u32, TxnReads>,
+    /// The number of times each table has been altered, which transactions
+    /// use to detect that a table they wrote to was altered before they
+    /// committed.
+    table_versions: HashMap<GlobalId, u64>,
+    /// The tables whose existing rows are being read in order to alter them.
+    altering_tables: HashSet<GlobalId>,
     timestamp_config: TimestampConfig,
     /// Delta from leading edge of an arrangement from which we allow compaction.
     logical_compaction_window_ms: Option<Timestamp>,
@@ -164,6 +210,24 @@ where
     transient_id_counter: u64,
 }
 
+/// A `TAIL` that is being read by a portal.
+struct ActiveTail {
+    /// The ID of the dataflow that is servicing the `TAIL`.
+    sink_id: GlobalId,
+    /// Whether the portal is a cursor, which is not closed when the statement
+    /// running on its connection is canceled.
+    cursor: bool,
+}
+
+/// The reads performed by an open transaction.
+struct TxnReads {
+    /// The timestamp at which all peeks in the transaction occur.
+    timestamp: Timestamp,
+    /// The indexes whose compaction is held back at `timestamp`, which are
+    /// the only indexes that the transaction can read.
+    index_ids: Vec<GlobalId>,
+}
+
 impl<C> Coordinator<C>
 where
     C: comm::Connection,
@@ -274,7 +338,7 @@ where
                     let connector = sink_connector::build(
                         builder.clone(),
                         sink.with_snapshot,
-                        self.determine_frontier(sink.as_of, sink.from)?,
+                        self.determine_frontier(sink.as_of, &sink.from)?,
                         *id,
                     )
                     .await
@@ -392,6 +456,8 @@ where
                         });
                         return;
                     }
+                    self.cancel_txs
+                        .insert(session.conn_id(), session.cancel_tx());
                     ClientTransmitter::new(tx).send(Ok(messages), session)
                 }
                 Message::Command(Command::Execute {
@@ -399,34 +465,25 @@ where
                     session,
                     tx,
                 }) => {
-                    let result = session
-                        .get_portal(&portal_name)
-                        .ok_or_else(|| anyhow::format_err!("portal does not exist {:?}", portal_name))
-                        .and_then(|portal| {
-                            session
-                                .get_prepared_statement(&portal.statement_name)
-                                .ok_or_else(|| anyhow::format_err!(
-                                    "statement for portal does not exist portal={:?} statement={:?}",
-                                    portal_name,
-                                    portal.statement_name
-                                ))
-                                .map(|ps| (portal, ps))
-                        });
-                    let (portal, prepared) = match result {
-                        Ok((portal, prepared)) => (portal, prepared),
-                        Err(e) => {
+                    let portal = match session.get_portal(&portal_name) {
+                        Some(portal) => portal,
+                        None => {
                             let _ = tx.send(Response {
-                                result: Err(e),
+                                result: Err(anyhow::format_err!(
+                                    "portal does not exist {:?}",
+                                    portal_name
+                                )),
                                 session,
                             });
                             return;
                         }
                     };
-                    match prepared.sql() {
+                    match &portal.stmt {
                         Some(stmt) => {
                             let mut internal_cmd_tx = internal_cmd_tx.clone();
                             let stmt = stmt.clone();
                             let params = portal.parameters.clone();
+                            let statement_name = portal.statement_name.clone();
                             tokio::spawn(async move {
                                 let result = sql::pure::purify(stmt).await;
                                 internal_cmd_tx
@@ -435,6 +492,8 @@ where
                                         tx: ClientTransmitter::new(tx),
                                         result,
                                         params,
+                                        portal_name,
+                                        statement_name,
                                     })
                                     .await
                                     .expect("sending to internal_cmd_tx cannot fail");
@@ -471,8 +530,17 @@ where
                                 finishing,
                                 copy_to,
                             } => {
-                                self.sequence_peek(conn_id, source, when, finishing, copy_to)
-                                    .await?
+                                self.sequence_peek(
+                                    conn_id,
+                                    IsolationLevel::Serializable,
+                                    false,
+                                    None,
+                                    source,
+                                    when,
+                                    finishing,
+                                    copy_to,
+                                )
+                                .await?
                             }
 
                             Plan::SendRows(rows) => send_immediate_rows(rows),
@@ -489,17 +557,34 @@ where
                 }
 
                 Message::StatementReady {
-                    session,
+                    mut session,
                     tx,
                     result,
                     params,
+                    portal_name,
+                    statement_name,
                 } => match future::ready(result)
-                    .and_then(|stmt| self.handle_statement(&session, stmt, &params))
+                    .and_then(|stmt| {
+                        self.handle_statement(
+                            &mut session,
+                            statement_name.as_deref(),
+                            stmt,
+                            &params,
+                        )
+                    })
                     .await
                 {
                     Ok((pcx, plan)) => {
-                        self.sequence_plan(&internal_cmd_tx, tx, session, pcx, plan)
-                            .await
+                        self.sequence_plan(
+                            &internal_cmd_tx,
+                            tx,
+                            session,
+                            portal_name,
+                            statement_name,
+                            pcx,
+                            plan,
+                        )
+                        .await
                     }
                     Err(e) => tx.send(Err(e), session),
                 },
@@ -594,6 +679,24 @@ where
                     }
                 }
 
+                Message::AlterTableRowsReady {
+                    session,
+                    tx,
+                    alter_table,
+                    timestamp,
+                    result,
+                } => {
+                    let result = self
+                        .sequence_alter_table_rows_ready(alter_table, timestamp, result)
+                        .await;
+                    tx.send(result, session);
+                }
+
+                Message::RestartSourceTimestamping { id, connector } => {
+                    ts_tx
+                        .send(TimestampMessage::Restart(id, connector))
+                        .expect("Failed to send Restart notice to timestamper");
+                }
                 Message::AdvanceSourceTimestamp { id, update } => {
                     broadcast(
                         &mut self.broadcast_tx,
@@ -668,11 +771,15 @@ where
                     if !index_state.upper.frontier().is_empty() {
                         let mut compaction_frontier = Antichain::new();
                         for time in index_state.upper.frontier().iter() {
-                            compaction_frontier.insert(
-                                compaction_window_ms
-                                    * (time.saturating_sub(compaction_window_ms)
-                                        / compaction_window_ms),
-                            );
+                            let mut time = compaction_window_ms
+                                * (time.saturating_sub(compaction_window_ms)
+                                    / compaction_window_ms);
+                            // Open transactions that read from this index
+                            // hold back its compaction.
+                            for hold in index_state.read_holds.frontier().iter() {
+                                time = cmp::min(time, *hold);
+                            }
+                            compaction_frontier.insert(time);
                         }
                         if index_state.since != compaction_frontier {
                             index_state.advance_since(&compaction_frontier);
@@ -710,12 +817,21 @@ where
 
     async fn handle_statement(
         &mut self,
-        session: &Session,
+        session: &mut Session,
+        statement_name: Option<&str>,
         stmt: sql::ast::Statement,
         params: &sql::plan::Params,
     ) -> Result<(PlanContext, sql::plan::Plan), anyhow::Error> {
         let pcx = PlanContext::default();
 
+        if let Some(statement_name) = statement_name {
+            if let Some(plan) =
+                self.plan_prepared_statement(session, statement_name, &pcx, &stmt, params)
+            {
+                return Ok((pcx, plan));
+            }
+        }
+
         // When symbiosis mode is enabled, use symbiosis planning for:
         //  - CREATE TABLE
         //  - DROP TABLE
@@ -757,6 +873,60 @@ where
         }
     }
 
+    /// Returns the cached plan of the prepared statement named
+    /// `statement_name`, whose SQL is `stmt` and whose parameters are
+    /// `params`, planning and caching it if the catalog or the session's
+    /// database has changed since it was cached.
+    ///
+    /// Returns `None` if the statement's plan cannot be reused, in which case
+    /// the statement must be planned afresh, with its parameters bound.
+    fn plan_prepared_statement(
+        &mut self,
+        session: &mut Session,
+        statement_name: &str,
+        pcx: &PlanContext,
+        stmt: &sql::ast::Statement,
+        params: &sql::plan::Params,
+    ) -> Option<sql::plan::Plan> {
+        let catalog_revision = self.catalog.transient_revision();
+        let database = session.vars().database().to_owned();
+        let is_current = session
+            .get_prepared_statement(statement_name)?
+            .plan_cache()
+            .is_current(catalog_revision, &database);
+        if !is_current {
+            let plan = match sql::plan::plan_reusable(
+                pcx,
+                &self.catalog.for_session(session),
+                stmt.clone(),
+                &params.types,
+            ) {
+                Ok(Plan::Peek {
+                    source, finishing, ..
+                }) => Some(CachedPlan {
+                    source,
+                    finishing,
+                    optimized_source: None,
+                }),
+                _ => None,
+            };
+            session
+                .get_prepared_statement_mut(statement_name)?
+                .plan_cache_mut()
+                .insert(catalog_revision, database, plan);
+        }
+        let plan = session
+            .get_prepared_statement_mut(statement_name)?
+            .plan_cache_mut()
+            .plan_mut()?;
+        Some(Plan::Peek {
+            source: plan.source.clone(),
+            when: PeekWhen::Immediately,
+            finishing: plan.finishing.clone(),
+            copy_to: None,
+        })
+    }
+
     fn handle_describe(
         &self,
         session: &mut Session,
@@ -783,6 +953,18 @@ where
         } else {
             StatementDesc::new(None)
         };
+        // The rows produced by a `FETCH` have the shape of its cursor's rows,
+        // which only the session knows about.
+        let desc = match &stmt {
+            Some(Statement::Fetch(FetchStatement { name, .. })) => {
+                let name = sql::normalize::ident(name.clone());
+                match session.get_portal(&name) {
+                    Some(cursor) => StatementDesc::new(cursor.desc.relation_desc.clone()),
+                    None => bail!("cursor \"{}\" does not exist", name),
+                }
+            }
+            _ => desc,
+        };
         session.set_prepared_statement(name, PreparedStatement::new(stmt, desc));
         Ok(())
     }
@@ -791,19 +973,38 @@ where
     /// the named `conn_id`. This means canceling the active PEEK or TAIL, if
     /// one exists.
     ///
+    /// The `TAIL`s that cursors read from are left running, as a cursor is not
+    /// closed when a statement that fetches from it is canceled. That
+    /// statement is instead interrupted via the session's cancellation
+    /// channel.
+    ///
     /// NOTE(benesch): this function makes the assumption that a connection can
     /// only have one active query at a time. This is true today, but will not
     /// be true once we have full support for portals.
     async fn handle_cancel(&mut self, conn_id: u32) {
-        if let Some(name) = self.active_tails.remove(&conn_id) {
-            // A TAIL is known to be active, so drop the dataflow that is
+        if let Some(cancel_tx) = self.cancel_txs.get(&conn_id) {
+            let _ = cancel_tx.unbounded_send(());
+        }
+        let mut names = vec![];
+        if let Some(tails) = self.active_tails.get_mut(&conn_id) {
+            tails.retain(|_, tail| {
+                if !tail.cursor {
+                    names.push(tail.sink_id);
+                }
+                tail.cursor
+            });
+        }
+        if !names.is_empty() {
+            // A TAIL is known to be active, so drop the dataflows that are
             // servicing it. No need to try to cancel PEEKs in this case,
             // because if a TAIL is active, a PEEK cannot be.
-            self.drop_sinks(vec![name]).await;
+            self.drop_sinks(names).await;
         } else {
             // No TAIL is known to be active, so drop the PEEK that may be
             // active on this connection. This is a no-op if no PEEKs are
-            // active.
+            // active. The index of any transient dataflow built to service
+            // the PEEK has already been dropped, so releasing the PEEK's
+            // hold on its arrangement tears down the dataflow.
             broadcast(
                 &mut self.broadcast_tx,
                 SequencedCommand::CancelPeek { conn_id },
@@ -816,9 +1017,13 @@ where
     ///
     // This cleans up any state in the coordinator associated with the session.
     async fn handle_terminate(&mut self, session: &mut Session) {
-        if let Some(name) = self.active_tails.remove(&session.conn_id()) {
-            self.drop_sinks(vec![name]).await;
+        self.handle_cancel(session.conn_id()).await;
+        self.cancel_txs.remove(&session.conn_id());
+        if let Some(tails) = self.active_tails.remove(&session.conn_id()) {
+            self.drop_sinks(tails.into_iter().map(|(_, tail)| tail.sink_id).collect())
+                .await;
         }
+        self.release_txn_reads(session.conn_id());
         self.drop_temp_items(session.conn_id()).await;
         self.catalog
             .drop_temporary_schema(session.conn_id())
@@ -861,8 +1066,14 @@ where
             .await
             .expect("replacing a sink cannot fail");
 
-        self.ship_dataflow(self.build_sink_dataflow(name.to_string(), id, sink.from, connector))
-            .await
+        let dataflow = match self.build_sink_dataflow(name.to_string(), id, &sink.from, connector) {
+            Ok(dataflow) => dataflow,
+            Err(e) => {
+                log::error!("unable to build dataflow for sink {}: {}", name, e);
+                return;
+            }
+        };
+        self.ship_dataflow(dataflow).await
     }
 
     /// Insert a single row into a given catalog view.
@@ -1126,6 +1337,29 @@ where
         .await
     }
 
+    async fn report_function_update(
+        &mut self,
+        global_id: GlobalId,
+        oid: u32,
+        schema_id: i64,
+        name: &str,
+        diff: isize,
+    ) {
+        self.update_catalog_view(
+            MZ_FUNCTIONS.id,
+            iter::once((
+                Row::pack(&[
+                    Datum::String(&global_id.to_string()),
+                    Datum::Int32(oid as i32),
+                    Datum::Int64(schema_id),
+                    Datum::String(name),
+                ]),
+                diff,
+            )),
+        )
+        .await
+    }
+
     async fn report_sink_update(
         &mut self,
         global_id: GlobalId,
@@ -1149,6 +1383,35 @@ where
         .await
     }
 
+    /// Records the external destination of the sink `id`, if any, in the
+    /// system catalog.
+    async fn report_sink_connector_update(
+        &mut self,
+        id: GlobalId,
+        connector: &SinkConnector,
+        diff: isize,
+    ) {
+        match connector {
+            SinkConnector::Kafka(KafkaSinkConnector { topic, .. }) => {
+                let row = Row::pack(&[
+                    Datum::String(&id.to_string()),
+                    Datum::String(topic.as_str()),
+                ]);
+                self.update_catalog_view(MZ_KAFKA_SINKS.id, iter::once((row, diff)))
+                    .await;
+            }
+            SinkConnector::AvroOcf(AvroOcfSinkConnector { path, .. }) => {
+                let row = Row::pack(&[
+                    Datum::String(&id.to_string()),
+                    Datum::Bytes(&path.clone().into_os_string().into_vec()),
+                ]);
+                self.update_catalog_view(MZ_AVRO_OCF_SINKS.id, iter::once((row, diff)))
+                    .await;
+            }
+            SinkConnector::Tail(_) => (),
+        }
+    }
+
     async fn report_type_update(
         &mut self,
         id: GlobalId,
@@ -1171,12 +1434,20 @@ where
             )),
         )
         .await;
-        match typ.inner {
+        match &typ.inner {
             TypeInner::Map { key_id, value_id } => {
-                self.report_map_type_update(id, key_id, value_id, diff)
+                self.report_map_type_update(id, *key_id, *value_id, diff)
                     .await
             }
             TypeInner::Base => self.report_base_type_update(id, diff).await,
+            TypeInner::Enum { labels } => self.report_enum_type_update(id, labels, diff).await,
+            TypeInner::Record { .. } => {
+                self.update_catalog_view(
+                    MZ_COMPOSITE_TYPES.id,
+                    iter::once((Row::pack(&[Datum::String(&id.to_string())]), diff)),
+                )
+                .await
+            }
         }
     }
 
@@ -1210,11 +1481,59 @@ where
         .await
     }
 
+    async fn report_enum_type_update(&mut self, id: GlobalId, labels: &[String], diff: isize) {
+        let id = id.to_string();
+        self.update_catalog_view(
+            MZ_ENUM_TYPES.id,
+            iter::once((Row::pack(&[Datum::String(&id)]), diff)),
+        )
+        .await;
+        let updates = labels
+            .iter()
+            .enumerate()
+            .map(|(position, label)| {
+                let row = Row::pack(&[
+                    Datum::String(&id),
+                    Datum::Int64(position as i64 + 1),
+                    Datum::String(label),
+                ]);
+                (row, diff)
+            })
+            .collect::<Vec<_>>();
+        self.update_catalog_view(MZ_ENUM_LABELS.id, updates).await
+    }
+
+    async fn report_comment_update(
+        &mut self,
+        id: GlobalId,
+        column: Option<usize>,
+        comment: &str,
+        diff: isize,
+    ) {
+        self.update_catalog_view(
+            MZ_COMMENTS.id,
+            iter::once((
+                Row::pack(&[
+                    Datum::String(&id.to_string()),
+                    match column {
+                        None => Datum::Null,
+                        Some(column) => Datum::Int64(column as i64 + 1),
+                    },
+                    Datum::String(comment),
+                ]),
+                diff,
+            )),
+        )
+        .await
+    }
+
     async fn sequence_plan(
         &mut self,
         internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
         tx: ClientTransmitter<ExecuteResponse>,
         mut session: Session,
+        portal_name: String,
+        statement_name: Option<String>,
         pcx: PlanContext,
         plan: Plan,
     ) {
@@ -1313,18 +1632,19 @@ where
                 tx.send(self.sequence_create_type(pcx, name, typ).await, session)
             }
 
-            Plan::DropDatabase { name } => {
-                tx.send(self.sequence_drop_database(name).await, session)
-            }
-
-            Plan::DropSchema {
-                database_name,
-                schema_name,
-            } => tx.send(
-                self.sequence_drop_schema(database_name, schema_name).await,
+            Plan::CreateFunction { name, function } => tx.send(
+                self.sequence_create_function(pcx, name, function).await,
                 session,
             ),
 
+            Plan::DropDatabases { names } => {
+                tx.send(self.sequence_drop_databases(names).await, session)
+            }
+
+            Plan::DropSchemas { schemas } => {
+                tx.send(self.sequence_drop_schemas(schemas).await, session)
+            }
+
             Plan::DropItems { items, ty } => {
                 tx.send(self.sequence_drop_items(items, ty).await, session)
             }
@@ -1339,6 +1659,8 @@ where
                 tx.send(self.sequence_show_variable(&session, name).await, session)
             }
 
+            Plan::ShowCreateAll => tx.send(Ok(self.sequence_show_create_all()), session),
+
             Plan::SetVariable { name, value } => tx.send(
                 self.sequence_set_variable(&mut session, name, value).await,
                 session,
@@ -1350,12 +1672,25 @@ where
             }
 
             Plan::CommitTransaction => {
+                // The writes of a failed transaction are discarded, as if it
+                // had been rolled back.
+                let result = if *session.transaction() == TransactionStatus::InTransaction {
+                    let writes = session.take_transaction_writes();
+                    self.sequence_transaction_writes(writes).await
+                } else {
+                    Ok(())
+                };
                 session.end_transaction();
-                tx.send(Ok(ExecuteResponse::CommittedTransaction), session)
+                self.release_txn_reads(session.conn_id());
+                tx.send(
+                    result.map(|()| ExecuteResponse::CommittedTransaction),
+                    session,
+                )
             }
 
             Plan::AbortTransaction => {
                 session.end_transaction();
+                self.release_txn_reads(session.conn_id());
                 tx.send(Ok(ExecuteResponse::AbortedTransaction), session)
             }
 
@@ -1364,28 +1699,55 @@ where
                 when,
                 finishing,
                 copy_to,
-            } => tx.send(
-                self.sequence_peek(session.conn_id(), source, when, finishing, copy_to)
-                    .await,
-                session,
-            ),
+            } => {
+                let conn_id = session.conn_id();
+                let isolation = session.vars().transaction_isolation();
+                let in_transaction = *session.transaction() != TransactionStatus::Idle;
+                // A prepared statement's plan, if cached, has placeholders for
+                // the parameters bound to the portal.
+                let params = session
+                    .get_portal(&portal_name)
+                    .map(|portal| portal.parameters.clone());
+                let cached_plan = statement_name
+                    .and_then(|name| session.get_prepared_statement_mut(&name))
+                    .and_then(|stmt| stmt.plan_cache_mut().plan_mut())
+                    .zip(params);
+                let result = self
+                    .sequence_peek(
+                        conn_id,
+                        isolation,
+                        in_transaction,
+                        cached_plan,
+                        source,
+                        when,
+                        finishing,
+                        copy_to,
+                    )
+                    .await;
+                tx.send(result, session)
+            }
 
             Plan::Tail {
-                id,
+                from,
                 ts,
                 with_snapshot,
                 copy_to,
                 emit_progress,
                 object_columns,
+                envelope,
+                key_indices,
             } => tx.send(
                 self.sequence_tail(
-                    session.conn_id(),
-                    id,
+                    &session,
+                    portal_name,
+                    from,
                     with_snapshot,
                     ts,
                     copy_to,
                     emit_progress,
                     object_columns,
+                    envelope,
+                    key_indices,
                 )
                 .await,
                 session,
@@ -1416,12 +1778,15 @@ where
                 affected_rows,
                 kind,
             } => tx.send(
-                self.sequence_send_diffs(id, updates, affected_rows, kind)
+                self.sequence_send_diffs(&mut session, id, updates, affected_rows, kind)
                     .await,
                 session,
             ),
 
-            Plan::Insert { id, values } => tx.send(self.sequence_insert(id, values).await, session),
+            Plan::Insert { id, values } => tx.send(
+                self.sequence_insert(&mut session, id, values).await,
+                session,
+            ),
 
             Plan::AlterItemRename {
                 id,
@@ -1438,6 +1803,23 @@ where
                 session,
             ),
 
+            Plan::AlterTable(alter_table) => {
+                self.sequence_alter_table(internal_cmd_tx.clone(), tx, session, alter_table)
+                    .await
+            }
+
+            Plan::AlterSource(alter_source) => tx.send(
+                self.sequence_alter_source(internal_cmd_tx, alter_source)
+                    .await,
+                session,
+            ),
+
+            Plan::Comment {
+                id,
+                column,
+                comment,
+            } => tx.send(self.sequence_comment(id, column, comment).await, session),
+
             Plan::DiscardTemp => {
                 self.drop_temp_items(session.conn_id()).await;
                 tx.send(Ok(ExecuteResponse::DiscardedTemp), session);
@@ -1445,9 +1827,30 @@ where
 
             Plan::DiscardAll => {
                 self.drop_temp_items(session.conn_id()).await;
+                self.release_txn_reads(session.conn_id());
                 session.reset();
                 tx.send(Ok(ExecuteResponse::DiscardedAll), session);
             }
+
+            Plan::Declare { name, stmt, params } => tx.send(
+                self.sequence_declare(&mut session, name, stmt, params),
+                session,
+            ),
+
+            Plan::Fetch {
+                name,
+                count,
+                timeout,
+            } => tx.send(
+                Ok(ExecuteResponse::Fetch {
+                    name,
+                    count,
+                    timeout,
+                }),
+                session,
+            ),
+
+            Plan::Close { name } => tx.send(self.sequence_close(&mut session, name).await, session),
         }
     }
 
@@ -1507,6 +1910,7 @@ where
             create_sql: table.create_sql,
             plan_cx: pcx,
             desc: table.desc,
+            depends_on: table.depends_on,
         };
         let index_id = self.catalog.allocate_id()?;
         let mut index_name = name.clone();
@@ -1626,7 +2030,56 @@ where
             }
         };
 
-        let frontier = match self.determine_frontier(as_of, sink.from) {
+        let from = match self.prep_sink_from(sink.from) {
+            Ok(from) => from,
+            Err(e) => {
+                tx.send(Err(e), session);
+                return;
+            }
+        };
+
+        // An exactly-once sink resumes after the last timestamp that it
+        // completed, which is only correct if its inputs assign the same
+        // timestamps to their data when they are read again.
+        if let SinkConnectorBuilder::Kafka(builder) = &sink.connector_builder {
+            if builder.exactly_once || builder.reuse_topic {
+                if let Some(input) = from
+                    .uses()
+                    .into_iter()
+                    .find_map(|id| self.catalog.inconsistent_input(id))
+                {
+                    let err = anyhow!(
+                        "exactly-once sinks require all of their inputs to be sources with \
+                         a consistency topic, but {} is not",
+                        self.catalog.get_by_id(&input).name()
+                    );
+                    tx.send(Err(err), session);
+                    return;
+                }
+            }
+            // A sink that reuses its topic writes to it with a transactional
+            // producer, so another sink writing to the same topic would both
+            // interleave its data and interfere with resuming the topic.
+            if builder.reuse_topic {
+                if let Some(other) = self.catalog.kafka_sink_writing_to(builder) {
+                    let err = anyhow!(
+                        "reuse_topic sinks require a topic that no other sink writes to, \
+                         but {} writes to topic {}",
+                        self.catalog.get_by_id(&other).name(),
+                        builder.topic_prefix
+                    );
+                    tx.send(Err(err), session);
+                    return;
+                }
+            }
+            if let Err(e) = self.validate_upsert_key(&from, builder.envelope, &builder.key_indices)
+            {
+                tx.send(Err(e), session);
+                return;
+            }
+        }
+
+        let frontier = match self.determine_frontier(as_of, &from) {
             Ok(frontier) => frontier,
             Err(e) => {
                 tx.send(Err(e), session);
@@ -1647,7 +2100,7 @@ where
             item: CatalogItem::Sink(catalog::Sink {
                 create_sql: sink.create_sql,
                 plan_cx: pcx,
-                from: sink.from,
+                from,
                 connector: catalog::SinkConnectorState::Pending(sink.connector_builder.clone()),
                 with_snapshot,
                 as_of,
@@ -1694,12 +2147,6 @@ where
         materialize: bool,
         if_not_exists: bool,
     ) -> Result<ExecuteResponse, anyhow::Error> {
-        let mut ops = vec![];
-        if let Some(id) = replace {
-            ops.extend(self.catalog.drop_items_ops(&[id]));
-        }
-        let view_id = self.catalog.allocate_id()?;
-        let view_oid = self.catalog.allocate_oid()?;
         // Optimize the expression so that we can form an accurately typed description.
         let optimized_expr = self.prep_relation_expr(view.expr, ExprPrepStyle::Static)?;
         let desc = RelationDesc::new(optimized_expr.as_ref().typ(), view.column_names);
@@ -1709,26 +2156,69 @@ where
             optimized_expr,
             desc,
             conn_id: if view.temporary { Some(conn_id) } else { None },
+            depends_on: view.depends_on,
         };
+        let mut ops = vec![];
+        let mut recreate_default_index = materialize;
+        if let Some(id) = replace {
+            if view.optimized_expr.as_ref().global_uses().contains(&id) {
+                bail!(
+                    "cannot replace view {}: the new definition refers to the view itself",
+                    name
+                );
+            }
+            // A sink has already emitted the contents of the old definition,
+            // and would not retract the rows that the new definition drops.
+            if let Some(sink) = self.dependent_sink(id) {
+                bail!(
+                    "cannot replace view {} because sink {} depends on it",
+                    name,
+                    self.catalog.get_by_id(&sink).name()
+                );
+            }
+            match self.check_view_replacement(id, &view) {
+                Ok(()) => {
+                    return self
+                        .sequence_replace_view(id, name, view, materialize)
+                        .await
+                }
+                Err(reason) => {
+                    // A view that only its default index depends upon can
+                    // instead be dropped and recreated, along with that index.
+                    // Other indexes cannot be recreated, as their keys may not
+                    // be valid for the new definition.
+                    let entry = self.catalog.get_by_id(&id);
+                    let default_index = self.catalog.default_index_for(id);
+                    if let Some(dependent) = entry
+                        .used_by()
+                        .iter()
+                        .filter(|id| Some(**id) != default_index)
+                        .map(|id| self.catalog.get_by_id(id))
+                        .next()
+                    {
+                        bail!(
+                            "cannot replace view {} because {} depends on it: {}",
+                            entry.name(),
+                            dependent.name(),
+                            reason
+                        );
+                    }
+                    recreate_default_index |= default_index.is_some();
+                    ops.extend(self.catalog.drop_items_ops(&[id]));
+                }
+            }
+        }
+        let view_id = self.catalog.allocate_id()?;
+        let view_oid = self.catalog.allocate_oid()?;
         ops.push(catalog::Op::CreateItem {
             id: view_id,
             oid: view_oid,
             name: name.clone(),
             item: CatalogItem::View(view.clone()),
         });
-        let index_id = if materialize {
-            let mut index_name = name.clone();
-            index_name.item += "_primary_idx";
-            let index =
-                auto_generate_primary_idx(index_name.item.clone(), name, view_id, &view.desc);
-            let index_id = self.catalog.allocate_id()?;
-            let index_oid = self.catalog.allocate_oid()?;
-            ops.push(catalog::Op::CreateItem {
-                id: index_id,
-                oid: index_oid,
-                name: index_name,
-                item: CatalogItem::Index(index),
-            });
+        let index_id = if recreate_default_index {
+            let (index_id, op) = self.primary_index_op(name, view_id, &view.desc)?;
+            ops.push(op);
             Some(index_id)
         } else {
             None
@@ -1746,15 +2236,141 @@ where
         }
     }
 
-    async fn sequence_create_index(
+    /// Checks whether the view `id` can be replaced by `view` in place, i.e.,
+    /// without disturbing the items that depend upon it. Returns the reason
+    /// that it cannot, if any.
+    ///
+    /// The new definition must produce the same columns as the old one, and
+    /// none of its columns may admit nulls that the old one did not.
+    fn check_view_replacement(&self, id: GlobalId, view: &catalog::View) -> Result<(), String> {
+        let uses = view.optimized_expr.as_ref().global_uses();
+        for used_id in uses.into_iter().chain(view.depends_on.iter().copied()) {
+            // Items are loaded from the catalog in the order in which they
+            // were created, so a persisted view cannot come to depend upon
+            // an item that was created after it.
+            if used_id > id && view.conn_id.is_none() {
+                return Err(format!(
+                    "the new definition refers to {}, which was created after the view",
+                    self.catalog.get_by_id(&used_id).name()
+                ));
+            }
+        }
+        let old_desc = self
+            .catalog
+            .get_by_id(&id)
+            .desc()
+            .expect("views have descriptions");
+        if old_desc.arity() != view.desc.arity() {
+            return Err(format!(
+                "the number of columns would change from {} to {}",
+                old_desc.arity(),
+                view.desc.arity()
+            ));
+        }
+        let unnamed = ColumnName::from("?column?");
+        for ((old_name, old_typ), (new_name, new_typ)) in old_desc.iter().zip(view.desc.iter()) {
+            let old_name = old_name.unwrap_or(&unnamed);
+            let new_name = new_name.unwrap_or(&unnamed);
+            if old_name != new_name {
+                return Err(format!(
+                    "column \"{}\" would be renamed to \"{}\"",
+                    old_name, new_name
+                ));
+            }
+            if old_typ.scalar_type != new_typ.scalar_type {
+                return Err(format!(
+                    "column \"{}\" would change type from {} to {}",
+                    old_name,
+                    pgrepr::Type::from(&old_typ.scalar_type).name(),
+                    pgrepr::Type::from(&new_typ.scalar_type).name()
+                ));
+            }
+            if new_typ.nullable && !old_typ.nullable {
+                return Err(format!("column \"{}\" would become nullable", old_name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a sink that reads from the item `id`, directly or through
+    /// other items, if there is one.
+    fn dependent_sink(&self, id: GlobalId) -> Option<GlobalId> {
+        let mut seen = HashSet::new();
+        let mut worklist = vec![id];
+        while let Some(id) = worklist.pop() {
+            for dependent in self.catalog.get_by_id(&id).used_by() {
+                if let CatalogItem::Sink(_) = self.catalog.get_by_id(dependent).item() {
+                    return Some(*dependent);
+                }
+                if seen.insert(*dependent) {
+                    worklist.push(*dependent);
+                }
+            }
+        }
+        None
+    }
+
+    /// Replaces the definition of the view `id` with `view`, which must have
+    /// passed `check_view_replacement`, and upon which no sinks may depend.
+    ///
+    /// The view keeps its ID, and the items that depend upon it are
+    /// redefined against its new definition. Only the dataflows of the
+    /// affected indexes are rebuilt.
+    async fn sequence_replace_view(
         &mut self,
-        pcx: PlanContext,
+        id: GlobalId,
         name: FullName,
-        mut index: sql::plan::Index,
+        view: catalog::View,
+        materialize: bool,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let desc = view.desc.clone();
+        let updates = self
+            .catalog
+            .plan_item_update(vec![(id, CatalogItem::View(view))])?;
+        self.update_items(&updates).await?;
+        self.rebuild_index_dataflows(&updates).await;
+
+        if materialize && self.catalog.default_index_for(id).is_none() {
+            let (index_id, op) = self.primary_index_op(name, id, &desc)?;
+            self.catalog_transact(vec![op]).await?;
+            self.ship_dataflow(self.build_index_dataflow(index_id))
+                .await;
+        }
+
+        Ok(ExecuteResponse::CreatedView { existed: false })
+    }
+
+    /// Plans the creation of the default index for the view `view_id`, named
+    /// `name`, whose description is `desc`.
+    fn primary_index_op(
+        &mut self,
+        name: FullName,
+        view_id: GlobalId,
+        desc: &RelationDesc,
+    ) -> Result<(GlobalId, catalog::Op), anyhow::Error> {
+        let mut index_name = name.clone();
+        index_name.item += "_primary_idx";
+        let index = auto_generate_primary_idx(index_name.item.clone(), name, view_id, desc);
+        let index_id = self.catalog.allocate_id()?;
+        let index_oid = self.catalog.allocate_oid()?;
+        let op = catalog::Op::CreateItem {
+            id: index_id,
+            oid: index_oid,
+            name: index_name,
+            item: CatalogItem::Index(index),
+        };
+        Ok((index_id, op))
+    }
+
+    async fn sequence_create_index(
+        &mut self,
+        pcx: PlanContext,
+        name: FullName,
+        mut index: sql::plan::Index,
         if_not_exists: bool,
     ) -> Result<ExecuteResponse, anyhow::Error> {
         for key in &mut index.keys {
-            Self::prep_scalar_expr(key, ExprPrepStyle::Static)?;
+            prep_scalar_expr(key, ExprPrepStyle::Static)?;
         }
         let index = catalog::Index {
             create_sql: index.create_sql,
@@ -1793,6 +2409,10 @@ where
                 sql::plan::TypeInner::Map { key_id, value_id } => {
                     catalog::TypeInner::Map { key_id, value_id }
                 }
+                sql::plan::TypeInner::Enum { labels } => catalog::TypeInner::Enum { labels },
+                sql::plan::TypeInner::Record { fields, depends_on } => {
+                    catalog::TypeInner::Record { fields, depends_on }
+                }
             },
         };
         let id = self.catalog.allocate_id()?;
@@ -1809,21 +2429,47 @@ where
         }
     }
 
-    async fn sequence_drop_database(
+    async fn sequence_create_function(
         &mut self,
-        name: String,
+        pcx: PlanContext,
+        name: FullName,
+        function: sql::plan::Function,
     ) -> Result<ExecuteResponse, anyhow::Error> {
-        let ops = self.catalog.drop_database_ops(name);
+        let func = catalog::Func {
+            create_sql: function.create_sql,
+            plan_cx: pcx,
+            params: function.params,
+            returns: function.returns,
+            depends_on: function.depends_on,
+        };
+        let id = self.catalog.allocate_id()?;
+        let oid = self.catalog.allocate_oid()?;
+        let op = catalog::Op::CreateItem {
+            id,
+            oid,
+            name,
+            item: CatalogItem::Func(func),
+        };
+        match self.catalog_transact(vec![op]).await {
+            Ok(()) => Ok(ExecuteResponse::CreatedFunction),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn sequence_drop_databases(
+        &mut self,
+        names: Vec<String>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let ops = self.catalog.drop_databases_ops(names);
         self.catalog_transact(ops).await?;
         Ok(ExecuteResponse::DroppedDatabase)
     }
 
-    async fn sequence_drop_schema(
+    async fn sequence_drop_schemas(
         &mut self,
-        database_name: DatabaseSpecifier,
-        schema_name: String,
+        schemas: Vec<(DatabaseSpecifier, String)>,
     ) -> Result<ExecuteResponse, anyhow::Error> {
-        let ops = self.catalog.drop_schema_ops(database_name, schema_name);
+        let ops = self.catalog.drop_schemas_ops(schemas);
         self.catalog_transact(ops).await?;
         Ok(ExecuteResponse::DroppedSchema)
     }
@@ -1853,6 +2499,7 @@ where
             ObjectType::Sink => ExecuteResponse::DroppedSink,
             ObjectType::Index => ExecuteResponse::DroppedIndex,
             ObjectType::Type => ExecuteResponse::DroppedType,
+            ObjectType::Function => ExecuteResponse::DroppedFunction,
         })
     }
 
@@ -1876,6 +2523,17 @@ where
         ))
     }
 
+    fn sequence_show_create_all(&self) -> ExecuteResponse {
+        let mut row_packer = RowPacker::new();
+        send_immediate_rows(
+            self.catalog
+                .dump_ddl()
+                .iter()
+                .map(|stmt| row_packer.pack(&[Datum::String(stmt)]))
+                .collect(),
+        )
+    }
+
     async fn sequence_show_variable(
         &self,
         session: &Session,
@@ -1896,22 +2554,78 @@ where
         Ok(ExecuteResponse::SetVariable { name })
     }
 
+    fn sequence_declare(
+        &self,
+        session: &mut Session,
+        name: String,
+        stmt: Statement,
+        params: Params,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        if session.get_portal(&name).is_some() {
+            bail!("cursor \"{}\" already exists", name);
+        }
+        let param_types: Vec<_> = params
+            .types
+            .iter()
+            .map(|ty| Some(pgrepr::Type::from(ty)))
+            .collect();
+        let desc = sql::plan::describe(
+            &self.catalog.for_session(session),
+            stmt.clone(),
+            &param_types,
+        )?;
+        session.declare_cursor(name, stmt, desc, params);
+        Ok(ExecuteResponse::DeclaredCursor)
+    }
+
+    async fn sequence_close(
+        &mut self,
+        session: &mut Session,
+        name: String,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        if session.get_portal(&name).is_none() {
+            bail!("cursor \"{}\" does not exist", name);
+        }
+        // Dropping the portal drops any rows it has yet to deliver. If the
+        // cursor is reading a `TAIL`, its dataflow is torn down too.
+        session.remove_portal(&name);
+        if let Some(tail) = self
+            .active_tails
+            .get_mut(&session.conn_id())
+            .and_then(|tails| tails.remove(&name))
+        {
+            self.drop_sinks(vec![tail.sink_id]).await;
+        }
+        Ok(ExecuteResponse::ClosedCursor)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn sequence_peek(
         &mut self,
         conn_id: u32,
+        isolation: IsolationLevel,
+        in_transaction: bool,
+        cached_plan: Option<(&mut CachedPlan, Params)>,
         source: RelationExpr,
         when: PeekWhen,
         finishing: RowSetFinishing,
         copy_to: Option<CopyFormat>,
     ) -> Result<ExecuteResponse, anyhow::Error> {
-        let timestamp = self.determine_timestamp(&source, when)?;
+        let timestamp = if in_transaction && when == PeekWhen::Immediately {
+            self.determine_txn_timestamp(conn_id, &source, isolation)?
+        } else {
+            self.determine_timestamp(&source, when, isolation)?
+        };
 
-        let source = self.prep_relation_expr(
-            source,
-            ExprPrepStyle::OneShot {
-                logical_time: timestamp,
-            },
-        )?;
+        let style = ExprPrepStyle::OneShot {
+            logical_time: timestamp,
+        };
+        let source = match cached_plan {
+            Some((cached_plan, params)) => {
+                self.prep_cached_relation_expr(cached_plan, style, &params)?
+            }
+            None => self.prep_relation_expr(source, style)?,
+        };
 
         // If this optimizes to a constant expression, we can immediately return the result.
         let resp = if let RelationExpr::Constant { rows, typ: _ } = source.as_ref() {
@@ -1935,11 +2649,6 @@ where
             // also to ensure that there is a view in place to query, if the source of data
             // for the peek is not a base relation.
 
-            // Choose a timestamp for all workers to use in the peek.
-            // We minimize over all participating views, to ensure that the query will not
-            // need to block on the arrival of further input data.
-            let (rows_tx, rows_rx) = self.switchboard.mpsc_limited(self.num_timely_workers);
-
             // Extract any surrounding linear operators to determine if we can simply read
             // out the contents from an existing arrangement.
             let (mut map_filter_project, inner) =
@@ -1949,39 +2658,60 @@ where
             // an existing materialization. This is the case if the expression is now a
             // `RelationExpr::Get` and its target is something we have materialized.
             // Otherwise, we will need to build a new dataflow.
-            let mut fast_path: Option<(_, Option<Row>)> = None;
+            let mut fast_path: Option<(_, Option<Vec<Row>>, Option<KeyRange>, Vec<ScalarExpr>)> =
+                None;
             if let RelationExpr::Get {
                 id: Id::Global(id),
                 typ: _,
             } = inner
             {
                 // Here we should check for an index whose keys are constrained to literal
-                // values by predicate constraints in `map_filter_project`. If we find such
-                // an index, we can use it with the literal to perform look-ups at workers,
-                // and in principle avoid even contacting all but one worker (future work).
+                // values by predicate constraints in `map_filter_project`, as in equalities,
+                // `IN` lists, and small integer ranges. If we find such an index, we can use
+                // it with the literals to perform look-ups at workers. Failing that, we can
+                // read only the range of keys that the predicates bound (as in `BETWEEN`),
+                // or still use predicates on the index's keys to skip over keys that cannot
+                // contribute to the results.
                 if let Some(indexes) = self.catalog.indexes().get(id) {
-                    // Determine for each index identifier, an optional row literal as key.
+                    // Determine for each index identifier, optional row literals as keys.
                     // We want to extract the "best" option, where we prefer indexes with
-                    // literals and long keys, then indexes at all, then exit correctly.
+                    // literals, then key ranges, then long keys, then key predicates, then
+                    // indexes at all, then exit correctly.
                     fast_path = indexes
                         .iter()
                         .map(|(id, exprs)| {
-                            let literal_row = map_filter_project.literal_constraints(exprs);
-                            // Prefer non-trivial literal rows foremost, then long expressions,
-                            // then we don't really care at that point.
-                            (literal_row.is_some(), exprs.len(), literal_row, *id)
+                            let literal_rows = map_filter_project.literal_keys(exprs);
+                            let key_range = match literal_rows {
+                                Some(_) => None,
+                                None => map_filter_project.key_range(exprs),
+                            };
+                            let key_predicates = map_filter_project.key_predicates(exprs);
+                            (*id, exprs.len(), literal_rows, key_range, key_predicates)
+                        })
+                        .max_by_key(|(_id, len, literal_rows, key_range, key_predicates)| {
+                            // Prefer non-trivial literal rows foremost, then key ranges,
+                            // then long expressions, then key predicates, then we don't
+                            // really care at that point.
+                            (
+                                literal_rows.is_some(),
+                                key_range.is_some(),
+                                *len,
+                                key_predicates.len(),
+                            )
                         })
-                        .max()
-                        .map(|(_some, _len, literal, id)| (id, literal));
+                        .map(|(id, _len, literal_rows, key_range, key_predicates)| {
+                            (id, literal_rows, key_range, key_predicates)
+                        });
                 }
             }
 
             // Unpack what we have learned with default values if we found nothing.
-            let (fast_path, index_id, literal_row) = if let Some((id, row)) = fast_path {
-                (true, id, row)
-            } else {
-                (false, self.allocate_transient_id()?, None)
-            };
+            let (fast_path, index_id, literal_rows, key_range, key_predicates) =
+                if let Some((id, rows, range, predicates)) = fast_path {
+                    (true, id, rows, range, predicates)
+                } else {
+                    (false, self.allocate_transient_id()?, None, None, vec![])
+                };
 
             if !fast_path {
                 // Slow path. We need to perform some computation, so build
@@ -1999,16 +2729,40 @@ where
                 self.ship_dataflow(dataflow).await;
             }
 
+            // A literal key lives on exactly one worker, so if all literal keys live on
+            // the same worker only that worker serves the peek. Every worker holds a copy
+            // of the errors of all workers, so it can check for errors on its own.
+            let target_worker = literal_rows.as_ref().and_then(|rows| {
+                let mut workers = rows
+                    .iter()
+                    .map(|row| dataflow::worker_for_key(row, self.num_timely_workers));
+                let worker = workers.next()?;
+                if workers.all(|w| w == worker) {
+                    Some(worker)
+                } else {
+                    None
+                }
+            });
+            let responders = if target_worker.is_some() {
+                1
+            } else {
+                self.num_timely_workers
+            };
+            let (rows_tx, rows_rx) = self.switchboard.mpsc_limited(responders);
+
             broadcast(
                 &mut self.broadcast_tx,
                 SequencedCommand::Peek {
                     id: index_id,
-                    key: literal_row,
+                    keys: literal_rows,
+                    key_range,
+                    key_predicates,
                     conn_id,
                     tx: rows_tx,
                     timestamp,
                     finishing: finishing.clone(),
                     map_filter_project,
+                    target_worker,
                 },
             )
             .await;
@@ -2055,40 +2809,66 @@ where
     #[allow(clippy::too_many_arguments)]
     async fn sequence_tail(
         &mut self,
-        conn_id: u32,
-        source_id: GlobalId,
+        session: &Session,
+        portal_name: String,
+        from: sql::plan::SinkFrom,
         with_snapshot: bool,
         ts: Option<Timestamp>,
         copy_to: Option<CopyFormat>,
         emit_progress: bool,
         object_columns: usize,
+        envelope: SinkEnvelope,
+        key_indices: Option<Vec<usize>>,
     ) -> Result<ExecuteResponse, anyhow::Error> {
+        let from = self.prep_sink_from(from)?;
+        self.validate_upsert_key(&from, envelope, &key_indices)?;
         // Determine the frontier of updates to tail *from*.
         // Updates greater or equal to this frontier will be produced.
-        let frontier = self.determine_frontier(ts, source_id)?;
-        let sink_name = format!(
-            "tail-source-{}",
-            self.catalog
-                .humanize_id(Id::Global(source_id))
-                .expect("Source id is known to exist in catalog")
-        );
+        let frontier = self.determine_frontier(ts, &from)?;
         let sink_id = self.catalog.allocate_id()?;
-        self.active_tails.insert(conn_id, sink_id);
+        let sink_name = match &from {
+            catalog::SinkFrom::Id(source_id) => format!(
+                "tail-source-{}",
+                self.catalog
+                    .humanize_id(Id::Global(*source_id))
+                    .expect("Source id is known to exist in catalog")
+            ),
+            catalog::SinkFrom::Query { .. } => format!("tail-query-{}", sink_id),
+        };
         let (tx, rx) = self.switchboard.mpsc_limited(self.num_timely_workers);
 
-        self.ship_dataflow(self.build_sink_dataflow(
+        let dataflow = self.build_sink_dataflow(
             sink_name,
             sink_id,
-            source_id,
+            &from,
             SinkConnector::Tail(TailSinkConnector {
                 tx,
                 frontier,
                 strict: !with_snapshot,
                 emit_progress,
                 object_columns,
+                envelope,
+                key_indices,
             }),
-        ))
-        .await;
+        )?;
+        let tail = ActiveTail {
+            sink_id,
+            cursor: session
+                .get_portal(&portal_name)
+                .map(|portal| portal.cursor)
+                .unwrap_or(false),
+        };
+        // A portal that is executed again no longer reads the rows of the
+        // `TAIL` that it previously ran, so that `TAIL` is torn down.
+        if let Some(old_tail) = self
+            .active_tails
+            .entry(session.conn_id())
+            .or_default()
+            .insert(portal_name, tail)
+        {
+            self.drop_sinks(vec![old_tail.sink_id]).await;
+        }
+        self.ship_dataflow(dataflow).await;
 
         let resp = ExecuteResponse::Tailing { rx };
 
@@ -2101,6 +2881,86 @@ where
         }
     }
 
+    /// Determines the timestamp for a peek inside a transaction.
+    ///
+    /// The first peek in the transaction chooses its timestamp as usual, and
+    /// all later peeks reuse it, so that every read in the transaction observes
+    /// the same snapshot. As the first peek cannot know what the rest of the
+    /// transaction will read, it holds back the compaction of every index in
+    /// the schemas that it reads from at that timestamp, until the transaction
+    /// ends. A later peek that needs any other index fails, rather than reading
+    /// an index that may have been compacted past the transaction's timestamp.
+    fn determine_txn_timestamp(
+        &mut self,
+        conn_id: u32,
+        source: &RelationExpr,
+        isolation: IsolationLevel,
+    ) -> Result<Timestamp, anyhow::Error> {
+        let uses_ids = source.global_uses();
+        let (index_ids, _) = self.catalog.nearest_indexes(&uses_ids);
+        if let Some(reads) = self.txn_reads.get(&conn_id) {
+            if let Some(id) = index_ids.iter().find(|id| !reads.index_ids.contains(id)) {
+                let name = match self.catalog.get_by_id(id).item() {
+                    CatalogItem::Index(index) => self.catalog.get_by_id(&index.on).name(),
+                    _ => self.catalog.get_by_id(id).name(),
+                };
+                bail!(
+                    "transaction cannot read {}: a transaction can only read from the \
+                     schemas that its first query read from, and only from the indexes \
+                     that existed at that time",
+                    name
+                );
+            }
+            let timestamp = reads.timestamp;
+            return self.determine_timestamp(source, PeekWhen::AtTimestamp(timestamp), isolation);
+        }
+        let timestamp = self.determine_timestamp(source, PeekWhen::Immediately, isolation)?;
+        if index_ids.is_empty() {
+            // Peeks that read no indexes, like `SELECT 1`, do not constrain
+            // the timestamp at which the rest of the transaction reads.
+            return Ok(timestamp);
+        }
+        let mut held = vec![];
+        for id in index_ids
+            .into_iter()
+            .chain(self.catalog.schema_indexes(&uses_ids, conn_id))
+        {
+            if held.contains(&id) {
+                continue;
+            }
+            // Indexes that have already been compacted past the timestamp
+            // cannot be read by the transaction, and are not held.
+            if let Some(index_state) = self.indexes.get_mut(&id) {
+                if index_state.since.less_equal(&timestamp) {
+                    index_state.read_holds.update_iter(Some((timestamp, 1)));
+                    held.push(id);
+                }
+            }
+        }
+        self.txn_reads.insert(
+            conn_id,
+            TxnReads {
+                timestamp,
+                index_ids: held,
+            },
+        );
+        Ok(timestamp)
+    }
+
+    /// Releases the compaction holds of the transaction, if any, in which the
+    /// specified connection is reading.
+    fn release_txn_reads(&mut self, conn_id: u32) {
+        if let Some(reads) = self.txn_reads.remove(&conn_id) {
+            for id in reads.index_ids {
+                if let Some(index_state) = self.indexes.get_mut(&id) {
+                    index_state
+                        .read_holds
+                        .update_iter(Some((reads.timestamp, -1)));
+                }
+            }
+        }
+    }
+
     /// A policy for determining the timestamp for a peek.
     ///
     /// The result may be `None` in the case that the `when` policy cannot be satisfied,
@@ -2111,6 +2971,7 @@ where
         &mut self,
         source: &RelationExpr,
         when: PeekWhen,
+        isolation: IsolationLevel,
     ) -> Result<Timestamp, anyhow::Error> {
         // Each involved trace has a validity interval `[since, upper)`.
         // The contents of a trace are only guaranteed to be correct when
@@ -2191,6 +3052,20 @@ where
                 if !since.less_equal(&candidate) {
                     candidate.advance_by(since.borrow());
                 }
+                // Under strict serializability, the timestamp must not be
+                // earlier than that of any read or write that has already
+                // completed, even if some inputs have yet to reach it. The
+                // peek will then wait for those inputs to catch up, rather
+                // than reading older data. Queries with no inputs are
+                // excluded, as their timestamp says nothing about real time.
+                if isolation == IsolationLevel::StrictSerializable
+                    && candidate != Timestamp::max_value()
+                {
+                    candidate = cmp::max(candidate, self.read_lower_bound);
+                    self.read_lower_bound = candidate;
+                    self.last_op_was_read = true;
+                    self.need_advance = true;
+                }
                 candidate
             }
         };
@@ -2224,29 +3099,31 @@ where
     fn determine_frontier(
         &mut self,
         as_of: Option<u64>,
-        source_id: GlobalId,
+        from: &catalog::SinkFrom,
     ) -> Result<Antichain<u64>, anyhow::Error> {
         let frontier = if let Some(ts) = as_of {
             // If a timestamp was explicitly requested, use that.
-            Antichain::from_elem(self.determine_timestamp(
-                &RelationExpr::Get {
-                    id: Id::Global(source_id),
+            let source = match from {
+                catalog::SinkFrom::Id(source_id) => RelationExpr::Get {
+                    id: Id::Global(*source_id),
                     // TODO(justin): find a way to avoid synthesizing an arbitrary relation type.
                     typ: RelationType::empty(),
                 },
+                catalog::SinkFrom::Query { optimized_expr, .. } => optimized_expr.as_ref().clone(),
+            };
+            Antichain::from_elem(self.determine_timestamp(
+                &source,
                 PeekWhen::AtTimestamp(ts),
+                IsolationLevel::Serializable,
             )?)
         }
         // TODO: The logic that follows is at variance from PEEK logic which consults the
         // "queryable" state of its inputs. We might want those to line up, but it is only
         // a "might".
-        else if let Some(index_id) = self.catalog.default_index_for(source_id) {
-            let upper = self
-                .indexes
-                .upper_of(&index_id)
-                .expect("name missing at coordinator");
+        else if let Some(index_ids) = self.sink_from_indexes(from) {
+            let upper = self.indexes.greatest_open_upper(index_ids);
 
-            if let Some(ts) = upper.get(0) {
+            if let Some(ts) = upper.elements().get(0) {
                 Antichain::from_elem(ts.saturating_sub(1))
             } else {
                 Antichain::from_elem(Timestamp::max_value())
@@ -2259,6 +3136,30 @@ where
         Ok(frontier)
     }
 
+    /// Returns the indexes that can satisfy the relation a sink reads from,
+    /// or `None` if there are no such indexes.
+    ///
+    /// A named relation is satisfied by its default index, while a query is
+    /// satisfied by the nearest indexes of the objects it depends upon, if
+    /// those indexes are complete.
+    fn sink_from_indexes(&self, from: &catalog::SinkFrom) -> Option<Vec<GlobalId>> {
+        match from {
+            catalog::SinkFrom::Id(source_id) => self
+                .catalog
+                .default_index_for(*source_id)
+                .map(|id| vec![id]),
+            catalog::SinkFrom::Query { optimized_expr, .. } => {
+                let uses = optimized_expr.as_ref().global_uses();
+                let (index_ids, indexes_complete) = self.catalog.nearest_indexes(&uses);
+                if indexes_complete && !index_ids.is_empty() {
+                    Some(index_ids)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
     fn sequence_explain_plan(
         &mut self,
         raw_plan: sql::plan::RelationExpr,
@@ -2309,12 +3210,114 @@ where
 
     async fn sequence_send_diffs(
         &mut self,
+        session: &mut Session,
         id: GlobalId,
         updates: Vec<(Row, isize)>,
         affected_rows: usize,
         kind: MutationKind,
     ) -> Result<ExecuteResponse, anyhow::Error> {
+        let in_transaction = *session.transaction() != TransactionStatus::Idle;
+        match (in_transaction, &kind) {
+            (false, _) => {
+                self.check_table_writable(id)?;
+                let timestamp = self.get_write_ts();
+                self.send_diffs(id, updates, timestamp).await;
+            }
+            // Inserts in a transaction are buffered in the session, and only
+            // become visible when the transaction commits.
+            (_, MutationKind::Insert) => session.add_transaction_write(WriteOp {
+                id,
+                version: self.table_version(id),
+                data: WriteData::Updates(updates),
+            }),
+            // Updates and deletes are applied immediately, as they are outside
+            // of a transaction. Their diffs were computed against a state that
+            // includes the transaction's earlier writes, so those are applied
+            // first.
+            (_, MutationKind::Update) | (_, MutationKind::Delete) => {
+                self.check_table_writable(id)?;
+                let writes = session.take_transaction_writes();
+                self.sequence_transaction_writes(writes).await?;
+                let timestamp = self.get_write_ts();
+                self.send_diffs(id, updates, timestamp).await;
+            }
+        }
+
+        Ok(match kind {
+            MutationKind::Delete => ExecuteResponse::Deleted(affected_rows),
+            MutationKind::Insert => ExecuteResponse::Inserted(affected_rows),
+            MutationKind::Update => ExecuteResponse::Updated(affected_rows),
+        })
+    }
+
+    /// Applies the writes buffered by a committing transaction.
+    ///
+    /// All of the writes are assigned the same timestamp, so that they become
+    /// visible atomically, even if they span several tables. None of them are
+    /// applied if any of them fails.
+    async fn sequence_transaction_writes(
+        &mut self,
+        writes: Vec<WriteOp>,
+    ) -> Result<(), anyhow::Error> {
+        if writes.is_empty() {
+            return Ok(());
+        }
+        for write in &writes {
+            // The table may have been dropped or altered since the write was
+            // buffered, in which case the transaction fails.
+            let entry = match self.catalog.try_get_by_id(write.id) {
+                Some(entry) => entry,
+                None => bail!("a table written by the transaction was dropped before it committed"),
+            };
+            if self.table_version(write.id) != write.version {
+                bail!(
+                    "table {} was altered while the transaction was in progress",
+                    entry.name()
+                );
+            }
+            self.check_table_writable(write.id)?;
+        }
         let timestamp = self.get_write_ts();
+        let mut valid_writes = Vec::with_capacity(writes.len());
+        for WriteOp { id, data, .. } in writes {
+            let updates = match data {
+                WriteData::Values(values) => self.eval_insert_values(
+                    id,
+                    values,
+                    ExprPrepStyle::OneShot {
+                        logical_time: timestamp,
+                    },
+                )?,
+                WriteData::Updates(updates) => updates,
+            };
+            valid_writes.push((id, updates));
+        }
+        for (id, updates) in valid_writes {
+            self.send_diffs(id, updates, timestamp).await;
+        }
+        Ok(())
+    }
+
+    /// Fails if the table identified by `id` is being altered, as the rows
+    /// written to it now would not be rewritten by the alteration.
+    fn check_table_writable(&self, id: GlobalId) -> Result<(), anyhow::Error> {
+        if self.altering_tables.contains(&id) {
+            bail!(
+                "table {} is being altered",
+                self.catalog.get_by_id(&id).name()
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the number of times the table identified by `id` has been
+    /// altered.
+    fn table_version(&self, id: GlobalId) -> u64 {
+        self.table_versions.get(&id).copied().unwrap_or(0)
+    }
+
+    /// Sends updates to the table identified by `id` at `timestamp`.
+    async fn send_diffs(&mut self, id: GlobalId, updates: Vec<(Row, isize)>, timestamp: Timestamp) {
         let updates = updates
             .into_iter()
             .map(|(row, diff)| Update {
@@ -2329,22 +3332,44 @@ where
             SequencedCommand::Insert { id, updates },
         )
         .await;
-
-        Ok(match kind {
-            MutationKind::Delete => ExecuteResponse::Deleted(affected_rows),
-            MutationKind::Insert => ExecuteResponse::Inserted(affected_rows),
-            MutationKind::Update => ExecuteResponse::Updated(affected_rows),
-        })
     }
 
     async fn sequence_insert(
         &mut self,
+        session: &mut Session,
         id: GlobalId,
         values: RelationExpr,
     ) -> Result<ExecuteResponse, anyhow::Error> {
-        let prep_style = ExprPrepStyle::OneShot {
-            logical_time: self.get_write_ts(),
-        };
+        if *session.transaction() == TransactionStatus::Idle {
+            let prep_style = ExprPrepStyle::OneShot {
+                logical_time: self.get_write_ts(),
+            };
+            let rows = self.eval_insert_values(id, values, prep_style)?;
+            let affected_rows = rows.len();
+            self.sequence_send_diffs(session, id, rows, affected_rows, MutationKind::Insert)
+                .await
+        } else {
+            // The values are evaluated again when the transaction commits,
+            // at the transaction's timestamp. Evaluating them now with a
+            // dummy timestamp reports errors as early as possible.
+            let rows = self.eval_insert_values(id, values.clone(), ExprPrepStyle::Explain)?;
+            session.add_transaction_write(WriteOp {
+                id,
+                version: self.table_version(id),
+                data: WriteData::Values(values),
+            });
+            Ok(ExecuteResponse::Inserted(rows.len()))
+        }
+    }
+
+    /// Evaluates the `VALUES` of an `INSERT` into the table identified by
+    /// `id`, checking them against the table's constraints.
+    fn eval_insert_values(
+        &mut self,
+        id: GlobalId,
+        values: RelationExpr,
+        prep_style: ExprPrepStyle,
+    ) -> Result<Vec<(Row, isize)>, anyhow::Error> {
         match self.prep_relation_expr(values, prep_style)?.into_inner() {
             RelationExpr::Constant { rows, typ: _ } => {
                 let desc = self.catalog.get_by_id(&id).desc()?;
@@ -2358,10 +3383,7 @@ where
                         }
                     }
                 }
-
-                let affected_rows = rows.len();
-                self.sequence_send_diffs(id, rows, affected_rows, MutationKind::Insert)
-                    .await
+                Ok(rows)
             }
             // If we couldn't optimize the INSERT statement to a constant, it
             // must depend on another relation. We're not yet sophisticated
@@ -2418,6 +3440,406 @@ where
         }
     }
 
+    /// Begins altering a table.
+    ///
+    /// The existing rows of the table are read out of its default index
+    /// without blocking the coordinator. Once they arrive, the alteration is
+    /// completed by [`Coordinator::sequence_alter_table_rows_ready`]. Writes
+    /// to the table are rejected in the meantime, as the rows they write would
+    /// not be rewritten.
+    async fn sequence_alter_table(
+        &mut self,
+        mut internal_cmd_tx: futures::channel::mpsc::UnboundedSender<Message>,
+        tx: ClientTransmitter<ExecuteResponse>,
+        session: Session,
+        alter_table: Option<AlterTable>,
+    ) {
+        let alter_table = match alter_table {
+            Some(alter_table) => alter_table,
+            // None is generated by `IF EXISTS` and `IF NOT EXISTS`
+            None => {
+                tx.send(
+                    Ok(ExecuteResponse::AlteredObject(ObjectType::Table)),
+                    session,
+                );
+                return;
+            }
+        };
+        let id = alter_table.id;
+        if self.altering_tables.contains(&id) {
+            let err = anyhow!(
+                "table {} is already being altered",
+                self.catalog.get_by_id(&id).name()
+            );
+            tx.send(Err(err), session);
+            return;
+        }
+        // Items are loaded from the catalog in the order in which they were
+        // created, so the table cannot come to depend upon an item, like the
+        // type of a new column, that was created after it.
+        if let Some(used_id) = alter_table
+            .table
+            .depends_on
+            .iter()
+            .find(|used_id| **used_id > id)
+        {
+            let err = anyhow!(
+                "cannot alter {}: the new definition refers to {}, which was created after \
+                 the table",
+                self.catalog.get_by_id(&id).name(),
+                self.catalog.get_by_id(used_id).name()
+            );
+            tx.send(Err(err), session);
+            return;
+        }
+        // Plan the update now only to report errors early. The catalog can
+        // change while the rows are read, so it is planned again afterwards.
+        if let Err(e) = self.plan_table_update(id, alter_table.table.clone()) {
+            tx.send(Err(e), session);
+            return;
+        }
+
+        // Read the existing rows of the table out of its default index. The
+        // coordinator does not advance the table's input while it waits for
+        // the read, so the input is closed up to the read timestamp first.
+        let timestamp = self.get_read_ts();
+        if self.closed_up_to <= timestamp {
+            broadcast(
+                &mut self.broadcast_tx,
+                SequencedCommand::AdvanceAllLocalInputs {
+                    advance_to: timestamp + 1,
+                },
+            )
+            .await;
+            self.closed_up_to = timestamp + 1;
+        }
+        let index_id = self
+            .catalog
+            .default_index_for(id)
+            .expect("tables always have a default index");
+        let arity = self
+            .catalog
+            .get_by_id(&id)
+            .desc()
+            .expect("tables have descriptions")
+            .arity();
+        let (rows_tx, rows_rx) = self.switchboard.mpsc_limited(self.num_timely_workers);
+        broadcast(
+            &mut self.broadcast_tx,
+            SequencedCommand::Peek {
+                id: index_id,
+                keys: None,
+                key_range: None,
+                key_predicates: vec![],
+                conn_id: session.conn_id(),
+                tx: rows_tx,
+                timestamp,
+                finishing: RowSetFinishing {
+                    order_by: vec![],
+                    limit: None,
+                    offset: 0,
+                    project: (0..arity).collect(),
+                },
+                map_filter_project: expr::MapFilterProject::new(arity),
+                target_worker: None,
+            },
+        )
+        .await;
+        self.altering_tables.insert(id);
+
+        // Arrange to notify the main coordinator thread when the rows arrive,
+        // as is done for peeks.
+        tokio::spawn(async move {
+            let result = rows_rx
+                .try_collect::<Vec<_>>()
+                .await
+                .map_err(anyhow::Error::from);
+            let result = result.and_then(|resps| {
+                let mut rows = vec![];
+                for resp in resps {
+                    match resp {
+                        PeekResponse::Rows(r) => rows.extend(r),
+                        PeekResponse::Error(e) => bail!(e),
+                        PeekResponse::Canceled => bail!("canceling statement due to user request"),
+                    }
+                }
+                Ok(rows)
+            });
+            internal_cmd_tx
+                .send(Message::AlterTableRowsReady {
+                    session,
+                    tx,
+                    alter_table,
+                    timestamp,
+                    result,
+                })
+                .await
+                .expect("sending to internal_cmd_tx cannot fail");
+        });
+    }
+
+    /// Completes altering a table, once its existing `rows` have been read as
+    /// of `timestamp`, by rewriting them and redefining the table and
+    /// everything that depends upon it.
+    async fn sequence_alter_table_rows_ready(
+        &mut self,
+        alter_table: AlterTable,
+        timestamp: Timestamp,
+        rows: Result<Vec<Row>, anyhow::Error>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let AlterTable {
+            id,
+            table,
+            migration,
+        } = alter_table;
+        self.altering_tables.remove(&id);
+        let rows = rows?;
+        if self.catalog.try_get_by_id(id).is_none() {
+            bail!("the table was dropped while it was being altered");
+        }
+        let desc = table.desc.clone();
+        let updates = self.plan_table_update(id, table)?;
+
+        let temp_storage = RowArena::new();
+        let rows: Vec<_> = match migration {
+            TableMigration::AddColumn { mut default } => {
+                prep_scalar_expr(
+                    &mut default,
+                    ExprPrepStyle::OneShot {
+                        logical_time: timestamp,
+                    },
+                )?;
+                let default = default.eval(&[], &temp_storage)?;
+                rows.iter()
+                    .map(|row| {
+                        let mut datums = row.unpack();
+                        datums.push(default);
+                        Row::pack(&datums)
+                    })
+                    .collect()
+            }
+            TableMigration::DropColumn { column } => rows
+                .iter()
+                .map(|row| {
+                    let mut datums = row.unpack();
+                    datums.remove(column);
+                    Row::pack(&datums)
+                })
+                .collect(),
+        };
+        for row in &rows {
+            for (datum, (name, typ)) in row.unpack().iter().zip(desc.iter()) {
+                if datum == &Datum::Null && !typ.nullable {
+                    bail!(
+                        "column {} contains null values",
+                        name.unwrap_or(&ColumnName::from("unnamed column"))
+                    )
+                }
+            }
+        }
+
+        self.update_items(&updates).await?;
+        *self.table_versions.entry(id).or_insert(0) += 1;
+
+        // Rebuild the dataflows of every affected index, including the one
+        // that houses the table's input.
+        broadcast(
+            &mut self.broadcast_tx,
+            SequencedCommand::DropSources(vec![id]),
+        )
+        .await;
+        self.rebuild_index_dataflows(&updates).await;
+
+        let timestamp = self.get_write_ts();
+        self.send_diffs(
+            id,
+            rows.into_iter().map(|row| (row, 1)).collect(),
+            timestamp,
+        )
+        .await;
+
+        Ok(ExecuteResponse::AlteredObject(ObjectType::Table))
+    }
+
+    /// Plans the redefinition of the table `id` as `table`, along with its
+    /// default index and everything that depends upon it.
+    fn plan_table_update(
+        &mut self,
+        id: GlobalId,
+        table: sql::plan::Table,
+    ) -> Result<Vec<(GlobalId, CatalogItem)>, anyhow::Error> {
+        // The default index must cover exactly the columns of the table, so it
+        // is generated anew rather than replanned.
+        let entry = self.catalog.get_by_id(&id);
+        let name = entry.name().clone();
+        let plan_cx = match entry.item() {
+            CatalogItem::Table(table) => table.plan_cx.clone(),
+            _ => unreachable!("ALTER TABLE planned for a non-table"),
+        };
+        let index_id = self
+            .catalog
+            .default_index_for(id)
+            .expect("tables always have a default index");
+        let index_name = self.catalog.get_by_id(&index_id).name().item.clone();
+        let index = auto_generate_primary_idx(index_name, name, id, &table.desc);
+        Ok(self.catalog.plan_item_update(vec![
+            (
+                id,
+                CatalogItem::Table(catalog::Table {
+                    create_sql: table.create_sql,
+                    plan_cx,
+                    desc: table.desc,
+                    depends_on: table.depends_on,
+                }),
+            ),
+            (index_id, CatalogItem::Index(index)),
+        ])?)
+    }
+
+    async fn sequence_alter_source(
+        &mut self,
+        internal_cmd_tx: &futures::channel::mpsc::UnboundedSender<Message>,
+        alter_source: Option<AlterSource>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let AlterSource {
+            id,
+            source,
+            restart_only,
+        } = match alter_source {
+            Some(alter_source) => alter_source,
+            // None is generated by `IF EXISTS`
+            None => return Ok(ExecuteResponse::AlteredObject(ObjectType::Source)),
+        };
+
+        let plan_cx = match self.catalog.get_by_id(&id).item() {
+            CatalogItem::Source(source) => source.plan_cx.clone(),
+            _ => unreachable!("ALTER SOURCE planned for a non-source"),
+        };
+        let source = catalog::Source {
+            create_sql: source.create_sql,
+            plan_cx,
+            connector: source.connector,
+            desc: source.desc,
+        };
+
+        if restart_only {
+            // The contents of the source are unchanged, so the dataflows that
+            // read from it can be left alone. Only the operators that read
+            // the source, and the timestamper, pick up the new definition.
+            let connector = source.connector.clone();
+            self.catalog_transact(vec![catalog::Op::UpdateItem {
+                id,
+                item: CatalogItem::Source(source),
+            }])
+            .await?;
+            broadcast(
+                &mut self.broadcast_tx,
+                SequencedCommand::ReconfigureSource {
+                    id,
+                    connector: connector.clone(),
+                },
+            )
+            .await;
+            internal_cmd_tx
+                .unbounded_send(Message::RestartSourceTimestamping { id, connector })
+                .expect("sending to internal_cmd_tx cannot fail");
+        } else {
+            // Every dataflow that instantiates the source must read it anew,
+            // so the indexes that depend on the source are rebuilt.
+            let updates = self
+                .catalog
+                .plan_item_update(vec![(id, CatalogItem::Source(source))])?;
+            self.update_items(&updates).await?;
+            self.rebuild_index_dataflows(&updates).await;
+        }
+
+        Ok(ExecuteResponse::AlteredObject(ObjectType::Source))
+    }
+
+    async fn sequence_comment(
+        &mut self,
+        id: GlobalId,
+        column: Option<usize>,
+        comment: Option<String>,
+    ) -> Result<ExecuteResponse, anyhow::Error> {
+        let op = catalog::Op::SetComment {
+            id,
+            column,
+            comment,
+        };
+        self.catalog_transact(vec![op]).await?;
+        Ok(ExecuteResponse::Commented)
+    }
+
+    /// Replaces the definitions of the items in `updates` in the catalog,
+    /// along with their column and index metadata in the system catalog.
+    async fn update_items(
+        &mut self,
+        updates: &[(GlobalId, CatalogItem)],
+    ) -> Result<(), anyhow::Error> {
+        for (id, _) in updates {
+            self.report_item_columns(*id, -1).await?;
+        }
+        self.catalog_transact(
+            updates
+                .iter()
+                .map(|(id, item)| catalog::Op::UpdateItem {
+                    id: *id,
+                    item: item.clone(),
+                })
+                .collect(),
+        )
+        .await?;
+        for (id, _) in updates {
+            self.report_item_columns(*id, 1).await?;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the dataflows of the indexes in `updates` from their new
+    /// definitions, keeping their compaction settings.
+    async fn rebuild_index_dataflows(&mut self, updates: &[(GlobalId, CatalogItem)]) {
+        let index_ids: Vec<_> = updates
+            .iter()
+            .filter_map(|(id, item)| match item {
+                CatalogItem::Index(_) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let compaction_windows: Vec<_> = index_ids
+            .iter()
+            .filter_map(|id| Some((*id, self.indexes.get(id)?.compaction_window_ms)))
+            .collect();
+        self.drop_indexes(index_ids.clone()).await;
+        for index_id in index_ids {
+            self.ship_dataflow(self.build_index_dataflow(index_id))
+                .await;
+        }
+        for (index_id, window_ms) in compaction_windows {
+            if let Some(index) = self.indexes.get_mut(&index_id) {
+                index.set_compaction_window_ms(window_ms);
+            }
+        }
+    }
+
+    /// Adds or retracts the column and index metadata of the item `id` in the
+    /// system catalog, according to `diff`.
+    async fn report_item_columns(
+        &mut self,
+        id: GlobalId,
+        diff: isize,
+    ) -> Result<(), anyhow::Error> {
+        let entry = self.catalog.get_by_id(&id);
+        if let CatalogItem::Index(index) = entry.item() {
+            let (oid, index, name) = (entry.oid(), index.clone(), entry.name().item.clone());
+            self.report_index_update(id, oid, &index, &name, diff).await;
+        } else if let Ok(desc) = entry.desc() {
+            let desc = desc.clone();
+            self.report_column_updates(&desc, id, diff).await?;
+        }
+        Ok(())
+    }
+
     async fn catalog_transact(&mut self, ops: Vec<catalog::Op>) -> Result<(), anyhow::Error> {
         let events = self.catalog.transact(ops)?;
         self.process_catalog_events(events).await
@@ -2486,6 +3908,10 @@ where
                             self.report_type_update(*id, *oid, *schema_id, &name.item, ty, 1)
                                 .await;
                         }
+                        CatalogItem::Func(_) => {
+                            self.report_function_update(*id, *oid, *schema_id, &name.item, 1)
+                                .await;
+                        }
                     }
                 }
                 catalog::Event::UpdatedItem {
@@ -2547,6 +3973,12 @@ where
                             self.report_type_update(*id, *oid, *schema_id, &to_name.item, &typ, 1)
                                 .await;
                         }
+                        CatalogItem::Func(_) => {
+                            self.report_function_update(*id, *oid, *schema_id, &from_name.item, -1)
+                                .await;
+                            self.report_function_update(*id, *oid, *schema_id, &to_name.item, 1)
+                                .await;
+                        }
                     }
                 }
                 catalog::Event::DroppedDatabase { id, oid, name } => {
@@ -2629,31 +4061,8 @@ where
                                 -1,
                             )
                             .await;
-                            match connector {
-                                SinkConnector::Kafka(KafkaSinkConnector { topic, .. }) => {
-                                    let row = Row::pack(&[
-                                        Datum::String(entry.id().to_string().as_str()),
-                                        Datum::String(topic.as_str()),
-                                    ]);
-                                    self.update_catalog_view(
-                                        MZ_KAFKA_SINKS.id,
-                                        iter::once((row, -1)),
-                                    )
-                                    .await;
-                                }
-                                SinkConnector::AvroOcf(AvroOcfSinkConnector { path, .. }) => {
-                                    let row = Row::pack(&[
-                                        Datum::String(entry.id().to_string().as_str()),
-                                        Datum::Bytes(&path.clone().into_os_string().into_vec()),
-                                    ]);
-                                    self.update_catalog_view(
-                                        MZ_AVRO_OCF_SINKS.id,
-                                        iter::once((row, -1)),
-                                    )
-                                    .await;
-                                }
-                                _ => (),
-                            }
+                            self.report_sink_connector_update(entry.id(), connector, -1)
+                                .await;
                         }
                         CatalogItem::Sink(catalog::Sink {
                             connector: SinkConnectorState::Pending(_),
@@ -2673,6 +4082,16 @@ where
                             )
                             .await;
                         }
+                        CatalogItem::Func(_) => {
+                            self.report_function_update(
+                                entry.id(),
+                                entry.oid(),
+                                *schema_id,
+                                &entry.name().item,
+                                -1,
+                            )
+                            .await;
+                        }
                         CatalogItem::Index(_) => {
                             unreachable!("dropped indexes should be handled by DroppedIndex");
                         }
@@ -2681,6 +4100,19 @@ where
                         self.report_column_updates(desc, entry.id(), -1).await?;
                     }
                 }
+                catalog::Event::UpdatedComment {
+                    id,
+                    column,
+                    from,
+                    to,
+                } => {
+                    if let Some(from) = from {
+                        self.report_comment_update(*id, *column, from, -1).await;
+                    }
+                    if let Some(to) = to {
+                        self.report_comment_update(*id, *column, to, 1).await;
+                    }
+                }
                 _ => (),
             }
         }
@@ -2730,51 +4162,68 @@ where
         }
     }
 
-    /// Prepares a relation expression for execution by preparing all contained
-    /// scalar expressions (see `prep_scalar_expr`), then optimizing the
-    /// relation expression.
+    /// Prepares a relation expression for execution with the coordinator's
+    /// optimizer. See the free function `prep_relation_expr`.
     fn prep_relation_expr(
         &mut self,
-        mut expr: RelationExpr,
+        expr: RelationExpr,
         style: ExprPrepStyle,
     ) -> Result<OptimizedRelationExpr, anyhow::Error> {
-        expr.try_visit_scalars_mut(&mut |s| Self::prep_scalar_expr(s, style))?;
-
-        // TODO (wangandi): Is there anything that optimizes to a
-        // constant expression that originally contains a global get? Is
-        // there anything not containing a global get that cannot be
-        // optimized to a constant expression?
-        Ok(self.optimizer.optimize(expr, self.catalog.indexes())?)
+        prep_relation_expr(&mut self.optimizer, self.catalog.indexes(), expr, style)
     }
 
-    /// Prepares a scalar expression for execution by replacing any placeholders
-    /// with their correct values.
-    ///
-    /// Specifically, calls to the special function `MzLogicalTimestamp` are
-    /// replaced according to `style`:
+    /// Like [`Coordinator::prep_relation_expr`], but for the cached plan of a
+    /// prepared statement, whose optimized form is reused if it exists.
     ///
-    ///   * if `OneShot`, calls are replaced according to the logical time
-    ///     specified in the `OneShot` variant.
-    ///   * if `Explain`, calls are replaced with a dummy time.
-    ///   * if `Static`, calls trigger an error indicating that static queries
-    ///     are not permitted to observe their own timestamps.
-    fn prep_scalar_expr(expr: &mut ScalarExpr, style: ExprPrepStyle) -> Result<(), anyhow::Error> {
-        // Replace calls to `MzLogicalTimestamp` as described above.
-        let ts = match style {
-            ExprPrepStyle::Explain | ExprPrepStyle::Static => 0, // dummy timestamp
-            ExprPrepStyle::OneShot { logical_time } => logical_time,
+    /// The plan is optimized with placeholders for the statement's parameters,
+    /// which are replaced with the values in `params` afterwards.
+    fn prep_cached_relation_expr(
+        &mut self,
+        cached_plan: &mut CachedPlan,
+        style: ExprPrepStyle,
+        params: &Params,
+    ) -> Result<OptimizedRelationExpr, anyhow::Error> {
+        let mut expr = match &cached_plan.optimized_source {
+            Some(optimized_source) => {
+                PLAN_CACHE_HITS.inc();
+                optimized_source.clone()
+            }
+            None => {
+                PLAN_CACHE_MISSES.inc();
+                let optimized_source = self
+                    .optimizer
+                    .optimize(cached_plan.source.clone(), self.catalog.indexes())?;
+                cached_plan.optimized_source = Some(optimized_source.clone());
+                optimized_source
+            }
         };
-        let mut observes_ts = false;
+        expr.as_mut().try_visit_scalars_mut(&mut |s| {
+            prep_scalar_expr(s, style)?;
+            Self::bind_parameters(s, params)
+        })?;
+        // Constant folding skips expressions with placeholders, so it must run
+        // again now that the placeholders have been replaced.
+        expr.as_mut()
+            .try_visit_mut(&mut |e| FoldConstants.action(e))?;
+        Ok(expr)
+    }
+
+    /// Replaces the placeholders for parameters in a scalar expression with
+    /// the corresponding values in `params`.
+    fn bind_parameters(expr: &mut ScalarExpr, params: &Params) -> Result<(), anyhow::Error> {
+        let mut result = Ok(());
         expr.visit_mut(&mut |e| {
-            if let ScalarExpr::CallNullary(f @ NullaryFunc::MzLogicalTimestamp) = e {
-                observes_ts = true;
-                *e = ScalarExpr::literal_ok(Datum::from(i128::from(ts)), f.output_type());
+            if let ScalarExpr::CallNullary(NullaryFunc::Parameter(n, scalar_type)) = e {
+                match params.datums.iter().nth(*n - 1) {
+                    Some(datum) => {
+                        let typ = scalar_type.clone().nullable(datum.is_null());
+                        *e = ScalarExpr::literal_ok(datum, typ);
+                    }
+                    None => result = Err(anyhow!("there is no parameter ${}", n)),
+                }
             }
         });
-        if observes_ts && matches!(style, ExprPrepStyle::Static) {
-            bail!("mz_logical_timestamp cannot be used in static queries");
-        }
-        Ok(())
+        result
     }
 
     /// Imports the view, source, or table with `id` into the provided
@@ -2909,19 +4358,74 @@ where
 
     /// Builds a dataflow description for the sink with the specified name,
     /// ID, source, and output connector.
+    ///
+    /// A sink that reads from a query builds the query as a transient view
+    /// within the sink's dataflow.
     fn build_sink_dataflow(
-        &self,
+        &mut self,
         name: String,
         id: GlobalId,
-        from: GlobalId,
+        from: &catalog::SinkFrom,
         connector: SinkConnector,
-    ) -> DataflowDesc {
+    ) -> Result<DataflowDesc, anyhow::Error> {
         let mut dataflow = DataflowDesc::new(name);
         dataflow.set_as_of(connector.get_frontier());
-        self.import_into_dataflow(&from, &mut dataflow);
-        let from_type = self.catalog.get_by_id(&from).desc().unwrap().clone();
-        dataflow.add_sink_export(id, from, from_type, connector);
-        dataflow
+        let (from_id, from_desc) = match from {
+            catalog::SinkFrom::Id(from_id) => {
+                self.import_into_dataflow(from_id, &mut dataflow);
+                let from_desc = self.catalog.get_by_id(from_id).desc().unwrap().clone();
+                (*from_id, from_desc)
+            }
+            catalog::SinkFrom::Query {
+                optimized_expr,
+                desc,
+            } => {
+                let view_id = self.allocate_transient_id()?;
+                self.import_view_into_dataflow(&view_id, optimized_expr, &mut dataflow);
+                (view_id, desc.clone())
+            }
+        };
+        dataflow.add_sink_export(id, from_id, from_desc, connector);
+        Ok(dataflow)
+    }
+
+    /// Prepares the relation that a sink or tail reads from for installation
+    /// in a dataflow.
+    fn prep_sink_from(
+        &mut self,
+        from: sql::plan::SinkFrom,
+    ) -> Result<catalog::SinkFrom, anyhow::Error> {
+        Ok(match from {
+            sql::plan::SinkFrom::Id(id) => catalog::SinkFrom::Id(id),
+            sql::plan::SinkFrom::Query { expr, desc } => catalog::SinkFrom::Query {
+                optimized_expr: self.prep_relation_expr(expr, ExprPrepStyle::Static)?,
+                desc,
+            },
+        })
+    }
+
+    /// Ensures that the key of an upsert sink or tail uniquely identifies the
+    /// rows of its input. Only one row per key is emitted at each timestamp,
+    /// so rows that share a key would otherwise be silently dropped.
+    fn validate_upsert_key(
+        &self,
+        from: &catalog::SinkFrom,
+        envelope: SinkEnvelope,
+        key_indices: &Option<Vec<usize>>,
+    ) -> Result<(), anyhow::Error> {
+        if let (SinkEnvelope::Upsert, Some(key_indices)) = (envelope, key_indices) {
+            let keys = self.catalog.sink_from_keys(from);
+            if !keys
+                .iter()
+                .any(|key| key.iter().all(|k| key_indices.contains(k)))
+            {
+                bail!(
+                    "ENVELOPE UPSERT requires a KEY that uniquely identifies the rows of \
+                     its input, but the KEY columns are not known to be unique"
+                );
+            }
+        }
+        Ok(())
     }
 
     /// Finalizes a dataflow and then broadcasts it to all workers.
@@ -2963,25 +4467,8 @@ where
         }
 
         for (id, sink) in &dataflow.sink_exports {
-            match &sink.connector {
-                SinkConnector::Kafka(KafkaSinkConnector { topic, .. }) => {
-                    let row = Row::pack(&[
-                        Datum::String(&id.to_string()),
-                        Datum::String(topic.as_str()),
-                    ]);
-                    self.update_catalog_view(MZ_KAFKA_SINKS.id, iter::once((row, 1)))
-                        .await;
-                }
-                SinkConnector::AvroOcf(AvroOcfSinkConnector { path, .. }) => {
-                    let row = Row::pack(&[
-                        Datum::String(&id.to_string()),
-                        Datum::Bytes(&path.clone().into_os_string().into_vec()),
-                    ]);
-                    self.update_catalog_view(MZ_AVRO_OCF_SINKS.id, iter::once((row, 1)))
-                        .await;
-                }
-                _ => (),
-            }
+            self.report_sink_connector_update(*id, &sink.connector, 1)
+                .await;
         }
 
         // TODO: Produce "valid from" information for each sink.
@@ -3153,6 +4640,10 @@ where
             indexes: ArrangementFrontiers::default(),
             since_updates: Vec::new(),
             active_tails: HashMap::new(),
+            cancel_txs: HashMap::new(),
+            txn_reads: HashMap::new(),
+            table_versions: HashMap::new(),
+            altering_tables: HashSet::new(),
             logging_granularity: logging.and_then(|c| c.granularity.as_millis().try_into().ok()),
             timestamp_config,
             logical_compaction_window_ms: logical_compaction_window
@@ -3193,7 +4684,7 @@ where
 
 /// The styles in which an expression can be prepared.
 #[derive(Clone, Copy, Debug)]
-enum ExprPrepStyle {
+pub(crate) enum ExprPrepStyle {
     /// The expression is being prepared for output as part of an `EXPLAIN`
     /// query.
     Explain,
@@ -3205,6 +4696,57 @@ enum ExprPrepStyle {
     OneShot { logical_time: u64 },
 }
 
+/// Prepares a relation expression for execution by preparing all contained
+/// scalar expressions (see `prep_scalar_expr`), then optimizing the relation
+/// expression.
+///
+/// The catalog uses this to prepare the expressions of items that it loads, so
+/// that they match the expressions of the items when they were created.
+pub(crate) fn prep_relation_expr(
+    optimizer: &mut Optimizer,
+    indexes: &HashMap<GlobalId, Vec<(GlobalId, Vec<ScalarExpr>)>>,
+    mut expr: RelationExpr,
+    style: ExprPrepStyle,
+) -> Result<OptimizedRelationExpr, anyhow::Error> {
+    expr.try_visit_scalars_mut(&mut |s| prep_scalar_expr(s, style))?;
+
+    // TODO (wangandi): Is there anything that optimizes to a
+    // constant expression that originally contains a global get? Is
+    // there anything not containing a global get that cannot be
+    // optimized to a constant expression?
+    Ok(optimizer.optimize(expr, indexes)?)
+}
+
+/// Prepares a scalar expression for execution by replacing any placeholders
+/// with their correct values.
+///
+/// Specifically, calls to the special function `MzLogicalTimestamp` are
+/// replaced according to `style`:
+///
+///   * if `OneShot`, calls are replaced according to the logical time
+///     specified in the `OneShot` variant.
+///   * if `Explain`, calls are replaced with a dummy time.
+///   * if `Static`, calls trigger an error indicating that static queries
+///     are not permitted to observe their own timestamps.
+fn prep_scalar_expr(expr: &mut ScalarExpr, style: ExprPrepStyle) -> Result<(), anyhow::Error> {
+    // Replace calls to `MzLogicalTimestamp` as described above.
+    let ts = match style {
+        ExprPrepStyle::Explain | ExprPrepStyle::Static => 0, // dummy timestamp
+        ExprPrepStyle::OneShot { logical_time } => logical_time,
+    };
+    let mut observes_ts = false;
+    expr.visit_mut(&mut |e| {
+        if let ScalarExpr::CallNullary(f @ NullaryFunc::MzLogicalTimestamp) = e {
+            observes_ts = true;
+            *e = ScalarExpr::literal_ok(Datum::from(i128::from(ts)), f.output_type());
+        }
+    });
+    if observes_ts && matches!(style, ExprPrepStyle::Static) {
+        bail!("mz_logical_timestamp cannot be used in static queries");
+    }
+    Ok(())
+}
+
 async fn broadcast(tx: &mut comm::broadcast::Sender<SequencedCommand>, cmd: SequencedCommand) {
     // TODO(benesch): avoid flushing after every send.
     tx.send(cmd).await.unwrap();
@@ -109,6 +109,11 @@ pub struct Frontiers<T: Timestamp> {
     /// This timestamp drives the advancement of the since frontier as a
     /// function of the upper frontier, trailing it by exactly this much.
     pub compaction_window_ms: Option<T>,
+    /// The timestamps at which open transactions are reading.
+    ///
+    /// The since frontier must not advance beyond any of these times, or the
+    /// transactions would no longer be able to read at them.
+    pub read_holds: MutableAntichain<T>,
 }
 
 impl<T: Timestamp> Frontiers<T> {
@@ -120,6 +125,7 @@ impl<T: Timestamp> Frontiers<T> {
             upper,
             since: Antichain::from_elem(T::minimum()),
             compaction_window_ms,
+            read_holds: MutableAntichain::new(),
         }
     }
 
@@ -33,7 +33,7 @@ pub mod session;
 
 pub use crate::catalog::dump as dump_catalog;
 pub use crate::client::{Client, SessionClient};
-pub use crate::command::{ExecuteResponse, NoSessionExecuteResponse, StartupMessage};
+pub use crate::command::{ExecuteResponse, NoSessionExecuteResponse, RowsFuture, StartupMessage};
 pub use crate::coord::{serve, Config, LoggingConfig};
 pub use crate::persistence::PersistenceConfig;
 pub use crate::timestamp::TimestampConfig;
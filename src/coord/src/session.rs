@@ -15,15 +15,17 @@ use std::collections::HashMap;
 
 use anyhow::bail;
 use derivative::Derivative;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use futures::Stream;
 
+use expr::{GlobalId, OptimizedRelationExpr, RelationExpr, RowSetFinishing};
 use repr::{Datum, Row, ScalarType};
 use sql::ast::Statement;
 use sql::plan::{Params, StatementDesc};
 
 mod vars;
 
-pub use self::vars::Vars;
+pub use self::vars::{IsolationLevel, Vars};
 
 const DUMMY_CONNECTION_ID: u32 = 0;
 
@@ -34,7 +36,10 @@ pub struct Session {
     prepared_statements: HashMap<String, PreparedStatement>,
     portals: HashMap<String, Portal>,
     transaction: TransactionStatus,
+    transaction_writes: Vec<WriteOp>,
     vars: Vars,
+    cancel_tx: UnboundedSender<()>,
+    cancel_rx: UnboundedReceiver<()>,
 }
 
 impl Session {
@@ -53,12 +58,16 @@ impl Session {
     }
 
     fn new_internal(conn_id: u32) -> Session {
+        let (cancel_tx, cancel_rx) = mpsc::unbounded();
         Session {
             conn_id,
             transaction: TransactionStatus::Idle,
+            transaction_writes: vec![],
             prepared_statements: HashMap::new(),
             portals: HashMap::new(),
             vars: Vars::default(),
+            cancel_tx,
+            cancel_rx,
         }
     }
 
@@ -67,14 +76,34 @@ impl Session {
         self.conn_id
     }
 
+    /// Returns a transmitter that cancels the statement, if any, that is
+    /// waiting on [`Session::cancel_rx`].
+    pub fn cancel_tx(&self) -> UnboundedSender<()> {
+        self.cancel_tx.clone()
+    }
+
+    /// Returns the receiver of cancellation requests for statements that wait
+    /// for results outside of the coordinator, like a `FETCH` that waits for
+    /// a `TAIL` to produce rows.
+    ///
+    /// Requests that arrive while no such statement is waiting are left in the
+    /// receiver, and should be drained before waiting.
+    pub fn cancel_rx(&mut self) -> &mut UnboundedReceiver<()> {
+        &mut self.cancel_rx
+    }
+
     /// Starts a transaction.
     pub fn start_transaction(&mut self) {
         self.transaction = TransactionStatus::InTransaction;
     }
 
     /// Ends a transaction.
+    ///
+    /// Any writes buffered by the transaction that have not been taken with
+    /// [`Session::take_transaction_writes`] are discarded.
     pub fn end_transaction(&mut self) {
         self.transaction = TransactionStatus::Idle;
+        self.transaction_writes.clear();
     }
 
     /// Marks the current transaction as failed.
@@ -91,6 +120,17 @@ impl Session {
         &self.transaction
     }
 
+    /// Buffers a write until the current transaction commits.
+    pub fn add_transaction_write(&mut self, write: WriteOp) {
+        self.transaction_writes.push(write);
+    }
+
+    /// Takes the writes buffered by the current transaction, in the order in
+    /// which they were made.
+    pub fn take_transaction_writes(&mut self) -> Vec<WriteOp> {
+        std::mem::take(&mut self.transaction_writes)
+    }
+
     /// Registers the prepared statement under `name`.
     pub fn set_prepared_statement(&mut self, name: String, statement: PreparedStatement) {
         self.prepared_statements.insert(name, statement);
@@ -108,6 +148,12 @@ impl Session {
         self.prepared_statements.get(name)
     }
 
+    /// Retrieves a mutable reference to the prepared statement associated
+    /// with `name`.
+    pub fn get_prepared_statement_mut(&mut self, name: &str) -> Option<&mut PreparedStatement> {
+        self.prepared_statements.get_mut(name)
+    }
+
     /// Binds the specified portal to the specified prepared statement.
     ///
     /// If the prepared statement contains parameters, the values and types of
@@ -127,30 +173,61 @@ impl Session {
         params: Vec<(Datum, ScalarType)>,
         result_formats: Vec<pgrepr::Format>,
     ) -> Result<(), anyhow::Error> {
-        if !self.prepared_statements.contains_key(&statement_name) {
-            bail!(
+        let statement = match self.prepared_statements.get(&statement_name) {
+            Some(statement) => statement,
+            None => bail!(
                 "statement does not exist for portal creation: \
                  statement={:?} portal={:?}",
                 statement_name,
                 portal_name
-            );
-        }
+            ),
+        };
 
         self.portals.insert(
             portal_name,
             Portal {
-                statement_name,
+                stmt: statement.sql().cloned(),
+                statement_name: Some(statement_name),
+                desc: statement.desc().clone(),
                 parameters: Params {
                     datums: Row::pack(params.iter().map(|(d, _t)| d)),
                     types: params.into_iter().map(|(_d, t)| t).collect(),
                 },
                 result_formats: result_formats.into_iter().map(Into::into).collect(),
+                cursor: false,
                 remaining_rows: None,
             },
         );
         Ok(())
     }
 
+    /// Declares a cursor named `name` that executes `stmt`.
+    ///
+    /// A cursor is a portal that is created by a `DECLARE` statement rather
+    /// than by the extended query protocol. Its results are delivered by
+    /// subsequent `FETCH` statements, so its result formats are immaterial.
+    pub fn declare_cursor(
+        &mut self,
+        name: String,
+        stmt: Statement,
+        desc: StatementDesc,
+        parameters: Params,
+    ) {
+        let result_formats = vec![pgrepr::Format::Text; desc.arity()];
+        self.portals.insert(
+            name,
+            Portal {
+                stmt: Some(stmt),
+                statement_name: None,
+                desc,
+                parameters,
+                result_formats,
+                cursor: true,
+                remaining_rows: None,
+            },
+        );
+    }
+
     /// Removes the specified portal.
     ///
     /// If there is no such portal, this method does nothing.
@@ -177,7 +254,7 @@ impl Session {
         self.end_transaction();
         self.prepared_statements.clear();
         self.portals.clear();
-        self.vars = Vars::default();
+        self.vars.reset();
     }
 
     /// Returns a reference to the variables in this session.
@@ -196,12 +273,17 @@ impl Session {
 pub struct PreparedStatement {
     sql: Option<Statement>,
     desc: StatementDesc,
+    plan_cache: PlanCache,
 }
 
 impl PreparedStatement {
     /// Constructs a new prepared statement.
     pub fn new(sql: Option<Statement>, desc: StatementDesc) -> PreparedStatement {
-        PreparedStatement { sql, desc }
+        PreparedStatement {
+            sql,
+            desc,
+            plan_cache: PlanCache::default(),
+        }
     }
 
     /// Returns the raw SQL string associated with this prepared statement,
@@ -214,18 +296,117 @@ impl PreparedStatement {
     pub fn desc(&self) -> &StatementDesc {
         &self.desc
     }
+
+    /// Returns the cache of the prepared statement's plan.
+    pub fn plan_cache(&self) -> &PlanCache {
+        &self.plan_cache
+    }
+
+    /// Returns a mutable reference to the cache of the prepared statement's
+    /// plan.
+    pub fn plan_cache_mut(&mut self) -> &mut PlanCache {
+        &mut self.plan_cache
+    }
+}
+
+/// The cached plan of a prepared statement.
+///
+/// The plan is cached before the statement's parameters are bound, so
+/// executing the statement again, even with different parameters, needs
+/// neither planning nor optimization. Plans depend on the catalog, like on
+/// which objects and indexes exist, and on the session's database, so the
+/// cache is keyed by both.
+#[derive(Debug, Default)]
+pub struct PlanCache {
+    entry: Option<PlanCacheEntry>,
+}
+
+#[derive(Debug)]
+struct PlanCacheEntry {
+    catalog_revision: u64,
+    database: String,
+    plan: Option<CachedPlan>,
+}
+
+/// The plan of a `SELECT` statement whose parameters are placeholders, as
+/// produced by [`sql::plan::plan_reusable`].
+#[derive(Debug)]
+pub struct CachedPlan {
+    /// The query to execute.
+    pub source: RelationExpr,
+    /// How to finish the results of the query.
+    pub finishing: RowSetFinishing,
+    /// The optimized form of `source`, once it has been optimized.
+    pub optimized_source: Option<OptimizedRelationExpr>,
+}
+
+impl PlanCache {
+    /// Reports whether the cache was filled at `catalog_revision` for a
+    /// session whose database is `database`.
+    pub fn is_current(&self, catalog_revision: u64, database: &str) -> bool {
+        match &self.entry {
+            Some(entry) => entry.catalog_revision == catalog_revision && entry.database == database,
+            None => false,
+        }
+    }
+
+    /// Returns the cached plan, if the statement's plan can be reused.
+    pub fn plan_mut(&mut self) -> Option<&mut CachedPlan> {
+        self.entry.as_mut().and_then(|entry| entry.plan.as_mut())
+    }
+
+    /// Caches `plan` as the plan at `catalog_revision` for a session whose
+    /// database is `database`, replacing any previously cached plan. A `plan`
+    /// of `None` records that the statement's plan cannot be reused.
+    pub fn insert(&mut self, catalog_revision: u64, database: String, plan: Option<CachedPlan>) {
+        self.entry = Some(PlanCacheEntry {
+            catalog_revision,
+            database,
+            plan,
+        });
+    }
+}
+
+/// A write to a table, buffered by a transaction until it commits.
+#[derive(Debug)]
+pub struct WriteOp {
+    /// The table to write to.
+    pub id: GlobalId,
+    /// The version of the table when the write was buffered. The write fails
+    /// if the table has been altered since.
+    pub version: u64,
+    /// The data to write.
+    pub data: WriteData,
+}
+
+/// The data of a [`WriteOp`].
+#[derive(Debug)]
+pub enum WriteData {
+    /// The rows produced by the `VALUES` of an `INSERT`, which are evaluated
+    /// at the timestamp at which the transaction commits.
+    Values(RelationExpr),
+    /// Rows to insert, or to retract if their diff is negative.
+    Updates(Vec<(Row, isize)>),
 }
 
 /// A portal represents the execution state of a running or runnable query.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Portal {
-    /// The name of the prepared statement that is bound to this portal.
-    pub statement_name: String,
+    /// The statement that is bound to this portal.
+    pub stmt: Option<Statement>,
+    /// The name of the prepared statement that is bound to this portal, if
+    /// the portal was bound from a prepared statement.
+    pub statement_name: Option<String>,
+    /// The description of the statement that is bound to this portal.
+    pub desc: StatementDesc,
     /// The bound values for the parameters in the prepared statement, if any.
     pub parameters: Params,
     /// The desired output format for each column in the result set.
     pub result_formats: Vec<pgrepr::Format>,
+    /// Whether the portal is a cursor, which outlives the statements that
+    /// fetch from it.
+    pub cursor: bool,
     /// The rows that have yet to be delivered to the client, if the portal is
     /// partially executed.
     #[derivative(Debug = "ignore")]
@@ -239,8 +420,9 @@ impl Portal {
     }
 }
 
-/// A stream of batched rows.
-pub type RowBatchStream = Box<dyn Stream<Item = Result<Vec<Row>, comm::Error>> + Send + Unpin>;
+/// A stream of batched rows, which ends early with an error if the statement
+/// producing them fails.
+pub type RowBatchStream = Box<dyn Stream<Item = Result<Vec<Row>, String>> + Send + Unpin>;
 
 /// The transaction status of a session.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,6 +8,7 @@
 // by the Apache License, Version 2.0.
 
 use std::borrow::Borrow;
+use std::time::Duration;
 
 use anyhow::bail;
 
@@ -77,6 +78,13 @@ const STANDARD_CONFORMING_STRINGS: ServerVar<bool> = ServerVar {
     description: "Causes '...' strings to treat backslashes literally (PostgreSQL).",
 };
 
+const STATEMENT_TIMEOUT: ServerVar<Duration> = ServerVar {
+    name: unicase::Ascii::new("statement_timeout"),
+    value: &Duration::from_secs(0),
+    description:
+        "Sets the maximum allowed duration that a query waits for its results (PostgreSQL).",
+};
+
 const TIMEZONE: ServerVar<str> = ServerVar {
     // TimeZone has nonstandard capitalization for historical reasons.
     name: unicase::Ascii::new("TimeZone"),
@@ -123,8 +131,9 @@ pub struct Vars {
     server_version: ServerVar<str>,
     sql_safe_updates: SessionVar<bool>,
     standard_conforming_strings: ServerVar<bool>,
+    statement_timeout: SessionVar<Duration>,
     timezone: ServerVar<str>,
-    transaction_isolation: ServerVar<str>,
+    transaction_isolation: SessionVar<str>,
 }
 
 impl Default for Vars {
@@ -140,8 +149,9 @@ impl Default for Vars {
             server_version: SERVER_VERSION,
             sql_safe_updates: SessionVar::new(&SQL_SAFE_UPDATES),
             standard_conforming_strings: STANDARD_CONFORMING_STRINGS,
+            statement_timeout: SessionVar::new(&STATEMENT_TIMEOUT),
             timezone: TIMEZONE,
-            transaction_isolation: TRANSACTION_ISOLATION,
+            transaction_isolation: SessionVar::new(&TRANSACTION_ISOLATION),
         }
     }
 }
@@ -161,6 +171,7 @@ impl Vars {
             &self.server_version,
             &self.sql_safe_updates,
             &self.standard_conforming_strings,
+            &self.statement_timeout,
             &self.timezone,
             &self.transaction_isolation,
         ]
@@ -213,6 +224,8 @@ impl Vars {
             Ok(&self.sql_safe_updates)
         } else if name == STANDARD_CONFORMING_STRINGS.name {
             Ok(&self.standard_conforming_strings)
+        } else if name == STATEMENT_TIMEOUT.name {
+            Ok(&self.statement_timeout)
         } else if name == TIMEZONE.name {
             Ok(&self.timezone)
         } else if name == TRANSACTION_ISOLATION.name {
@@ -253,6 +266,8 @@ impl Vars {
                 "parameter {} is read only",
                 STANDARD_CONFORMING_STRINGS.name
             );
+        } else if name == STATEMENT_TIMEOUT.name {
+            self.statement_timeout.set(value)
         } else if name == TIMEZONE.name {
             if unicase::Ascii::new(value) != TIMEZONE.value {
                 bail!(
@@ -264,7 +279,15 @@ impl Vars {
                 Ok(())
             }
         } else if name == TRANSACTION_ISOLATION.name {
-            bail!("parameter {} is read only", TRANSACTION_ISOLATION.name);
+            match IsolationLevel::parse(value) {
+                Some(level) => self.transaction_isolation.set(level.as_str()),
+                None => bail!(
+                    "parameter {} can only be set to {} or {}",
+                    TRANSACTION_ISOLATION.name,
+                    IsolationLevel::Serializable.as_str(),
+                    IsolationLevel::StrictSerializable.as_str(),
+                ),
+            }
         } else {
             bail!("unknown parameter: {}", name)
         }
@@ -321,6 +344,32 @@ impl Vars {
         *self.standard_conforming_strings.value
     }
 
+    /// Returns the value of the `statement_timeout` configuration parameter.
+    ///
+    /// The timeout bounds how long a query waits for its results. A zero
+    /// duration means that queries may wait indefinitely.
+    pub fn statement_timeout(&self) -> Duration {
+        *self.statement_timeout.value()
+    }
+
+    /// Sets the default of the `statement_timeout` configuration parameter
+    /// that is configured for the server. The default applies whenever the
+    /// parameter is not set in the session, including after [`Vars::reset`].
+    pub fn set_statement_timeout_default(&mut self, timeout: Duration) {
+        self.statement_timeout.default = Some(timeout);
+    }
+
+    /// Resets every configuration parameter that was set in the session to its
+    /// default.
+    pub fn reset(&mut self) {
+        self.application_name.reset();
+        self.database.reset();
+        self.extra_float_digits.reset();
+        self.sql_safe_updates.reset();
+        self.statement_timeout.reset();
+        self.transaction_isolation.reset();
+    }
+
     /// Returns the value of the `timezone` configuration parameter.
     pub fn timezone(&self) -> &'static str {
         self.timezone.value
@@ -328,8 +377,44 @@ impl Vars {
 
     /// Returns the value of the `transaction_isolation` configuration
     /// parameter.
-    pub fn transaction_isolation(&self) -> &'static str {
-        self.transaction_isolation.value
+    pub fn transaction_isolation(&self) -> IsolationLevel {
+        IsolationLevel::parse(self.transaction_isolation.value())
+            .expect("transaction_isolation is validated when set")
+    }
+}
+
+/// The isolation levels that the `transaction_isolation` configuration
+/// parameter can be set to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Reads observe a consistent snapshot of their inputs, but the snapshot
+    /// chosen for a read may be older than one already observed by an earlier
+    /// read.
+    Serializable,
+    /// Like `Serializable`, but reads additionally never observe a snapshot
+    /// older than one observed by an earlier read or write, in any session.
+    StrictSerializable,
+}
+
+impl IsolationLevel {
+    fn parse(s: &str) -> Option<IsolationLevel> {
+        let s = unicase::Ascii::new(s);
+        if s == "serializable" {
+            Some(IsolationLevel::Serializable)
+        } else if s == "strict serializable" {
+            Some(IsolationLevel::StrictSerializable)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the isolation level, as reported by
+    /// `SHOW TRANSACTION ISOLATION LEVEL`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IsolationLevel::Serializable => "serializable",
+            IsolationLevel::StrictSerializable => "strict serializable",
+        }
     }
 }
 
@@ -376,13 +461,15 @@ where
 }
 
 /// A `SessionVar` is the session value for a configuration parameter. If unset,
-/// the server default is used instead.
+/// the server default is used instead, which is the default configured when
+/// the server was started, if any, or else the parent's value.
 #[derive(Debug)]
 pub struct SessionVar<V>
 where
     V: Value + ?Sized + 'static,
 {
     value: Option<V::Owned>,
+    default: Option<V::Owned>,
     parent: &'static ServerVar<V>,
 }
 
@@ -393,6 +480,7 @@ where
     pub fn new(parent: &'static ServerVar<V>) -> SessionVar<V> {
         SessionVar {
             value: None,
+            default: None,
             parent,
         }
     }
@@ -411,9 +499,14 @@ where
         }
     }
 
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+
     pub fn value(&self) -> &V {
         self.value
             .as_ref()
+            .or_else(|| self.default.as_ref())
             .map(|v| v.borrow())
             .unwrap_or(self.parent.value)
     }
@@ -477,6 +570,29 @@ impl Value for i32 {
     }
 }
 
+impl Value for Duration {
+    const TYPE_NAME: &'static str = "duration";
+
+    fn parse(s: &str) -> Result<Duration, ()> {
+        // As in PostgreSQL, a number without units is in milliseconds.
+        match s.parse() {
+            Ok(ms) => Ok(Duration::from_millis(ms)),
+            Err(_) => parse_duration::parse(s).map_err(|_| ()),
+        }
+    }
+
+    fn format(&self) -> String {
+        let ms = self.as_millis();
+        if ms == 0 {
+            "0".into()
+        } else if ms % 1000 == 0 {
+            format!("{}s", ms / 1000)
+        } else {
+            format!("{}ms", ms)
+        }
+    }
+}
+
 impl Value for str {
     const TYPE_NAME: &'static str = "string";
 
@@ -7,19 +7,27 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::cmp;
 use std::fs::OpenOptions;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, Context};
+use log::info;
 use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
 use rdkafka::client::DefaultClientContext;
 use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::RDKafkaError;
+use rdkafka::message::Message;
+use rdkafka::topic_partition_list::{Offset, TopicPartitionList};
 
 use dataflow_types::{
     AvroOcfSinkConnector, AvroOcfSinkConnectorBuilder, KafkaSinkConnector,
-    KafkaSinkConnectorBuilder, KafkaSinkConsistencyConnector, SinkConnector, SinkConnectorBuilder,
+    KafkaSinkConnectorBuilder, KafkaSinkConsistencyConnector, KafkaSinkFormat,
+    KafkaSinkFormatBuilder, SinkConnector, SinkConnectorBuilder, SinkEnvelope,
 };
 use expr::GlobalId;
+use interchange::avro;
 use ore::collections::CollectionExt;
 use repr::Timestamp;
 use timely::progress::Antichain;
@@ -36,21 +44,20 @@ pub async fn build(
     }
 }
 
-async fn register_kafka_topic(
+async fn create_kafka_topic(
     client: &AdminClient<DefaultClientContext>,
     topic: &str,
     replication_factor: i32,
-    ccsr: &ccsr::Client,
-    value_schema: &str,
-    key_schema: Option<&str>,
-) -> Result<(Option<i32>, i32), anyhow::Error> {
+    allow_existing: bool,
+    compact: bool,
+) -> Result<(), anyhow::Error> {
+    let mut new_topic = NewTopic::new(&topic, 1, TopicReplication::Fixed(replication_factor));
+    if compact {
+        new_topic = new_topic.set("cleanup.policy", "compact");
+    }
     let res = client
         .create_topics(
-            &[NewTopic::new(
-                &topic,
-                1,
-                TopicReplication::Fixed(replication_factor),
-            )],
+            &[new_topic],
             &AdminOptions::new().request_timeout(Some(Duration::from_secs(5))),
         )
         .await
@@ -63,9 +70,19 @@ async fn register_kafka_topic(
             res.len()
         );
     }
-    res.into_element()
-        .map_err(|(_, e)| anyhow!("error creating topic {} for sink: {}", topic, e))?;
+    match res.into_element() {
+        Ok(_) => Ok(()),
+        Err((_, RDKafkaError::TopicAlreadyExists)) if allow_existing => Ok(()),
+        Err((_, e)) => bail!("error creating topic {} for sink: {}", topic, e),
+    }
+}
 
+async fn publish_kafka_schemas(
+    ccsr: &ccsr::Client,
+    topic: &str,
+    value_schema: &str,
+    key_schema: Option<&str>,
+) -> Result<(Option<i32>, i32), anyhow::Error> {
     // Publish value schema for the topic.
     //
     // TODO(benesch): do we need to delete the Kafka topic if publishing the
@@ -94,7 +111,22 @@ async fn build_kafka(
     frontier: Antichain<Timestamp>,
     id: GlobalId,
 ) -> Result<SinkConnector, anyhow::Error> {
-    let topic = format!("{}-{}-{}", builder.topic_prefix, id, builder.topic_suffix);
+    // Exactly-once sinks must write to the same topic across restarts, so that
+    // they can pick up where the previous incarnation of the sink left off.
+    // The sink's global ID is stable across restarts, so unless the user asked
+    // to reuse the topic name verbatim, it suffices to omit the per-process
+    // suffix.
+    //
+    // Resuming is only correct if each timestamp was written atomically, so
+    // reusing a topic implies exactly-once writes.
+    let exactly_once = builder.exactly_once || builder.reuse_topic;
+    let topic = if builder.reuse_topic {
+        builder.topic_prefix.clone()
+    } else if builder.exactly_once {
+        format!("{}-{}", builder.topic_prefix, id)
+    } else {
+        format!("{}-{}-{}", builder.topic_prefix, id, builder.topic_suffix)
+    };
 
     // Create Kafka topic with single partition.
     let mut config = ClientConfig::new();
@@ -105,54 +137,171 @@ async fn build_kafka(
     let client = config
         .create::<AdminClient<_>>()
         .expect("creating admin client failed");
-    let ccsr = builder.ccsr_config.build();
-
-    let (key_schema_id, value_schema_id) = register_kafka_topic(
-        &client,
-        &topic,
-        builder.replication_factor as i32,
-        &ccsr,
-        &builder.value_schema,
-        builder.key_schema.as_deref(),
-    )
-    .await
-    .context("error registering kafka topic for sink")?;
-
-    let consistency = if let Some(consistency_value_schema) = builder.consistency_value_schema {
-        let consistency_topic = format!("{}-consistency", topic);
-        let (_, consistency_schema_id) = register_kafka_topic(
-            &client,
-            &consistency_topic,
-            builder.replication_factor as i32,
-            &ccsr,
-            &consistency_value_schema,
-            None,
-        )
+    let replication_factor = builder.replication_factor as i32;
+
+    // Upsert sinks only need the latest value for each key, so their topics
+    // can be log-compacted.
+    let compact = builder.envelope == SinkEnvelope::Upsert;
+    create_kafka_topic(&client, &topic, replication_factor, exactly_once, compact)
         .await
-        .context("error registering kafka consistency topic for sink")?;
+        .context("error registering kafka topic for sink")?;
 
-        Some(KafkaSinkConsistencyConnector {
-            topic: consistency_topic,
-            schema_id: consistency_schema_id,
-        })
-    } else {
-        None
+    // Only Avro sinks publish schemas, and the consistency topic, which is
+    // always Avro-encoded, is only permitted alongside them.
+    let (format, ccsr) = match builder.format {
+        KafkaSinkFormatBuilder::Avro {
+            key_schema,
+            value_schema,
+            ccsr_config,
+            ..
+        } => {
+            let ccsr = ccsr_config.build();
+            let (key_schema_id, value_schema_id) =
+                publish_kafka_schemas(&ccsr, &topic, &value_schema, key_schema.as_deref())
+                    .await
+                    .context("error registering kafka topic for sink")?;
+            (
+                KafkaSinkFormat::Avro {
+                    key_schema_id,
+                    value_schema_id,
+                },
+                Some(ccsr),
+            )
+        }
+        KafkaSinkFormatBuilder::Json => (KafkaSinkFormat::Json, None),
+        KafkaSinkFormatBuilder::Protobuf(encoding) => (KafkaSinkFormat::Protobuf(encoding), None),
+    };
+
+    let consistency = match (builder.consistency_value_schema, ccsr) {
+        (Some(consistency_value_schema), Some(ccsr)) => {
+            let consistency_topic = format!("{}-consistency", topic);
+            create_kafka_topic(
+                &client,
+                &consistency_topic,
+                replication_factor,
+                exactly_once,
+                false,
+            )
+            .await
+            .context("error registering kafka consistency topic for sink")?;
+            let (_, consistency_schema_id) =
+                publish_kafka_schemas(&ccsr, &consistency_topic, &consistency_value_schema, None)
+                    .await
+                    .context("error registering kafka consistency topic for sink")?;
+
+            Some(KafkaSinkConsistencyConnector {
+                topic: consistency_topic,
+                schema_id: consistency_schema_id,
+            })
+        }
+        (Some(_), None) => bail!("kafka sink consistency topics require a schema registry"),
+        (None, _) => None,
+    };
+
+    // If a previous incarnation of the sink already completed some
+    // timestamps, resume strictly after the last completed timestamp, rather
+    // than emitting the snapshot again.
+    let (frontier, strict) = match &consistency {
+        Some(consistency) if exactly_once => {
+            let topic = consistency.topic.clone();
+            let config = config.clone();
+            let latest_ts = tokio::task::spawn_blocking(move || {
+                determine_latest_committed_timestamp(&config, &topic)
+            })
+            .await?
+            .context("error reading kafka consistency topic for sink")?;
+            match latest_ts {
+                Some(ts) => {
+                    info!("resuming kafka sink {} after timestamp {}", id, ts);
+                    (Antichain::from_elem(ts), true)
+                }
+                None => (frontier, !with_snapshot),
+            }
+        }
+        _ => (frontier, !with_snapshot),
     };
 
     Ok(SinkConnector::Kafka(KafkaSinkConnector {
-        key_schema_id,
-        value_schema_id,
         topic,
+        format,
+        envelope: builder.envelope,
         addrs: builder.broker_addrs,
         consistency,
         fuel: builder.fuel,
         frontier,
-        strict: !with_snapshot,
+        strict,
         config_options: builder.config_options,
         key_indices: builder.key_indices,
+        exactly_once,
     }))
 }
 
+/// Returns the latest timestamp for which an `END` record was committed to
+/// the consistency topic `topic`, if any.
+///
+/// Consistency records are only ever committed in the same Kafka transaction
+/// as the data for their timestamp, so reading with `read_committed` isolation
+/// guarantees that all data up to and including the returned timestamp is
+/// visible in the sink topic.
+fn determine_latest_committed_timestamp(
+    config: &ClientConfig,
+    topic: &str,
+) -> Result<Option<Timestamp>, anyhow::Error> {
+    let mut config = config.clone();
+    config
+        .set("group.id", &format!("materialize-sink-resume-{}", topic))
+        .set("enable.auto.commit", "false")
+        .set("enable.partition.eof", "false")
+        .set("isolation.level", "read_committed");
+    let consumer = config
+        .create::<BaseConsumer>()
+        .context("creating kafka consumer for consistency topic")?;
+
+    let timeout = Duration::from_secs(5);
+    let (lo, hi) = consumer.fetch_watermarks(topic, 0, timeout)?;
+
+    // Transaction control records and aborted transactions occupy offsets, so
+    // the last few offsets in the topic might not contain any `END` record.
+    // Scan progressively larger windows at the end of the topic until we find
+    // one, or until we have scanned the whole topic.
+    let mut window = 16;
+    loop {
+        let start = cmp::max(lo, hi - window);
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition_offset(topic, 0, Offset::Offset(start));
+        consumer.assign(&tpl)?;
+
+        let mut latest_ts = None;
+        let mut position = start;
+        while position < hi {
+            let message = match consumer.poll(timeout) {
+                Some(message) => message?,
+                // We cannot observe the last stable offset directly. If the
+                // consumer stops making progress, the remaining offsets were
+                // consumed by transaction markers or aborted records.
+                None => break,
+            };
+            position = message.offset() + 1;
+            let mut payload = match message.payload() {
+                // The first 5 bytes are reserved for the schema registry
+                // magic byte and schema id.
+                Some(payload) if payload.len() > 5 => &payload[5..],
+                _ => continue,
+            };
+            let record =
+                mz_avro::from_avro_datum(avro::get_debezium_transaction_schema(), &mut payload)?;
+            if let Some(ts) = avro::decode_debezium_transaction_end(record)? {
+                latest_ts = cmp::max(latest_ts, Some(ts));
+            }
+        }
+
+        if latest_ts.is_some() || start == lo {
+            return Ok(latest_ts);
+        }
+        window *= 16;
+    }
+}
+
 fn build_avro_ocf(
     builder: AvroOcfSinkConnectorBuilder,
     with_snapshot: bool,
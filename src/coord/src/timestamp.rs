@@ -41,7 +41,7 @@ use dataflow_types::{
     FileSourceConnector, KafkaSourceConnector, KinesisSourceConnector, MzOffset, SourceConnector,
     TimestampSourceUpdate,
 };
-use expr::{PartitionId, SourceInstanceId};
+use expr::{GlobalId, PartitionId, SourceInstanceId};
 use ore::collections::CollectionExt;
 
 use crate::coord;
@@ -120,6 +120,9 @@ pub struct TimestampConfig {
 pub enum TimestampMessage {
     Add(SourceInstanceId, SourceConnector),
     DropInstance(SourceInstanceId),
+    /// Restart the timestamping of every instance of a source with a new
+    /// connector.
+    Restart(GlobalId, SourceConnector),
     Shutdown,
 }
 
@@ -129,6 +132,18 @@ struct RtTimestampConsumer {
     connector: RtTimestampConnector,
 }
 
+impl RtTimestampConsumer {
+    /// Stops any thread that timestamps on behalf of this consumer.
+    fn stop(&self) {
+        if let RtTimestampConnector::Kafka(RtKafkaConnector {
+            coordination_state, ..
+        }) = &self.connector
+        {
+            coordination_state.stop.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
 enum RtTimestampConnector {
     Kafka(RtKafkaConnector),
     File(RtFileConnector),
@@ -800,18 +815,35 @@ impl Timestamper {
                 }
                 TimestampMessage::DropInstance(id) => {
                     info!("Dropping Timestamping for Source {}.", id);
-                    if let Some(RtTimestampConsumer {
-                        connector:
-                            RtTimestampConnector::Kafka(RtKafkaConnector {
-                                coordination_state, ..
-                            }),
-                        ..
-                    }) = self.rt_sources.remove(&id)
-                    {
-                        coordination_state.stop.store(true, Ordering::SeqCst);
+                    if let Some(consumer) = self.rt_sources.remove(&id) {
+                        consumer.stop();
                     }
                     self.byo_sources.remove(&id);
                 }
+                TimestampMessage::Restart(source_id, sc) => {
+                    let sc = if let SourceConnector::External { connector, .. } = sc {
+                        connector
+                    } else {
+                        panic!("A Local Source should never be timestamped");
+                    };
+                    // BYO consumers are left alone, as they cannot resume
+                    // reading the consistency topic where they left off.
+                    let ids: Vec<_> = self
+                        .rt_sources
+                        .keys()
+                        .filter(|id| id.source_id == source_id)
+                        .copied()
+                        .collect();
+                    for id in ids {
+                        info!("Restarting Timestamping for Source {}.", id);
+                        if let Some(consumer) = self.rt_sources.remove(&id) {
+                            consumer.stop();
+                        }
+                        if let Some(consumer) = self.create_rt_connector(id, sc.clone()) {
+                            self.rt_sources.insert(id, consumer);
+                        }
+                    }
+                }
                 TimestampMessage::Shutdown => return true,
             }
         }
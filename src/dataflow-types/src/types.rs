@@ -633,8 +633,8 @@ pub struct KafkaSinkConsistencyConnector {
 pub struct KafkaSinkConnector {
     pub addrs: KafkaAddrs,
     pub topic: String,
-    pub key_schema_id: Option<i32>,
-    pub value_schema_id: i32,
+    pub format: KafkaSinkFormat,
+    pub envelope: SinkEnvelope,
     pub consistency: Option<KafkaSinkConsistencyConnector>,
     // Maximum number of records the sink will attempt to send each time it is
     // invoked
@@ -643,6 +643,34 @@ pub struct KafkaSinkConnector {
     pub strict: bool,
     pub config_options: HashMap<String, String>,
     pub key_indices: Option<Vec<usize>>,
+    // Whether the sink writes each closed timestamp's data and consistency
+    // records in a single Kafka transaction
+    pub exactly_once: bool,
+}
+
+/// The encoding of the records written to a Kafka sink's topic.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KafkaSinkFormat {
+    /// Avro, with schemas published to a Confluent schema registry.
+    Avro {
+        key_schema_id: Option<i32>,
+        value_schema_id: i32,
+    },
+    /// JSON, using the envelope described in [`interchange::json`].
+    Json,
+    /// Protobuf, using the envelope described in
+    /// [`interchange::protobuf::Encoder`].
+    Protobuf(ProtobufEncoding),
+}
+
+/// How a sink represents the changes to its input.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SinkEnvelope {
+    /// Each change is a record with `before` and `after` fields.
+    Debezium,
+    /// Each record is keyed, and its value is the latest row for the key, or
+    /// empty if the key was deleted.
+    Upsert,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -664,11 +692,15 @@ impl SinkConnector {
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TailSinkConnector {
-    pub tx: comm::mpsc::Sender<Vec<Row>>,
+    /// Receives batches of updates, or the error that ended the tail.
+    pub tx: comm::mpsc::Sender<Result<Vec<Row>, String>>,
     pub frontier: Antichain<Timestamp>,
     pub strict: bool,
     pub emit_progress: bool,
     pub object_columns: usize,
+    pub envelope: SinkEnvelope,
+    /// The columns that make up the key of an upsert tail.
+    pub key_indices: Option<Vec<usize>>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -686,17 +718,33 @@ pub struct AvroOcfSinkConnectorBuilder {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct KafkaSinkConnectorBuilder {
     pub broker_addrs: KafkaAddrs,
-    pub schema_registry_url: Url,
-    pub value_schema: String,
+    pub format: KafkaSinkFormatBuilder,
+    pub envelope: SinkEnvelope,
     pub topic_prefix: String,
     pub topic_suffix: String,
     pub replication_factor: u32,
     pub fuel: usize,
     pub consistency_value_schema: Option<String>,
     pub config_options: HashMap<String, String>,
-    pub ccsr_config: ccsr::ClientConfig,
     pub key_indices: Option<Vec<usize>>,
-    pub key_schema: Option<String>,
+    pub exactly_once: bool,
+    // Whether to write to the topic named by the user verbatim, rather than
+    // deriving a new topic name from it
+    pub reuse_topic: bool,
+}
+
+/// The planned encoding of a Kafka sink, before any schemas have been
+/// published.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KafkaSinkFormatBuilder {
+    Avro {
+        schema_registry_url: Url,
+        key_schema: Option<String>,
+        value_schema: String,
+        ccsr_config: ccsr::ClientConfig,
+    },
+    Json,
+    Protobuf(ProtobufEncoding),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
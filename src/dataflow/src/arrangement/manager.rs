@@ -119,6 +119,8 @@ impl TraceManager {
             bundle.oks.distinguish_since(antichain.borrow());
             bundle.errs.read_upper(&mut antichain);
             bundle.errs.distinguish_since(antichain.borrow());
+            bundle.all_errs.read_upper(&mut antichain);
+            bundle.all_errs.distinguish_since(antichain.borrow());
 
             maintenance_metrics
                 .total_maintenance_time
@@ -138,6 +140,7 @@ impl TraceManager {
         if let Some(bundle) = self.traces.get_mut(&id) {
             bundle.oks.advance_by(frontier);
             bundle.errs.advance_by(frontier);
+            bundle.all_errs.advance_by(frontier);
         }
     }
 
@@ -174,21 +177,25 @@ impl TraceManager {
 }
 
 /// Bundles together traces for the successful computations (`oks`), the
-/// failed computations (`errs`), and additional tokens that should share
-/// the lifetime of the bundled traces (`to_drop`).
+/// failed computations (`errs`), a copy of the failed computations of all
+/// workers (`all_errs`), and additional tokens that should share the lifetime
+/// of the bundled traces (`to_drop`).
 #[derive(Clone)]
 pub struct TraceBundle {
     oks: KeysValsHandle,
     errs: ErrsHandle,
+    all_errs: ErrsHandle,
     to_drop: Option<Rc<dyn Any>>,
 }
 
 impl TraceBundle {
-    /// Constructs a new trace bundle out of an `oks` trace and `errs` trace.
-    pub fn new(oks: KeysValsHandle, errs: ErrsHandle) -> TraceBundle {
+    /// Constructs a new trace bundle out of an `oks` trace, an `errs` trace,
+    /// and an `all_errs` trace that holds the errors of every worker.
+    pub fn new(oks: KeysValsHandle, errs: ErrsHandle, all_errs: ErrsHandle) -> TraceBundle {
         TraceBundle {
             oks,
             errs,
+            all_errs,
             to_drop: None,
         }
     }
@@ -201,6 +208,7 @@ impl TraceBundle {
         TraceBundle {
             oks: self.oks,
             errs: self.errs,
+            all_errs: self.all_errs,
             to_drop: Some(Rc::new(Box::new(to_drop))),
         }
     }
@@ -215,6 +223,14 @@ impl TraceBundle {
         &mut self.errs
     }
 
+    /// Returns a mutable reference to the `all_errs` trace.
+    ///
+    /// Unlike the `errs` trace, which each worker holds only its share of,
+    /// every worker holds all of the errors in the `all_errs` trace.
+    pub fn all_errs_mut(&mut self) -> &mut ErrsHandle {
+        &mut self.all_errs
+    }
+
     /// Returns a reference to the `to_drop` tokens.
     pub fn to_drop(&self) -> &Option<Rc<dyn Any>> {
         &self.to_drop
@@ -23,6 +23,6 @@ pub mod logging;
 pub mod source;
 
 pub use server::{
-    serve, BroadcastToken, PersistenceMessage, SequencedCommand, WorkerFeedback,
+    serve, worker_for_key, BroadcastToken, PersistenceMessage, SequencedCommand, WorkerFeedback,
     WorkerFeedbackWithMeta,
 };
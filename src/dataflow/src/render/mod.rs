@@ -109,12 +109,14 @@ use differential_dataflow::lattice::Lattice;
 use differential_dataflow::operators::arrange::arrangement::{Arrange, ArrangeByKey};
 use differential_dataflow::operators::arrange::upsert::arrange_from_upsert;
 use differential_dataflow::operators::consolidate::Consolidate;
+use differential_dataflow::trace::implementations::ord::OrdKeySpine;
 use differential_dataflow::{AsCollection, Collection};
 use futures::executor::block_on;
 
+use timely::dataflow::channels::pact::Pipeline;
 use timely::dataflow::operators::to_stream::ToStream;
 use timely::dataflow::operators::unordered_input::UnorderedInput;
-use timely::dataflow::operators::Map;
+use timely::dataflow::operators::{Broadcast, Map};
 use timely::dataflow::scopes::Child;
 use timely::dataflow::Scope;
 
@@ -650,9 +652,22 @@ where
         let get_expr = RelationExpr::global_get(idx.on_id, typ.clone());
         match self.arrangement(&get_expr, &idx.keys) {
             Some(ArrangementFlavor::Local(oks, errs)) => {
+                // Errors are not partitioned by the keys of the index, so every
+                // worker keeps a copy of all of them. This lets the worker that
+                // holds the keys of a peek check for errors on its own.
+                let all_errs = errs
+                    .as_collection(|k, _| k.clone())
+                    .inner
+                    .broadcast()
+                    .as_collection()
+                    .map(|err| (err, ()))
+                    .arrange_core::<_, OrdKeySpine<_, _, _>>(
+                        Pipeline,
+                        &format!("{}-all-errors", idx_id),
+                    );
                 render_state.traces.set(
                     idx_id,
-                    TraceBundle::new(oks.trace, errs.trace).with_drop(tokens),
+                    TraceBundle::new(oks.trace, errs.trace, all_errs.trace).with_drop(tokens),
                 );
             }
             Some(ArrangementFlavor::Trace(gid, _, _)) => {
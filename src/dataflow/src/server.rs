@@ -18,6 +18,7 @@ use std::rc::Rc;
 use std::sync::Mutex;
 use std::time::{Instant, UNIX_EPOCH};
 
+use differential_dataflow::hashable::Hashable;
 use differential_dataflow::operators::arrange::arrangement::Arrange;
 use differential_dataflow::trace::cursor::Cursor;
 use differential_dataflow::trace::TraceReader;
@@ -42,13 +43,18 @@ use uuid::Uuid;
 
 use dataflow_types::logging::LoggingConfig;
 use dataflow_types::{
-    DataflowDesc, DataflowError, MzOffset, PeekResponse, TimestampSourceUpdate, Update,
+    DataflowDesc, DataflowError, MzOffset, PeekResponse, SourceConnector, TimestampSourceUpdate,
+    Update,
 };
-use expr::{GlobalId, MapFilterProject, PartitionId, RowSetFinishing, SourceInstanceId};
+use expr::{
+    GlobalId, KeyRange, MapFilterProject, PartitionId, RowSetFinishing, ScalarExpr,
+    SourceInstanceId,
+};
+use ore::cast::CastFrom;
 use ore::future::channel::mpsc::ReceiverExt;
-use repr::{Diff, Row, RowArena, Timestamp};
+use repr::{Datum, Diff, Row, RowArena, Timestamp};
 
-use crate::arrangement::manager::{TraceBundle, TraceManager};
+use crate::arrangement::manager::{ErrsHandle, TraceBundle, TraceManager};
 use crate::logging;
 use crate::logging::materialized::MaterializedEvent;
 use crate::operator::CollectionExt;
@@ -94,8 +100,15 @@ pub enum SequencedCommand {
     Peek {
         /// The identifier of the arrangement.
         id: GlobalId,
-        /// An optional key that should be used for the arrangement.
-        key: Option<Row>,
+        /// The keys of the arrangement to read, in sorted order, if not all keys
+        /// need to be read.
+        keys: Option<Vec<Row>>,
+        /// The range of keys of the arrangement to read, if not all keys need
+        /// to be read. Ignored if `keys` is present.
+        key_range: Option<KeyRange>,
+        /// Predicates over the key of the arrangement, which each key must
+        /// satisfy for its values to be read.
+        key_predicates: Vec<ScalarExpr>,
         /// The identifier of this peek request.
         ///
         /// Used in responses and cancelation requests.
@@ -108,6 +121,13 @@ pub enum SequencedCommand {
         finishing: RowSetFinishing,
         /// Linear operation to apply in-line on each result.
         map_filter_project: MapFilterProject,
+        /// The only worker that serves the peek, if any.
+        ///
+        /// A peek at `keys` that all belong to the same worker can only find
+        /// data at that worker (see [`worker_for_key`]). That worker checks
+        /// for errors in its copy of the errors of all workers, and is the
+        /// only one to respond. The other workers ignore the peek.
+        target_worker: Option<usize>,
     },
     /// Cancel the peek associated with the given `conn_id`.
     CancelPeek {
@@ -134,6 +154,13 @@ pub enum SequencedCommand {
         /// The associated update (RT or BYO)
         update: TimestampSourceUpdate,
     },
+    /// Restart the running instances of a source with a new connector.
+    ReconfigureSource {
+        /// The ID of the source.
+        id: GlobalId,
+        /// The new connector for the source.
+        connector: SourceConnector,
+    },
     /// Advance all local inputs to the given timestamp.
     AdvanceAllLocalInputs {
         /// The timestamp to advance to.
@@ -150,6 +177,15 @@ pub enum SequencedCommand {
     Shutdown,
 }
 
+/// Returns the index of the worker that holds `key` in an arrangement
+/// distributed over `workers` workers.
+///
+/// Arrangements exchange their updates by the hash of the key, so all updates
+/// for a given key live on exactly one worker.
+pub fn worker_for_key(key: &Row, workers: usize) -> usize {
+    usize::cast_from(key.hashed()) % workers
+}
+
 /// Information from timely dataflow workers.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WorkerFeedbackWithMeta {
@@ -420,7 +456,7 @@ where
             let id = logging.active_logs[&log];
             self.render_state
                 .traces
-                .set(id, TraceBundle::new(trace, errs.clone()));
+                .set(id, TraceBundle::new(trace, errs.clone(), errs.clone()));
             self.reported_frontiers.insert(id, Antichain::from_elem(0));
             logger.log(MaterializedEvent::Frontier(id, 0, 1));
         }
@@ -428,7 +464,7 @@ where
             let id = logging.active_logs[&log];
             self.render_state
                 .traces
-                .set(id, TraceBundle::new(trace, errs.clone()));
+                .set(id, TraceBundle::new(trace, errs.clone(), errs.clone()));
             self.reported_frontiers.insert(id, Antichain::from_elem(0));
             logger.log(MaterializedEvent::Frontier(id, 0, 1));
         }
@@ -436,7 +472,7 @@ where
             let id = logging.active_logs[&log];
             self.render_state
                 .traces
-                .set(id, TraceBundle::new(trace, errs.clone()));
+                .set(id, TraceBundle::new(trace, errs.clone(), errs.clone()));
             self.reported_frontiers.insert(id, Antichain::from_elem(0));
             logger.log(MaterializedEvent::Frontier(id, 0, 1));
         }
@@ -620,13 +656,19 @@ where
 
             SequencedCommand::Peek {
                 id,
-                key,
+                keys,
+                key_range,
+                key_predicates,
                 timestamp,
                 conn_id,
                 tx,
                 finishing,
                 map_filter_project,
+                target_worker,
             } => {
+                if target_worker.map_or(false, |w| w != self.timely_worker.index()) {
+                    return;
+                }
                 // Acquire a copy of the trace suitable for fulfilling the peek.
                 let mut trace_bundle = self.render_state.traces.get(&id).unwrap().clone();
                 let timestamp_frontier = Antichain::from_elem(timestamp);
@@ -637,22 +679,31 @@ where
                 trace_bundle
                     .errs_mut()
                     .advance_by(timestamp_frontier.borrow());
+                trace_bundle
+                    .all_errs_mut()
+                    .advance_by(timestamp_frontier.borrow());
                 trace_bundle
                     .oks_mut()
                     .distinguish_since(empty_frontier.borrow());
                 trace_bundle
                     .errs_mut()
                     .distinguish_since(empty_frontier.borrow());
+                trace_bundle
+                    .all_errs_mut()
+                    .distinguish_since(empty_frontier.borrow());
                 // Prepare a description of the peek work to do.
                 let mut peek = PendingPeek {
                     id,
-                    key,
+                    keys,
+                    key_range,
+                    key_predicates,
                     conn_id,
                     tx,
                     timestamp,
                     finishing,
                     trace_bundle,
                     map_filter_project,
+                    targeted: target_worker.is_some(),
                 };
                 // Log the receipt of the peek.
                 if let Some(logger) = self.materialized_logger.as_mut() {
@@ -675,8 +726,11 @@ where
                 let logger = &mut self.materialized_logger;
                 self.pending_peeks.retain(|peek| {
                     if peek.conn_id == conn_id {
-                        let mut tx = block_on(peek.tx.connect()).unwrap();
-                        block_on(tx.send(PeekResponse::Canceled)).unwrap();
+                        // The client may have gone away, in which case there
+                        // is nobody to notify.
+                        if let Ok(mut tx) = block_on(peek.tx.connect()) {
+                            let _ = block_on(tx.send(PeekResponse::Canceled));
+                        }
 
                         if let Some(logger) = logger {
                             logger.log(MaterializedEvent::Peek(peek.as_log_event(), false));
@@ -796,6 +850,25 @@ where
                     }
                 }
             }
+            SequencedCommand::ReconfigureSource { id, connector } => {
+                if let SourceConnector::External {
+                    connector,
+                    ts_frequency,
+                    ..
+                } = connector
+                {
+                    for (instance_id, source) in &self.render_state.ts_source_mapping {
+                        if instance_id.source_id != id {
+                            continue;
+                        }
+                        if let Some(source) = source.upgrade() {
+                            if let Some(token) = &*source {
+                                token.reconfigure(connector.clone(), ts_frequency);
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -831,8 +904,14 @@ pub struct LocalInput {
 struct PendingPeek {
     /// The identifier of the dataflow to peek.
     id: GlobalId,
-    /// An optional key to use for the arrangement.
-    key: Option<Row>,
+    /// The keys of the arrangement to read, in sorted order, if not all keys
+    /// need to be read.
+    keys: Option<Vec<Row>>,
+    /// The range of keys of the arrangement to read, if not all keys need to
+    /// be read and `keys` is absent.
+    key_range: Option<KeyRange>,
+    /// Predicates that a key must satisfy for its values to be read.
+    key_predicates: Vec<ScalarExpr>,
     /// The ID of the connection that submitted the peek. For logging only.
     conn_id: u32,
     /// A transmitter connected to the intended recipient of the peek.
@@ -846,6 +925,9 @@ struct PendingPeek {
     map_filter_project: MapFilterProject,
     /// The data from which the trace derives.
     trace_bundle: TraceBundle,
+    /// Whether the peek is served by this worker alone, in which case the
+    /// errors of all workers must be checked rather than only this worker's.
+    targeted: bool,
 }
 
 impl PendingPeek {
@@ -871,7 +953,7 @@ impl PendingPeek {
         if upper.less_equal(&self.timestamp) {
             return false;
         }
-        self.trace_bundle.errs_mut().read_upper(upper);
+        self.errs_mut().read_upper(upper);
         if upper.less_equal(&self.timestamp) {
             return false;
         }
@@ -887,11 +969,20 @@ impl PendingPeek {
         true
     }
 
+    /// Returns the trace of the errors that the peek must check.
+    fn errs_mut(&mut self) -> &mut ErrsHandle {
+        if self.targeted {
+            self.trace_bundle.all_errs_mut()
+        } else {
+            self.trace_bundle.errs_mut()
+        }
+    }
+
     /// Collects data for a known-complete peek.
     fn collect_finished_data(&mut self) -> Result<Vec<Row>, String> {
         // Check if there exist any errors and, if so, return whatever one we
         // find first.
-        let (mut cursor, storage) = self.trace_bundle.errs_mut().cursor();
+        let (mut cursor, storage) = self.errs_mut().cursor();
         while cursor.key_valid(&storage) {
             let mut copies = 0;
             cursor.map_times(&storage, |time, diff| {
@@ -925,11 +1016,59 @@ impl PendingPeek {
         // just at least those results that would have been returned.
         let max_results = self.finishing.limit.map(|l| l + self.finishing.offset);
 
-        if let Some(literal) = &self.key {
-            cursor.seek_key(&storage, literal);
-        }
+        // When set, the keys that remain to be read, in sorted order.
+        let mut keys = self.keys.as_ref().map(|keys| keys.iter());
+
+        // Otherwise, when set, the range of keys to read. Arrangements are
+        // ordered by the bytes of their keys rather than by their datums, so
+        // the keys in the range are not contiguous and every key is checked.
+        let key_range = if keys.is_none() {
+            self.key_range.as_ref()
+        } else {
+            None
+        };
+
+        loop {
+            // Position the cursor at the next key to read, either by seeking to
+            // the next of `keys` or by stepping through every key in turn.
+            if let Some(keys) = &mut keys {
+                match keys.next() {
+                    Some(key) => {
+                        cursor.seek_key(&storage, key);
+                        if !cursor.key_valid(&storage) || cursor.key(&storage) != key {
+                            continue;
+                        }
+                    }
+                    None => break,
+                }
+            } else if !cursor.key_valid(&storage) {
+                break;
+            } else if let Some(range) = key_range {
+                if !range.contains(cursor.key(&storage)) {
+                    cursor.step_key(&storage);
+                    continue;
+                }
+            }
+
+            // Skip over keys that cannot satisfy the predicates. Predicates that
+            // produce errors are left for `map_filter_project` to report.
+            if !self.key_predicates.is_empty() {
+                let arena = RowArena::new();
+                let datums = cursor.key(&storage).unpack();
+                let rejected = self.key_predicates.iter().any(|predicate| {
+                    match predicate.eval(&datums, &arena) {
+                        Ok(datum) => datum != Datum::True,
+                        Err(_) => false,
+                    }
+                });
+                if rejected {
+                    if keys.is_none() {
+                        cursor.step_key(&storage);
+                    }
+                    continue;
+                }
+            }
 
-        while cursor.key_valid(&storage) {
             while cursor.val_valid(&storage) {
                 // TODO: This arena could be maintained and reuse for longer
                 // but it wasn't clear at what granularity we should flush
@@ -1002,10 +1141,8 @@ impl PendingPeek {
                 }
                 cursor.step_val(&storage);
             }
-            // If we had a key, we are now done and can return.
-            if self.key.is_some() {
-                return Ok(results);
-            } else {
+            // With explicit keys, the next iteration seeks to the next key.
+            if keys.is_none() {
                 cursor.step_key(&storage);
             }
         }
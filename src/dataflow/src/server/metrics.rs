@@ -107,6 +107,8 @@ struct CommandsProcessedMetrics {
     append_log: IntCounter,
     advance_source_timestamp_int: i32,
     advance_source_timestamp: IntCounter,
+    reconfigure_source_int: i32,
+    reconfigure_source: IntCounter,
     enable_feedback_int: i32,
     enable_feedback: IntCounter,
     enable_logging_int: i32,
@@ -148,6 +150,9 @@ impl CommandsProcessedMetrics {
             advance_source_timestamp_int: 0,
             advance_source_timestamp: COMMANDS_PROCESSED_RAW
                 .with_label_values(&[worker, "advance_source_timestamp"]),
+            reconfigure_source_int: 0,
+            reconfigure_source: COMMANDS_PROCESSED_RAW
+                .with_label_values(&[worker, "reconfigure_source"]),
             enable_feedback_int: 0,
             enable_feedback: COMMANDS_PROCESSED_RAW.with_label_values(&[worker, "enable_feedback"]),
             enable_logging_int: 0,
@@ -176,6 +181,7 @@ impl CommandsProcessedMetrics {
             SequencedCommand::AdvanceSourceTimestamp { .. } => {
                 self.advance_source_timestamp_int += 1
             }
+            SequencedCommand::ReconfigureSource { .. } => self.reconfigure_source_int += 1,
             SequencedCommand::EnableFeedback(..) => self.enable_feedback_int += 1,
             SequencedCommand::EnablePersistence(..) => self.enable_persistence_int += 1,
             SequencedCommand::EnableLogging(_) => self.enable_logging_int += 1,
@@ -226,6 +232,11 @@ impl CommandsProcessedMetrics {
                 .inc_by(self.advance_source_timestamp_int as i64);
             self.advance_source_timestamp_int = 0;
         }
+        if self.reconfigure_source_int > 0 {
+            self.reconfigure_source
+                .inc_by(self.reconfigure_source_int as i64);
+            self.reconfigure_source_int = 0;
+        }
         if self.enable_feedback_int > 0 {
             self.enable_feedback.inc_by(self.enable_feedback_int as i64);
             self.enable_feedback_int = 0;
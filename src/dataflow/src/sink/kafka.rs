@@ -12,9 +12,12 @@ use std::collections::{BTreeMap, VecDeque};
 use std::iter::Iterator;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::sync::Arc;
+use std::thread;
 use std::time::Duration;
 
+use anyhow::bail;
 use differential_dataflow::hashable::Hashable;
 use differential_dataflow::operators::arrange::ShutdownButton;
 use lazy_static::lazy_static;
@@ -25,20 +28,22 @@ use prometheus::{
 };
 use rdkafka::client::ClientContext;
 use rdkafka::config::ClientConfig;
-use rdkafka::error::{KafkaError, RDKafkaError};
+use rdkafka::error::{KafkaError, KafkaResult, RDKafkaError};
 use rdkafka::message::Message;
-use rdkafka::producer::{BaseRecord, DeliveryResult, ProducerContext, ThreadedProducer};
+use rdkafka::producer::{BaseRecord, DeliveryResult, Producer, ProducerContext, ThreadedProducer};
 use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::operators::generic::FrontieredInputHandle;
 use timely::dataflow::{Scope, Stream};
 use timely::progress::frontier::MutableAntichain;
+use timely::scheduling::SyncActivator;
 
-use dataflow_types::KafkaSinkConnector;
+use dataflow_types::{KafkaSinkConnector, KafkaSinkFormat, ProtobufEncoding, SinkEnvelope};
 use expr::GlobalId;
 use interchange::avro::{self, DiffPair, Encoder};
+use interchange::{json, protobuf};
 use repr::{Diff, RelationDesc, Row, Timestamp};
 
-use super::util::sink_reschedule;
+use super::util::{latest_per_key, sink_reschedule};
 
 /// Per-Kafka sink metrics.
 #[derive(Clone)]
@@ -151,6 +156,185 @@ pub struct SinkConsistencyInfo {
     queue: VecDeque<(SinkConsistencyState, Timestamp, Option<i64>)>,
 }
 
+/// State for sinks that write each closed timestamp in its own Kafka
+/// transaction.
+#[derive(Debug, Default)]
+pub struct SinkTransactionInfo {
+    // Whether the transactional producer has been initialized. Initialization
+    // fences out any previous producers with the same transactional id, so we
+    // only do it on the worker that actually receives the sink's data.
+    initialized: bool,
+    // The result of the blocking transactional operation, like initializing
+    // transactions or committing a transaction, that is running on another
+    // thread, if any
+    in_progress: Option<Receiver<KafkaResult<()>>>,
+    // Rows received for timestamps that are not yet closed
+    pending: BTreeMap<Timestamp, Vec<(Row, Diff)>>,
+    // Closed timestamps, and their message counts, waiting for a transaction
+    ready: VecDeque<(Timestamp, i64)>,
+    // The timestamp whose transaction is currently open, if any
+    open: Option<Timestamp>,
+}
+
+/// The time that Kafka is allowed to take to initialize transactions, or to
+/// commit or abort a transaction.
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Runs `op`, a transactional operation that blocks until Kafka acknowledges
+/// it, on a separate thread, so that it does not stall the timely worker.
+///
+/// The result of the operation is sent on the returned receiver, after which
+/// the operator is activated via `activator`.
+fn spawn_transaction_op<F>(
+    producer: &Arc<ThreadedProducer<SinkProducerContext>>,
+    activator: SyncActivator,
+    op: F,
+) -> Receiver<KafkaResult<()>>
+where
+    F: FnOnce(&ThreadedProducer<SinkProducerContext>) -> KafkaResult<()> + Send + 'static,
+{
+    let producer = Arc::clone(producer);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(op(&producer));
+        let _ = activator.activate();
+    });
+    rx
+}
+
+/// State for sinks that only write the latest value for each key.
+#[derive(Debug)]
+pub struct SinkUpsertInfo {
+    // The columns that make up the key
+    key_indices: Vec<usize>,
+    // Rows received for timestamps that are not yet closed
+    pending: BTreeMap<Timestamp, Vec<(Row, Diff)>>,
+}
+
+impl SinkUpsertInfo {
+    pub fn new(key_indices: Vec<usize>) -> Self {
+        SinkUpsertInfo {
+            key_indices,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Removes the rows for all closed timestamps, and reduces them to at most
+    /// one update per key and timestamp with [`latest_per_key`]. Negative
+    /// updates are sent as tombstones.
+    pub fn take_complete_updates(
+        &mut self,
+        frontier: &MutableAntichain<Timestamp>,
+    ) -> Vec<(Row, Timestamp, Diff)> {
+        let closed_timestamps: Vec<Timestamp> = self
+            .pending
+            .keys()
+            .filter(|t| !frontier.less_equal(t))
+            .copied()
+            .collect();
+
+        let mut updates = Vec::new();
+        for time in closed_timestamps {
+            let rows = self.pending.remove(&time).unwrap_or_default();
+            let latest = latest_per_key(&self.key_indices, rows);
+            updates.extend(latest.into_iter().map(|(row, diff)| (row, time, diff)));
+        }
+        updates
+    }
+}
+
+/// Encodes rows in the format the sink was created with.
+enum SinkEncoder {
+    Avro {
+        encoder: Encoder,
+        key_schema_id: Option<i32>,
+        value_schema_id: i32,
+    },
+    Json(json::Encoder),
+    Protobuf(protobuf::Encoder),
+}
+
+impl SinkEncoder {
+    /// Encodes an update as a (key, value) pair, returning the encoded pair
+    /// and the number of copies of it to send.
+    fn encode(
+        &self,
+        row: &Row,
+        time: Timestamp,
+        diff: Diff,
+        with_transaction_id: bool,
+    ) -> Result<((Option<Vec<u8>>, Option<Vec<u8>>), Diff), anyhow::Error> {
+        let diff_pair = if diff < 0 {
+            DiffPair {
+                before: Some(row),
+                after: None,
+            }
+        } else {
+            DiffPair {
+                before: None,
+                after: Some(row),
+            }
+        };
+
+        match self {
+            SinkEncoder::Avro {
+                encoder,
+                key_schema_id,
+                value_schema_id,
+            } => {
+                let transaction_id = if with_transaction_id {
+                    Some(time.to_string())
+                } else {
+                    None
+                };
+                let (key, value) = encoder.encode_unchecked(
+                    *key_schema_id,
+                    *value_schema_id,
+                    diff_pair,
+                    transaction_id,
+                );
+                // For diffs other than +/- 1, we send repeated copies of the
+                // Avro record [diff] times. Since the format and envelope
+                // capture the "polarity" of the update, we need to remember
+                // how many times to send the data.
+                Ok(((key, Some(value)), diff.abs()))
+            }
+            // The JSON and protobuf envelopes include the diff, so each
+            // update is sent exactly once.
+            SinkEncoder::Json(encoder) => {
+                let (key, value) = encoder.encode_unchecked(diff_pair, time, diff);
+                Ok(((key, Some(value)), 1))
+            }
+            SinkEncoder::Protobuf(encoder) => {
+                let buf = encoder.encode(diff_pair.before, diff_pair.after, time, diff)?;
+                Ok(((None, Some(buf)), 1))
+            }
+        }
+    }
+
+    /// Encodes the latest value of a key in an upsert sink. Negative diffs
+    /// produce a tombstone, i.e., a message with no value, for the row's key.
+    fn encode_upsert(
+        &self,
+        row: &Row,
+        diff: Diff,
+    ) -> Result<((Option<Vec<u8>>, Option<Vec<u8>>), Diff), anyhow::Error> {
+        let (key, value) = match self {
+            SinkEncoder::Avro {
+                encoder,
+                key_schema_id,
+                value_schema_id,
+            } => {
+                let key_schema_id = key_schema_id.expect("upsert sinks have a key schema");
+                encoder.encode_upsert_unchecked(key_schema_id, *value_schema_id, row, diff < 0)
+            }
+            SinkEncoder::Json(encoder) => encoder.encode_upsert_unchecked(row, diff < 0),
+            SinkEncoder::Protobuf(_) => bail!("protobuf sinks do not support ENVELOPE UPSERT"),
+        };
+        Ok(((Some(key), value), 1))
+    }
+}
+
 impl SinkConsistencyInfo {
     pub fn new(topic: String, schema_id: i32) -> Self {
         SinkConsistencyInfo {
@@ -201,7 +385,7 @@ pub fn kafka<G>(
     id: GlobalId,
     mut connector: KafkaSinkConnector,
     desc: RelationDesc,
-) -> ShutdownButton<ThreadedProducer<SinkProducerContext>>
+) -> ShutdownButton<Arc<ThreadedProducer<SinkProducerContext>>>
 where
     G: Scope<Timestamp = Timestamp>,
 {
@@ -237,6 +421,17 @@ where
         config.set(k, v);
     }
 
+    if connector.exactly_once {
+        // The transactional id must be stable across restarts, so that a new
+        // incarnation of the sink fences out any zombie producers. The
+        // coordinator ensures that no two sinks write to the same topic, so
+        // sinks never fence out each other.
+        config.set(
+            "transactional.id",
+            &format!("mz-producer-{}", connector.topic),
+        );
+    }
+
     let sink_metrics = SinkMetrics::new(
         &connector.topic,
         &id.to_string(),
@@ -244,17 +439,22 @@ where
     );
 
     let shutdown = Arc::new(AtomicBool::new(false));
-    let producer = Rc::new(RefCell::new(Some(
+    let producer = Rc::new(RefCell::new(Some(Arc::new(
         config
             .create_with_context::<_, ThreadedProducer<_>>(SinkProducerContext::new(
                 &sink_metrics,
                 &shutdown,
             ))
             .expect("creating kafka producer for kafka sinks failed"),
-    )));
+    ))));
     let mut queue: VecDeque<(Row, Timestamp, Diff)> = VecDeque::new();
     let mut vector = Vec::new();
     let mut encoded_buffer = None;
+    let mut transaction = if connector.exactly_once {
+        Some(SinkTransactionInfo::default())
+    } else {
+        None
+    };
 
     let mut consistency = if let Some(consistency) = &connector.consistency {
         Some(SinkConsistencyInfo::new(
@@ -265,7 +465,54 @@ where
         None
     };
 
-    let encoder = Encoder::new(desc, consistency.is_some(), connector.key_indices.take());
+    let mut upsert = match connector.envelope {
+        SinkEnvelope::Upsert => Some(SinkUpsertInfo::new(
+            connector
+                .key_indices
+                .clone()
+                .expect("upsert sinks have a key"),
+        )),
+        SinkEnvelope::Debezium => None,
+    };
+
+    let encoder = match connector.format.clone() {
+        KafkaSinkFormat::Avro {
+            key_schema_id,
+            value_schema_id,
+        } => {
+            let encoder = match connector.envelope {
+                SinkEnvelope::Debezium => {
+                    Encoder::new(desc, consistency.is_some(), connector.key_indices.take())
+                }
+                SinkEnvelope::Upsert => Encoder::new_upsert(
+                    desc,
+                    connector
+                        .key_indices
+                        .take()
+                        .expect("upsert sinks have a key"),
+                ),
+            };
+            SinkEncoder::Avro {
+                encoder,
+                key_schema_id,
+                value_schema_id,
+            }
+        }
+        KafkaSinkFormat::Json => {
+            SinkEncoder::Json(json::Encoder::new(desc, connector.key_indices.take()))
+        }
+        KafkaSinkFormat::Protobuf(ProtobufEncoding {
+            descriptors,
+            message_name,
+        }) => {
+            let descriptors = protobuf::decode_descriptors(&descriptors)
+                .expect("descriptors provided to protobuf sink are pre-validated");
+            SinkEncoder::Protobuf(
+                protobuf::Encoder::new(descriptors, &message_name, &desc)
+                    .expect("protobuf sink message is pre-validated"),
+            )
+        }
+    };
     let name = format!("kafka-{}", id);
     sink_reschedule(
         &stream,
@@ -274,6 +521,7 @@ where
         |info| {
             // Setup activator and shutdown buttons for this operator
             let activator = stream.scope().activator_for(&info.address[..]);
+            let sync_activator = stream.scope().sync_activator_for(&info.address[..]);
             let shutdown_button = ShutdownButton::new(
                 producer.clone(),
                 stream.scope().activator_for(&info.address[..]),
@@ -314,7 +562,25 @@ where
                             continue;
                         }
 
-                        queue.push_back((row, time, diff));
+                        if let Some(upsert) = &mut upsert {
+                            // Hold back the row until its timestamp closes
+                            // and we know the latest value for its key.
+                            upsert
+                                .pending
+                                .entry(time)
+                                .or_insert_with(Vec::new)
+                                .push((row, diff));
+                        } else if let Some(transaction) = &mut transaction {
+                            // Hold back the row until its timestamp closes
+                            // and we can write it in a transaction.
+                            transaction
+                                .pending
+                                .entry(time)
+                                .or_insert_with(Vec::new)
+                                .push((row, diff));
+                        } else {
+                            queue.push_back((row, time, diff));
+                        }
                         if let Some(consistency) = &mut consistency {
                             // Note that since a single differential message
                             // turns into |diff| messages we need to increment
@@ -323,7 +589,9 @@ where
                             let insert =
                                 consistency.update_timestamp_count(time, diff.abs() as i64);
 
-                            if insert {
+                            // Transactional sinks send the BEGIN message when
+                            // they open the timestamp's transaction instead.
+                            if insert && transaction.is_none() {
                                 // Send a BEGIN message for a timestamp the first
                                 // time we encounter it
                                 consistency.queue.push_back((
@@ -336,18 +604,81 @@ where
                     }
                 });
 
+                if let Some(upsert) = &mut upsert {
+                    queue.extend(upsert.take_complete_updates(input.frontier()));
+                }
+
+                if let Some(transaction) = &mut transaction {
+                    // Nothing can be written until the blocking transactional
+                    // operation in progress, if any, completes. The thread
+                    // running it activates the operator when it does.
+                    if let Some(in_progress) = &transaction.in_progress {
+                        match in_progress.try_recv() {
+                            Ok(Ok(())) => transaction.in_progress = None,
+                            Ok(Err(e)) => {
+                                error!("transactional operation failed in {}: {}", name, e);
+                                shutdown.store(true, Ordering::SeqCst);
+                                return false;
+                            }
+                            Err(TryRecvError::Empty) => return true,
+                            Err(TryRecvError::Disconnected) => {
+                                error!("transactional operation panicked in {}", name);
+                                shutdown.store(true, Ordering::SeqCst);
+                                return false;
+                            }
+                        }
+                    }
+                }
+
                 if let Some(consistency) = &mut consistency {
                     // Find the timestamps that are now complete (meaning all
                     // timestamps t !<= input_frontier. For each closed timestamp
                     // send a END message in the consistency topic
-                    consistency
-                        .get_complete_timestamps(input.frontier())
-                        .iter()
-                        .for_each(|(k, v)| {
+                    let complete = consistency.get_complete_timestamps(input.frontier());
+                    if let Some(transaction) = &mut transaction {
+                        transaction.ready.extend(complete);
+
+                        // Open a transaction for the next closed timestamp if
+                        // we are not already in the middle of one. All of the
+                        // timestamp's data and consistency messages are
+                        // written in the transaction, so that consumers
+                        // reading committed messages see either all or none
+                        // of them.
+                        if transaction.open.is_none() && !transaction.ready.is_empty() {
+                            if !transaction.initialized {
+                                transaction.initialized = true;
+                                transaction.in_progress = Some(spawn_transaction_op(
+                                    producer,
+                                    sync_activator.clone(),
+                                    |producer| producer.init_transactions(TRANSACTION_TIMEOUT),
+                                ));
+                                return true;
+                            }
+                            let (time, count) = transaction.ready.pop_front().unwrap();
+                            if let Err(e) = producer.begin_transaction() {
+                                error!("unable to begin transaction in {}: {}", name, e);
+                                shutdown.store(true, Ordering::SeqCst);
+                                return false;
+                            }
+                            let rows = transaction.pending.remove(&time).unwrap_or_default();
+                            queue.extend(rows.into_iter().map(|(row, diff)| (row, time, diff)));
+                            consistency
+                                .queue
+                                .push_back((SinkConsistencyState::Begin, time, None));
+                            consistency.queue.push_back((
+                                SinkConsistencyState::End,
+                                time,
+                                Some(count),
+                            ));
+                            transaction.open = Some(time);
+                        }
+                    } else {
+                        complete.iter().for_each(|(k, v)| {
                             consistency
                                 .queue
                                 .push_back((SinkConsistencyState::End, *k, Some(*v)));
                         });
+                    }
 
                     // Send a bounded number of queued consistency messages to
                     // the consistency topic
@@ -405,50 +736,39 @@ where
                             // We still need to send more copies of this record.
                             (encoded, count)
                         } else if let Some((row, time, diff)) = queue.pop_front() {
-                            // Convert a previously queued (Row, Diff) to a Avro diff
+                            // Convert a previously queued (Row, Diff) to a diff
                             // envelope record
                             if diff == 0 {
                                 // Explicitly refuse to send no-op records
                                 continue;
                             };
 
-                            let time = match consistency {
-                                Some(_) => Some(time.to_string()),
-                                None => None,
-                            };
-
-                            let diff_pair = if diff < 0 {
-                                DiffPair {
-                                    before: Some(&row),
-                                    after: None,
-                                }
+                            let encoded = if upsert.is_some() {
+                                encoder.encode_upsert(&row, diff)
                             } else {
-                                DiffPair {
-                                    before: None,
-                                    after: Some(&row),
-                                }
+                                encoder.encode(&row, time, diff, consistency.is_some())
                             };
-
-                            let bufs = encoder.encode_unchecked(
-                                connector.key_schema_id,
-                                connector.value_schema_id,
-                                diff_pair,
-                                time,
-                            );
-                            // For diffs other than +/- 1, we send repeated copies of the
-                            // Avro record [diff] times. Since the format and envelope
-                            // capture the "polarity" of the update, we need to remember
-                            // how many times to send the data.
-                            (bufs, diff.abs())
+                            match encoded {
+                                Ok(encoded) => encoded,
+                                Err(e) => {
+                                    error!("unable to encode row in {}: {:#}", name, e);
+                                    shutdown.store(true, Ordering::SeqCst);
+                                    return false;
+                                }
+                            }
                         } else {
                             // Nothing left for us to do
                             break;
                         };
 
-                    let record =
-                        BaseRecord::<Vec<u8>, _>::to(&connector.topic).payload(&encoded_val);
-                    let record = if encoded_key.is_some() {
-                        record.key(encoded_key.as_ref().unwrap())
+                    let record = BaseRecord::<Vec<u8>, Vec<u8>>::to(&connector.topic);
+                    let record = if let Some(encoded_val) = &encoded_val {
+                        record.payload(encoded_val)
+                    } else {
+                        record
+                    };
+                    let record = if let Some(encoded_key) = &encoded_key {
+                        record.key(encoded_key)
                     } else {
                         record
                     };
@@ -482,6 +802,39 @@ where
                     }
                 }
 
+                if let Some(transaction) = &mut transaction {
+                    let consistency_done = consistency
+                        .as_ref()
+                        .map(|c| c.queue.is_empty())
+                        .unwrap_or(true);
+                    if encoded_buffer.is_none() && queue.is_empty() && consistency_done {
+                        if transaction.open.take().is_some() {
+                            // Committing flushes all outstanding messages in
+                            // the transaction, so it blocks until Kafka has
+                            // acknowledged the whole timestamp.
+                            transaction.in_progress = Some(spawn_transaction_op(
+                                producer,
+                                sync_activator.clone(),
+                                |producer| {
+                                    producer
+                                        .commit_transaction(TRANSACTION_TIMEOUT)
+                                        .map_err(|e| {
+                                            let _ = producer.abort_transaction(TRANSACTION_TIMEOUT);
+                                            e
+                                        })
+                                },
+                            ));
+                            return true;
+                        }
+                    }
+                    if !transaction.ready.is_empty() {
+                        // More closed timestamps are waiting for their own
+                        // transactions.
+                        activator.activate();
+                        return true;
+                    }
+                }
+
                 let in_flight = producer.in_flight_count();
 
                 sink_metrics.rows_queued.set(queue.len() as u64);
@@ -7,24 +7,39 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::{BTreeMap, VecDeque};
+use std::mem;
 use std::rc::Rc;
+use std::sync::mpsc::{self, TrySendError};
+use std::thread;
 
 use differential_dataflow::trace::cursor::Cursor;
 use differential_dataflow::trace::implementations::ord::OrdValBatch;
 use differential_dataflow::trace::BatchReader;
 use timely::dataflow::channels::pact::Pipeline;
-use timely::dataflow::operators::Operator;
+use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
 use timely::dataflow::{Scope, Stream};
 
 use futures::executor::block_on;
 use futures::sink::SinkExt;
+use log::error;
 
-use dataflow_types::TailSinkConnector;
+use dataflow_types::{SinkEnvelope, TailSinkConnector};
 use expr::GlobalId;
 use ore::cast::CastFrom;
 use repr::adt::decimal::Significand;
 use repr::{Datum, Diff, Row, RowPacker, Timestamp};
 
+use super::util::latest_per_key;
+
+/// The number of result batches that may be in flight between the tail
+/// operator and the thread that writes them to the client.
+const TAIL_CHANNEL_CAPACITY: usize = 4;
+
+/// The number of updates that the tail operator may hold back for a client
+/// that is not keeping up before the tail fails.
+const TAIL_MAX_PENDING_UPDATES: usize = 1 << 20;
+
 pub fn tail<G>(
     stream: Stream<G, Rc<OrdValBatch<GlobalId, Row, Timestamp, Diff>>>,
     id: GlobalId,
@@ -32,68 +47,194 @@ pub fn tail<G>(
 ) where
     G: Scope<Timestamp = Timestamp>,
 {
-    let mut tx = block_on(connector.tx.connect()).expect("tail transmitter failed");
+    let mut builder = OperatorBuilder::new(format!("tail-{}", id), stream.scope());
+    let activator = stream
+        .scope()
+        .sync_activator_for(&builder.operator_info().address[..]);
+    let mut input = builder.new_input(&stream, Pipeline);
+
+    // Results are written to the client by a dedicated thread, so that a slow
+    // client cannot stall the timely worker. The channel to that thread is
+    // small; once it is full, further input is held back as arrangement
+    // batches, which are shared with the arrangement and so much cheaper to
+    // hold onto than the rows they would expand into. The thread activates the
+    // operator whenever it frees up room in the channel. The operator cannot
+    // slow down its input, so if the client falls more than
+    // `TAIL_MAX_PENDING_UPDATES` behind, the tail fails rather than buffer
+    // without bound.
+    let (results_tx, results_rx) =
+        mpsc::sync_channel::<Result<Vec<Row>, String>>(TAIL_CHANNEL_CAPACITY);
+    let tx = connector.tx.clone();
+    // Connecting and sending require a Tokio runtime, which the worker
+    // threads have entered but a freshly spawned thread has not.
+    let tokio_executor = tokio::runtime::Handle::current();
+    thread::spawn(move || {
+        tokio_executor.enter(|| {
+            let mut tx = match block_on(tx.connect()) {
+                Ok(tx) => tx,
+                Err(e) => {
+                    error!("tail transmitter failed: {}", e);
+                    return;
+                }
+            };
+            while let Ok(results) = results_rx.recv() {
+                if activator.activate().is_err() {
+                    break;
+                }
+                if block_on(tx.send(results)).is_err() {
+                    // The client has gone away. Dropping `results_rx` lets
+                    // the operator know to stop producing output.
+                    break;
+                }
+            }
+        })
+    });
+
     let mut packer = RowPacker::new();
-    stream.sink(Pipeline, &format!("tail-{}", id), move |input| {
-        input.for_each(|_, batches| {
-            let mut results = vec![];
-            for batch in batches.iter() {
-                let mut cursor = batch.cursor();
-                while cursor.key_valid(&batch) {
-                    while cursor.val_valid(&batch) {
-                        let row = cursor.val(&batch);
-                        cursor.map_times(&batch, |time, diff| {
-                            let should_emit = if connector.strict {
-                                connector.frontier.less_than(time)
-                            } else {
-                                connector.frontier.less_equal(time)
-                            };
-                            if should_emit {
-                                packer.push(Datum::Decimal(Significand::new(i128::from(*time))));
-                                if connector.emit_progress {
-                                    packer.push(Datum::False);
-                                }
-                                packer.push(Datum::Int64(i64::cast_from(*diff)));
-                                packer.extend_by_row(row);
-                                // Add the unpacked timestamp so we can sort by them later.
-                                results.push((*time, packer.finish_and_reuse()));
-                            }
-                        });
-                        cursor.step_val(&batch);
+    let mut buffer = Vec::new();
+    let mut pending: VecDeque<Vec<Rc<OrdValBatch<GlobalId, Row, Timestamp, Diff>>>> =
+        VecDeque::new();
+    let mut pending_updates = 0;
+    let mut unsent = None;
+    let mut failed = false;
+    // The channel to the thread, which is dropped once the tail is done, so
+    // that the thread exits after writing whatever remains in the channel.
+    let mut results_tx = Some(results_tx);
+    builder.build_reschedule(move |_capabilities| {
+        move |_frontiers| {
+            input.for_each(|_, batches| {
+                batches.swap(&mut buffer);
+                if failed || results_tx.is_none() {
+                    buffer.clear();
+                } else {
+                    pending_updates += buffer.iter().map(|batch| batch.len()).sum::<usize>();
+                    pending.push_back(mem::take(&mut buffer));
+                }
+            });
+            // Batches are always accepted while nothing else is pending, so
+            // that a large snapshot does not fail the tail.
+            if pending_updates > TAIL_MAX_PENDING_UPDATES && pending.len() > 1 {
+                failed = true;
+                pending.clear();
+                pending_updates = 0;
+                unsent = Some(Err(format!(
+                    "TAIL failed: client fell more than {} updates behind",
+                    TAIL_MAX_PENDING_UPDATES
+                )));
+            }
+            while let Some(tx) = &results_tx {
+                let results = match unsent.take() {
+                    Some(results) => results,
+                    None => match pending.pop_front() {
+                        Some(batches) => {
+                            pending_updates -=
+                                batches.iter().map(|batch| batch.len()).sum::<usize>();
+                            Ok(pack_results(&batches, &connector, &mut packer))
+                        }
+                        None => break,
+                    },
+                };
+                let is_err = results.is_err();
+                match tx.try_send(results) {
+                    Ok(()) if is_err => results_tx = None,
+                    Ok(()) => (),
+                    Err(TrySendError::Full(results)) => {
+                        unsent = Some(results);
+                        break;
                     }
-                    cursor.step_key(&batch);
+                    Err(TrySendError::Disconnected(_)) => results_tx = None,
                 }
             }
+            if results_tx.is_none() {
+                pending.clear();
+                unsent = None;
+            }
+            false
+        }
+    });
+}
 
-            // Sort results by time and convert to Vec<Row>. We use stable sort here even
-            // though it is slower because it will produce deterministic results since the
-            // cursor will always produce rows in the same order.
-            results.sort_by_key(|(time, _)| *time);
-            let mut results: Vec<Row> = results.into_iter().map(|(_, row)| row).collect();
-
-            if connector.emit_progress {
-                if let Some(upper) = batch_upper(batches.last()) {
-                    // The user has requested progress messages and there's at least one
-                    // batch. All of the batches might have zero rows, so we do not depend on
-                    // results at all. Another benefit of using upper (instead of the largest row
-                    // time) is that the batch's upper may be larger than the row time.
-                    packer.push(Datum::Decimal(Significand::new(i128::from(upper))));
-                    packer.push(Datum::True);
-                    // Fill in the diff column and all table columns with NULL.
-                    for _ in 0..(connector.object_columns + 1) {
-                        packer.push(Datum::Null);
+/// Converts the updates in `batches` that are beyond the connector's frontier
+/// into rows for the client, ordered by time.
+///
+/// Upsert tails produce at most one row per key and time, holding the latest
+/// value for the key, or the last value before the key was deleted.
+fn pack_results(
+    batches: &[Rc<OrdValBatch<GlobalId, Row, Timestamp, Diff>>],
+    connector: &TailSinkConnector,
+    packer: &mut RowPacker,
+) -> Vec<Row> {
+    let mut results = vec![];
+    // Updates for upsert tails, which are reduced to one row per key and time
+    // once all the batches have been read.
+    let mut upserts: BTreeMap<Timestamp, Vec<(Row, Diff)>> = BTreeMap::new();
+    for batch in batches.iter() {
+        let mut cursor = batch.cursor();
+        while cursor.key_valid(&batch) {
+            while cursor.val_valid(&batch) {
+                let row = cursor.val(&batch);
+                cursor.map_times(&batch, |time, diff| {
+                    let should_emit = if connector.strict {
+                        connector.frontier.less_than(time)
+                    } else {
+                        connector.frontier.less_equal(time)
+                    };
+                    if should_emit && connector.envelope == SinkEnvelope::Upsert {
+                        upserts.entry(*time).or_default().push((row.clone(), *diff));
+                    } else if should_emit {
+                        packer.push(Datum::Decimal(Significand::new(i128::from(*time))));
+                        if connector.emit_progress {
+                            packer.push(Datum::False);
+                        }
+                        packer.push(Datum::Int64(i64::cast_from(*diff)));
+                        packer.extend_by_row(row);
+                        // Add the unpacked timestamp so we can sort by them later.
+                        results.push((*time, packer.finish_and_reuse()));
                     }
-                    results.push(packer.finish_and_reuse());
+                });
+                cursor.step_val(&batch);
+            }
+            cursor.step_key(&batch);
+        }
+    }
+
+    if let Some(key_indices) = &connector.key_indices {
+        for (time, rows) in upserts {
+            for (row, diff) in latest_per_key(key_indices, rows) {
+                packer.push(Datum::Decimal(Significand::new(i128::from(time))));
+                if connector.emit_progress {
+                    packer.push(Datum::False);
                 }
+                packer.push(Datum::from(diff < 0));
+                packer.extend_by_row(&row);
+                results.push((time, packer.finish_and_reuse()));
+            }
+        }
+    }
+
+    // Sort results by time and convert to Vec<Row>. We use stable sort here even
+    // though it is slower because it will produce deterministic results since the
+    // cursor will always produce rows in the same order.
+    results.sort_by_key(|(time, _)| *time);
+    let mut results: Vec<Row> = results.into_iter().map(|(_, row)| row).collect();
+
+    if connector.emit_progress {
+        if let Some(upper) = batch_upper(batches.last()) {
+            // The user has requested progress messages and there's at least one
+            // batch. All of the batches might have zero rows, so we do not depend on
+            // results at all. Another benefit of using upper (instead of the largest row
+            // time) is that the batch's upper may be larger than the row time.
+            packer.push(Datum::Decimal(Significand::new(i128::from(upper))));
+            packer.push(Datum::True);
+            // Fill in the diff column and all table columns with NULL.
+            for _ in 0..(connector.object_columns + 1) {
+                packer.push(Datum::Null);
             }
+            results.push(packer.finish_and_reuse());
+        }
+    }
 
-            // TODO(benesch): this blocks the Timely thread until the send
-            // completes. Hopefully it's just a quick write to a kernel buffer,
-            // but perhaps not if the batch gets too large? We may need to do
-            // something smarter, like offloading to a networking thread.
-            block_on(tx.send(results)).expect("tail send failed");
-        });
-    })
+    results
 }
 
 fn batch_upper(
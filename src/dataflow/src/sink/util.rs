@@ -7,6 +7,8 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::BTreeMap;
+
 use differential_dataflow::operators::arrange::ShutdownButton;
 use timely::dataflow::channels::pact::ParallelizationContract;
 use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
@@ -14,6 +16,8 @@ use timely::dataflow::operators::generic::{FrontieredInputHandle, OperatorInfo};
 use timely::dataflow::{Scope, Stream};
 use timely::Data;
 
+use repr::{Diff, Row};
+
 pub fn sink_reschedule<G: Scope, D, B, L, P, T>(
     s: &Stream<G, D>,
     pact: P,
@@ -40,3 +44,36 @@ where
 
     button
 }
+
+/// Reduces the updates for a single timestamp to at most one update per key,
+/// where the key is made up of the columns at `key_indices`. The key must
+/// uniquely identify the rows of the input; the coordinator rejects upsert
+/// sinks and tails whose key is not known to be unique.
+///
+/// A key whose consolidated updates include an insertion produces that row
+/// with a diff of 1. A key with only retractions produces one of the retracted
+/// rows with a diff of -1, marking the key as deleted.
+pub fn latest_per_key(key_indices: &[usize], rows: Vec<(Row, Diff)>) -> Vec<(Row, Diff)> {
+    let mut consolidated: BTreeMap<Row, Diff> = BTreeMap::new();
+    for (row, diff) in rows {
+        *consolidated.entry(row).or_insert(0) += diff;
+    }
+
+    let mut latest: BTreeMap<Row, (Row, Diff)> = BTreeMap::new();
+    for (row, diff) in consolidated {
+        if diff == 0 {
+            continue;
+        }
+        let key = {
+            let datums = row.unpack();
+            Row::pack(key_indices.iter().map(|i| datums[*i]))
+        };
+        match latest.get(&key) {
+            Some((_, existing)) if *existing > 0 || diff < 0 => (),
+            _ => {
+                latest.insert(key, (row, diff.signum()));
+            }
+        }
+    }
+    latest.into_iter().map(|(_, update)| update).collect()
+}
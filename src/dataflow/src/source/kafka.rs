@@ -223,7 +223,7 @@ impl SourceInfo<Vec<u8>> for KafkaSourceInfo {
         };
         for i in self.known_partitions..=pid {
             if self.has_partition(i) {
-                self.create_partition_queue(i);
+                self.create_partition_queue(i, Offset::Beginning);
                 consistency_info.partition_metrics.insert(
                     PartitionId::Kafka(i),
                     PartitionMetrics::new(
@@ -372,6 +372,27 @@ impl SourceInfo<Vec<u8>> for KafkaSourceInfo {
         self.buffered_metadata.insert(consumer.pid);
     }
 
+    /// Recreates the partition queues of the partitions that this worker was
+    /// reading, starting each from the message after the last one processed.
+    fn resume(&mut self, consistency_info: &mut ConsistencyInfo) {
+        let partition_count = consistency_info
+            .partition_metadata
+            .len()
+            .try_into()
+            .unwrap();
+        for pid in 0..partition_count {
+            if self.has_partition(pid) {
+                // Resume after the last offset that was processed.
+                let mut next_offset =
+                    consistency_info.partition_metadata[&PartitionId::Kafka(pid)].offset;
+                next_offset.offset += 1;
+                let next_offset: KafkaOffset = next_offset.into();
+                self.create_partition_queue(pid, Offset::Offset(next_offset.offset));
+            }
+        }
+        self.known_partitions = partition_count;
+    }
+
     fn next_persisted_file(&mut self) -> Option<Vec<(Vec<u8>, Vec<u8>, Timestamp, i64)>> {
         if let Some(f) = &self.persisted_files.pop() {
             debug!("reading persisted data from {}", f.display());
@@ -530,8 +551,9 @@ impl KafkaSourceInfo {
         self.buffered_metadata.contains(&pid)
     }
 
-    /// Creates a new partition queue for `partition_id`.
-    fn create_partition_queue(&mut self, partition_id: i32) {
+    /// Creates a new partition queue for `partition_id`, which starts reading
+    /// at `offset`.
+    fn create_partition_queue(&mut self, partition_id: i32, offset: Offset) {
         info!(
             "Activating Kafka queue for {} [{}] (source {}) on worker {}",
             self.topic_name, partition_id, self.id, self.worker_id
@@ -549,7 +571,7 @@ impl KafkaSourceInfo {
             );
         }
         // Add new partition
-        partition_list.add_partition_offset(&self.topic_name, partition_id, Offset::Beginning);
+        partition_list.add_partition_offset(&self.topic_name, partition_id, offset);
         self.consumer
             .assign(&partition_list)
             .expect("assignment known to be valid");
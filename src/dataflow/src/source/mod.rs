@@ -185,6 +185,10 @@ where
     }
 }
 
+/// A new connector and timestamp frequency for a running source, which the
+/// source operator picks up the next time it is scheduled.
+pub(crate) type SourceReconfiguration = Rc<RefCell<Option<(ExternalSourceConnector, Duration)>>>;
+
 /// A `SourceToken` manages interest in a source.
 ///
 /// When the `SourceToken` is dropped the associated source will be stopped.
@@ -195,6 +199,8 @@ pub struct SourceToken {
     /// A reference to the timestamper control channel. Inserts a timestamp drop message
     /// when this source token is dropped
     timestamp_drop: Option<TimestampMetadataUpdates>,
+    /// A slot for a new configuration of the source operator.
+    reconfiguration: SourceReconfiguration,
 }
 
 impl SourceToken {
@@ -202,6 +208,14 @@ impl SourceToken {
     pub fn activate(&self) {
         self.activator.activate();
     }
+
+    /// Restarts the associated timely source operator with a new connector
+    /// and timestamp frequency. The operator resumes reading the source where
+    /// it left off.
+    pub fn reconfigure(&self, connector: ExternalSourceConnector, timestamp_frequency: Duration) {
+        *self.reconfiguration.borrow_mut() = Some((connector, timestamp_frequency));
+        self.activator.activate();
+    }
 }
 
 impl Drop for SourceToken {
@@ -339,6 +353,16 @@ pub(crate) trait SourceInfo<Out> {
     /// Buffer a message that cannot get timestamped
     fn buffer_message(&mut self, message: SourceMessage<Out>);
 
+    /// Resumes reading the source after the offsets recorded in
+    /// `consistency_info`, when this source replaces one that was reading
+    /// the same source under a different connector.
+    ///
+    /// Sources whose connectors cannot be changed while they are running
+    /// need not implement this.
+    fn resume(&mut self, _consistency_info: &mut ConsistencyInfo) {
+        unreachable!("source cannot be resumed with a new connector")
+    }
+
     /// Persist a message
     fn persist_message(
         &self,
@@ -470,6 +494,12 @@ impl ConsistencyInfo {
         }
     }
 
+    /// Changes the frequency at which timestamps are closed.
+    fn set_timestamp_frequency(&mut self, timestamp_frequency: Duration) {
+        // Safe conversion: statement.rs checks that value specified fits in u64
+        self.downgrade_capability_frequency = timestamp_frequency.as_millis().try_into().unwrap();
+    }
+
     /// Returns true if we currently know of particular partition. We know (and have updated the
     /// metadata for this partition) if there is an entry for it
     pub fn knows_of(&self, pid: PartitionId) -> bool {
@@ -881,128 +911,232 @@ where
         timestamp_tx,
     );
 
-    let (stream, capability) = source(id, timestamp_channel, scope, name.clone(), move |info| {
-        // Create activator for source
-        let activator = scope.activator_for(&info.address[..]);
-
-        // Create control plane information (Consistency-related information)
-        let mut consistency_info = ConsistencyInfo::new(
-            name.clone(),
-            id,
-            worker_id,
-            consistency,
-            timestamp_frequency,
-            &source_connector,
-        );
-
-        // Create source information (this function is specific to a specific
-        // source
-        let mut source_info: Result<S, anyhow::Error> = SourceConstructor::<Out>::new(
-            name.clone(),
-            id,
-            active,
-            worker_id,
-            worker_count,
-            logger,
-            scope.sync_activator_for(&info.address[..]),
-            source_connector.clone(),
-            &mut consistency_info,
-            encoding,
-        );
-
-        let mut read_persisted_files = false;
-        let mut predecessor = None;
-
-        move |cap, output| {
-            // First check that the source was successfully created
-            let source_info = match &mut source_info {
-                Ok(source_info) => source_info,
-                Err(e) => {
-                    error!("Failed to create source: {}", e);
-                    return SourceStatus::Done;
+    let reconfiguration = SourceReconfiguration::default();
+    let (stream, capability) = source(
+        id,
+        timestamp_channel,
+        reconfiguration.clone(),
+        scope,
+        name.clone(),
+        move |info| {
+            // Create activator for source
+            let activator = scope.activator_for(&info.address[..]);
+            let consumer_activator = scope.sync_activator_for(&info.address[..]);
+
+            // Create control plane information (Consistency-related information)
+            let mut consistency_info = ConsistencyInfo::new(
+                name.clone(),
+                id,
+                worker_id,
+                consistency,
+                timestamp_frequency,
+                &source_connector,
+            );
+
+            // Create source information (this function is specific to a specific
+            // source
+            let mut source_info: Result<S, anyhow::Error> = SourceConstructor::<Out>::new(
+                name.clone(),
+                id,
+                active,
+                worker_id,
+                worker_count,
+                logger.clone(),
+                consumer_activator.clone(),
+                source_connector.clone(),
+                &mut consistency_info,
+                encoding.clone(),
+            );
+
+            let mut source_connector = source_connector;
+            let mut read_persisted_files = false;
+            let mut predecessor = None;
+
+            move |cap, output| {
+                if let Some((connector, timestamp_frequency)) = reconfiguration.borrow_mut().take()
+                {
+                    consistency_info.set_timestamp_frequency(timestamp_frequency);
+                    // Only a change of connector requires the source to be
+                    // reconstructed, picking up where its predecessor left off.
+                    if connector != source_connector {
+                        source_info = SourceConstructor::<Out>::new(
+                            name.clone(),
+                            id,
+                            active,
+                            worker_id,
+                            worker_count,
+                            logger.clone(),
+                            consumer_activator.clone(),
+                            connector.clone(),
+                            &mut consistency_info,
+                            encoding.clone(),
+                        );
+                        if let Ok(source_info) = &mut source_info {
+                            source_info.resume(&mut consistency_info);
+                        }
+                        source_connector = connector;
+                    }
                 }
-            };
 
-            if !active {
-                return SourceStatus::Done;
-            }
+                // First check that the source was successfully created
+                let source_info = match &mut source_info {
+                    Ok(source_info) => source_info,
+                    Err(e) => {
+                        error!("Failed to create source: {}", e);
+                        return SourceStatus::Done;
+                    }
+                };
 
-            if !read_persisted_files {
-                // Downgrade capability (if possible) before reading next persisted file.
-                consistency_info.downgrade_capability(&id, cap, source_info, &timestamp_histories);
+                if !active {
+                    return SourceStatus::Done;
+                }
 
-                if let Some(msgs) = source_info.next_persisted_file() {
-                    // TODO(rkhaitan) change this to properly re-use old timestamps.
-                    // Currently this is hard to do because there can be arbitrary delays between
-                    // different workers being scheduled, and this means that all persisted state
-                    // can potentially get pulled into memory without being able to close timestamps
-                    // which causes the system to go out of memory.
-                    // For now, constrain we constrain persistence to RT sources, and we re-assign
-                    // timestamps to persisted messages on startup.
-                    let ts = consistency_info.find_matching_rt_timestamp();
-                    let ts_cap = cap.delayed(&ts);
-                    for m in msgs {
-                        output.session(&ts_cap).give(Ok(SourceOutput::new(
-                            m.0,
-                            m.1,
-                            Some(m.3),
-                            None, // upstream timestamps are normalized before they are persisted
-                        )));
-                    }
+                if !read_persisted_files {
+                    // Downgrade capability (if possible) before reading next persisted file.
+                    consistency_info.downgrade_capability(
+                        &id,
+                        cap,
+                        source_info,
+                        &timestamp_histories,
+                    );
+
+                    if let Some(msgs) = source_info.next_persisted_file() {
+                        // TODO(rkhaitan) change this to properly re-use old timestamps.
+                        // Currently this is hard to do because there can be arbitrary delays between
+                        // different workers being scheduled, and this means that all persisted state
+                        // can potentially get pulled into memory without being able to close timestamps
+                        // which causes the system to go out of memory.
+                        // For now, constrain we constrain persistence to RT sources, and we re-assign
+                        // timestamps to persisted messages on startup.
+                        let ts = consistency_info.find_matching_rt_timestamp();
+                        let ts_cap = cap.delayed(&ts);
+                        for m in msgs {
+                            output.session(&ts_cap).give(Ok(SourceOutput::new(
+                                m.0,
+                                m.1,
+                                Some(m.3),
+                                None, // upstream timestamps are normalized before they are persisted
+                            )));
+                        }
 
-                    // Yield to give downstream operators time to handle this data.
-                    activator.activate_after(Duration::from_millis(10));
-                    return SourceStatus::Alive;
-                } else {
-                    // We've finished reading all persistence data
-                    read_persisted_files = true;
+                        // Yield to give downstream operators time to handle this data.
+                        activator.activate_after(Duration::from_millis(10));
+                        return SourceStatus::Alive;
+                    } else {
+                        // We've finished reading all persistence data
+                        read_persisted_files = true;
+                    }
                 }
-            }
 
-            // Bound execution of operator to prevent a single operator from hogging
-            // the CPU if there are many messages to process
-            let timer = Instant::now();
-            // Accumulate updates to BYTES_READ_COUNTER for Prometheus metrics collection
-            let mut bytes_read = 0;
-            // Accumulate updates to offsets for system table metrics collection
-            let mut metric_updates = HashMap::new();
-
-            // Record operator has been scheduled
-            consistency_info
-                .source_metrics
-                .operator_scheduled_counter
-                .inc();
-
-            loop {
-                match source_info.get_next_message(&mut consistency_info, &activator) {
-                    Ok(NextMessage::Ready(message)) => {
-                        let partition = message.partition.clone();
-                        let offset = message.offset;
-                        let msg_predecessor = predecessor;
-                        predecessor = Some(offset);
-
-                        // Update ingestion metrics. Guaranteed to exist as the appropriate
-                        // entry gets created in SourceConstructor or when a new partition
-                        // is discovered
-                        consistency_info
-                            .partition_metrics
-                            .get_mut(&partition)
-                            .unwrap()
-                            .offset_received
-                            .set(offset.offset);
-
-                        // Determine the timestamp to which we need to assign this message
-                        let ts = consistency_info.find_matching_timestamp(
-                            &id,
-                            &partition,
-                            offset,
-                            &timestamp_histories,
-                        );
-                        match ts {
-                            None => {
-                                // We have not yet decided on a timestamp for this message,
-                                // we need to buffer the message
-                                source_info.buffer_message(message);
+                // Bound execution of operator to prevent a single operator from hogging
+                // the CPU if there are many messages to process
+                let timer = Instant::now();
+                // Accumulate updates to BYTES_READ_COUNTER for Prometheus metrics collection
+                let mut bytes_read = 0;
+                // Accumulate updates to offsets for system table metrics collection
+                let mut metric_updates = HashMap::new();
+
+                // Record operator has been scheduled
+                consistency_info
+                    .source_metrics
+                    .operator_scheduled_counter
+                    .inc();
+
+                loop {
+                    match source_info.get_next_message(&mut consistency_info, &activator) {
+                        Ok(NextMessage::Ready(message)) => {
+                            let partition = message.partition.clone();
+                            let offset = message.offset;
+                            let msg_predecessor = predecessor;
+                            predecessor = Some(offset);
+
+                            // Update ingestion metrics. Guaranteed to exist as the appropriate
+                            // entry gets created in SourceConstructor or when a new partition
+                            // is discovered
+                            consistency_info
+                                .partition_metrics
+                                .get_mut(&partition)
+                                .unwrap()
+                                .offset_received
+                                .set(offset.offset);
+
+                            // Determine the timestamp to which we need to assign this message
+                            let ts = consistency_info.find_matching_timestamp(
+                                &id,
+                                &partition,
+                                offset,
+                                &timestamp_histories,
+                            );
+                            match ts {
+                                None => {
+                                    // We have not yet decided on a timestamp for this message,
+                                    // we need to buffer the message
+                                    source_info.buffer_message(message);
+                                    consistency_info.downgrade_capability(
+                                        &id,
+                                        cap,
+                                        source_info,
+                                        &timestamp_histories,
+                                    );
+                                    activator.activate();
+                                    return SourceStatus::Alive;
+                                }
+                                Some(ts) => {
+                                    if let Some((off, t)) = metric_updates.get_mut(&partition) {
+                                        *off = offset;
+                                        *t = ts;
+                                    } else {
+                                        metric_updates.insert(partition.clone(), (offset, ts));
+                                    }
+
+                                    source_info.persist_message(
+                                        &mut persistence_tx,
+                                        &message,
+                                        ts,
+                                        msg_predecessor,
+                                    );
+                                    // Note: empty and null payload/keys are currently
+                                    // treated as the same thing.
+                                    let key = message.key.unwrap_or_default();
+                                    let out = message.payload.unwrap_or_default();
+                                    // Entry for partition_metadata is guaranteed to exist as messages
+                                    // are only processed after we have updated the partition_metadata for a
+                                    // partition and created a partition queue for it.
+                                    consistency_info
+                                        .partition_metadata
+                                        .get_mut(&partition)
+                                        .unwrap()
+                                        .offset = offset;
+                                    bytes_read += key.len() as i64;
+                                    bytes_read += out.len().unwrap_or(0) as i64;
+                                    let ts_cap = cap.delayed(&ts);
+
+                                    output.session(&ts_cap).give(Ok(SourceOutput::new(
+                                        key,
+                                        out,
+                                        Some(offset.offset),
+                                        message.upstream_time_millis,
+                                    )));
+
+                                    // Update ingestion metrics
+                                    // Entry is guaranteed to exist as it gets created when we initialise the partition
+                                    let partition_metrics = consistency_info
+                                        .partition_metrics
+                                        .get_mut(&partition)
+                                        .unwrap();
+                                    partition_metrics.offset_ingested.set(offset.offset);
+                                    partition_metrics.messages_ingested.inc();
+                                }
+                            }
+
+                            //TODO(ncrooks): this behaviour should probably be made configurable
+                            if timer.elapsed().as_millis() > 10 {
+                                // We didn't drain the entire queue, so indicate that we
+                                // should run again.
+                                if bytes_read > 0 {
+                                    BYTES_READ_COUNTER.inc_by(bytes_read);
+                                }
+                                // Downgrade capability (if possible) before exiting
                                 consistency_info.downgrade_capability(
                                     &id,
                                     cap,
@@ -1012,115 +1146,51 @@ where
                                 activator.activate();
                                 return SourceStatus::Alive;
                             }
-                            Some(ts) => {
-                                if let Some((off, t)) = metric_updates.get_mut(&partition) {
-                                    *off = offset;
-                                    *t = ts;
-                                } else {
-                                    metric_updates.insert(partition.clone(), (offset, ts));
-                                }
-
-                                source_info.persist_message(
-                                    &mut persistence_tx,
-                                    &message,
-                                    ts,
-                                    msg_predecessor,
-                                );
-                                // Note: empty and null payload/keys are currently
-                                // treated as the same thing.
-                                let key = message.key.unwrap_or_default();
-                                let out = message.payload.unwrap_or_default();
-                                // Entry for partition_metadata is guaranteed to exist as messages
-                                // are only processed after we have updated the partition_metadata for a
-                                // partition and created a partition queue for it.
-                                consistency_info
-                                    .partition_metadata
-                                    .get_mut(&partition)
-                                    .unwrap()
-                                    .offset = offset;
-                                bytes_read += key.len() as i64;
-                                bytes_read += out.len().unwrap_or(0) as i64;
-                                let ts_cap = cap.delayed(&ts);
-
-                                output.session(&ts_cap).give(Ok(SourceOutput::new(
-                                    key,
-                                    out,
-                                    Some(offset.offset),
-                                    message.upstream_time_millis,
-                                )));
-
-                                // Update ingestion metrics
-                                // Entry is guaranteed to exist as it gets created when we initialise the partition
-                                let partition_metrics = consistency_info
-                                    .partition_metrics
-                                    .get_mut(&partition)
-                                    .unwrap();
-                                partition_metrics.offset_ingested.set(offset.offset);
-                                partition_metrics.messages_ingested.inc();
-                            }
                         }
-
-                        //TODO(ncrooks): this behaviour should probably be made configurable
-                        if timer.elapsed().as_millis() > 10 {
-                            // We didn't drain the entire queue, so indicate that we
-                            // should run again.
-                            if bytes_read > 0 {
-                                BYTES_READ_COUNTER.inc_by(bytes_read);
+                        Ok(NextMessage::Pending) => {
+                            // There were no new messages
+                            break;
+                        }
+                        Ok(NextMessage::Finished) => {
+                            if let Consistency::RealTime = consistency_info.source_type {
+                                return SourceStatus::Done;
+                            } else {
+                                break;
                             }
-                            // Downgrade capability (if possible) before exiting
+                        }
+                        Err(e) => {
+                            output.session(&cap).give(Err(e.to_string()));
                             consistency_info.downgrade_capability(
                                 &id,
                                 cap,
                                 source_info,
                                 &timestamp_histories,
                             );
-                            activator.activate();
-                            return SourceStatus::Alive;
-                        }
-                    }
-                    Ok(NextMessage::Pending) => {
-                        // There were no new messages
-                        break;
-                    }
-                    Ok(NextMessage::Finished) => {
-                        if let Consistency::RealTime = consistency_info.source_type {
                             return SourceStatus::Done;
-                        } else {
-                            break;
                         }
                     }
-                    Err(e) => {
-                        output.session(&cap).give(Err(e.to_string()));
-                        consistency_info.downgrade_capability(
-                            &id,
-                            cap,
-                            source_info,
-                            &timestamp_histories,
-                        );
-                        return SourceStatus::Done;
-                    }
                 }
-            }
 
-            for (partition, (offset, ts)) in metric_updates {
-                let partition_metrics = consistency_info
-                    .partition_metrics
-                    .get_mut(&partition)
-                    .unwrap();
-                partition_metrics.record_offset(offset.offset, ts as i64);
-            }
+                for (partition, (offset, ts)) in metric_updates {
+                    let partition_metrics = consistency_info
+                        .partition_metrics
+                        .get_mut(&partition)
+                        .unwrap();
+                    partition_metrics.record_offset(offset.offset, ts as i64);
+                }
 
-            // Downgrade capability (if possible) before exiting
-            consistency_info.downgrade_capability(&id, cap, source_info, &timestamp_histories);
+                // Downgrade capability (if possible) before exiting
+                consistency_info.downgrade_capability(&id, cap, source_info, &timestamp_histories);
 
-            // Ensure that we activate the source frequently enough to keep downgrading
-            // capabilities, even when no data has arrived
-            activator.activate_after(Duration::from_millis(
-                consistency_info.downgrade_capability_frequency,
-            ));
-            SourceStatus::Alive
-        }
-    });
+                // Ensure that we activate the source frequently enough to keep downgrading
+                // capabilities, even when no data has arrived
+                activator.activate_after(Duration::from_millis(
+                    consistency_info.downgrade_capability_frequency,
+                ));
+                SourceStatus::Alive
+            }
+        },
+    );
 
     let (ok_stream, err_stream) = stream.map_fallible(|r| r.map_err(SourceError::FileIO));
 
@@ -22,7 +22,7 @@ use timely::Data;
 use crate::server::TimestampMetadataUpdates;
 use repr::Timestamp;
 
-use super::{SourceStatus, SourceToken};
+use super::{SourceReconfiguration, SourceStatus, SourceToken};
 
 /// Constructs a source named `name` in `scope` whose lifetime is controlled
 /// both internally and externally.
@@ -52,9 +52,13 @@ use super::{SourceStatus, SourceToken};
 ///
 /// When the source token is dropped, the timestamping_flag is set to false
 /// to terminate any spawned threads in the source operator
+///
+/// New configurations handed to the source token are deposited in
+/// `reconfiguration`, for `tick` to pick up.
 pub fn source<G, D, B, L>(
     id: SourceInstanceId,
     timestamp_channel: Option<TimestampMetadataUpdates>,
+    reconfiguration: SourceReconfiguration,
     scope: &G,
     name: String,
     construct: B,
@@ -79,6 +83,7 @@ where
             capability: cap.clone(),
             activator: scope.activator_for(&info.address[..]),
             timestamp_drop: timestamp_channel,
+            reconfiguration,
         });
 
         let mut tick = construct(info);
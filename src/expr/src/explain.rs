@@ -19,8 +19,8 @@
 //!
 //! It's important to avoid trailing whitespace everywhere, because it plays havoc with SLT
 use super::{
-    AggregateExpr, Id, IdHumanizer, JoinImplementation, LocalId, RelationExpr, RowSetFinishing,
-    ScalarExpr,
+    AggregateExpr, Id, IdHumanizer, JoinImplementation, LocalId, NullaryFunc, RelationExpr,
+    RowSetFinishing, ScalarExpr,
 };
 use repr::RelationType;
 use std::collections::HashMap;
@@ -376,6 +376,7 @@ impl std::fmt::Display for ScalarExpr {
             Column(i) => write!(f, "#{}", i)?,
             Literal(Ok(row), _) => write!(f, "{}", row.unpack_first())?,
             Literal(Err(e), _) => write!(f, "(err: {})", e)?,
+            CallNullary(func @ NullaryFunc::Parameter(..)) => write!(f, "{}", func)?,
             CallNullary(func) => write!(f, "{}()", func)?,
             CallUnary { func, expr } => {
                 write!(f, "{}({})", func, expr)?;
@@ -7,11 +7,22 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Bound;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{scalar::EvalError, RelationExpr, ScalarExpr};
+use crate::{scalar::EvalError, BinaryFunc, RelationExpr, ScalarExpr};
 use repr::{Datum, Row, RowArena, RowPacker};
 
+/// The largest number of keys that `MapFilterProject::literal_keys` will
+/// enumerate.
+///
+/// Each key results in a separate seek into an arrangement; past some number
+/// of keys it is cheaper to scan the arrangement instead.
+const MAX_LITERAL_KEYS: usize = 1024;
+
 /// A compound operator that can be applied row-by-row.
 ///
 /// This operator integrates the map, filter, and project operators.
@@ -232,6 +243,180 @@ impl MapFilterProject {
         Some(row_packer.finish_and_reuse())
     }
 
+    /// Determines the literal datums that a scalar expression may take, if there
+    /// are few enough of them to enumerate.
+    ///
+    /// The datums are derived from equality predicates, from disjunctions of
+    /// equality predicates (which is how `IN` lists are planned), and from
+    /// integer ranges that are bounded on both sides. Any other comparisons
+    /// against literals further restrict the datums. The result is sorted and
+    /// deduplicated; an empty result means that no datum satisfies the
+    /// predicates.
+    pub fn literal_values(&self, expr: &ScalarExpr) -> Option<Vec<Datum>> {
+        let mut values: Option<Vec<Datum>> = None;
+        let mut bounds = Vec::new();
+        for (_pos, predicate) in self.predicates.iter() {
+            if let Some(mut disjuncts) = literal_disjuncts(predicate, expr) {
+                disjuncts.sort();
+                disjuncts.dedup();
+                values = Some(match values {
+                    None => disjuncts,
+                    Some(values) => values
+                        .into_iter()
+                        .filter(|datum| disjuncts.binary_search(datum).is_ok())
+                        .collect(),
+                });
+            } else if let Some(bound) = literal_bound(predicate, expr) {
+                bounds.push(bound);
+            }
+        }
+        let values = match values {
+            Some(values) => values,
+            None => enumerate_range(&bounds)?,
+        };
+        Some(
+            values
+                .into_iter()
+                .filter(|datum| {
+                    bounds.iter().all(|(func, bound)| match func {
+                        BinaryFunc::Lt => datum < bound,
+                        BinaryFunc::Lte => datum <= bound,
+                        BinaryFunc::Gt => datum > bound,
+                        BinaryFunc::Gte => datum >= bound,
+                        _ => unreachable!(),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Determines the literal rows that a sequence of scalar expressions may take.
+    ///
+    /// This generalizes `literal_constraints` to expressions constrained to a few
+    /// datums each (see `literal_values`), and returns every combination of these
+    /// datums, sorted and deduplicated. Like `literal_constraints`, this method
+    /// returns `None` on an empty `exprs`. It also returns `None` if there would be
+    /// more than `MAX_LITERAL_KEYS` rows.
+    pub fn literal_keys(&self, exprs: &[ScalarExpr]) -> Option<Vec<Row>> {
+        if exprs.is_empty() {
+            return None;
+        }
+        let mut columns = Vec::with_capacity(exprs.len());
+        let mut count = 1;
+        for expr in exprs {
+            let values = self.literal_values(expr)?;
+            count *= values.len();
+            if count > MAX_LITERAL_KEYS {
+                return None;
+            }
+            columns.push(values);
+        }
+        let mut row_packer = RowPacker::new();
+        let mut rows = Vec::with_capacity(count);
+        let mut datums = Vec::with_capacity(columns.len());
+        cartesian_product(&columns, &mut datums, &mut |datums| {
+            row_packer.extend(datums.iter());
+            rows.push(row_packer.finish_and_reuse());
+        });
+        // Arrangements are ordered by key, and so visiting keys in sorted order
+        // allows a cursor to seek to each of them in turn.
+        rows.sort();
+        rows.dedup();
+        Some(rows)
+    }
+
+    /// Determines a range of keys outside of which no key can satisfy the predicates.
+    ///
+    /// The range is described by bounds on a prefix of the key expressions: every
+    /// expression but the last in the prefix must equal a literal, and the last
+    /// may instead be bounded above and below by literals (e.g., by `BETWEEN`).
+    /// Keys outside of the range can be skipped without visiting their values.
+    /// Arrangements are not ordered by the datums of their keys, so the range
+    /// does not identify a contiguous run of keys and every key must still be
+    /// checked. This method returns `None` if the predicates do not bound the
+    /// first key expression.
+    pub fn key_range(&self, exprs: &[ScalarExpr]) -> Option<KeyRange> {
+        let mut prefix = Vec::new();
+        let mut lower = Bound::Unbounded;
+        let mut upper = Bound::Unbounded;
+        for expr in exprs {
+            if let Some(datum) = self.literal_constraint(expr) {
+                prefix.push(datum);
+                continue;
+            }
+            for (_pos, predicate) in self.predicates.iter() {
+                match literal_bound(predicate, expr) {
+                    Some((BinaryFunc::Gt, datum)) => {
+                        lower = tighter(lower, datum, false, Ordering::Greater)
+                    }
+                    Some((BinaryFunc::Gte, datum)) => {
+                        lower = tighter(lower, datum, true, Ordering::Greater)
+                    }
+                    Some((BinaryFunc::Lt, datum)) => {
+                        upper = tighter(upper, datum, false, Ordering::Less)
+                    }
+                    Some((BinaryFunc::Lte, datum)) => {
+                        upper = tighter(upper, datum, true, Ordering::Less)
+                    }
+                    _ => (),
+                }
+            }
+            break;
+        }
+        if prefix.is_empty() && lower == Bound::Unbounded && upper == Bound::Unbounded {
+            return None;
+        }
+        let mut row_packer = RowPacker::new();
+        let mut bound_row = |bound: Bound<Datum>| match bound {
+            Bound::Included(datum) => {
+                Bound::Included(row_packer.pack(prefix.iter().chain(Some(&datum))))
+            }
+            Bound::Excluded(datum) => {
+                Bound::Excluded(row_packer.pack(prefix.iter().chain(Some(&datum))))
+            }
+            Bound::Unbounded if prefix.is_empty() => Bound::Unbounded,
+            Bound::Unbounded => Bound::Included(row_packer.pack(prefix.iter())),
+        };
+        Some(KeyRange {
+            lower: bound_row(lower),
+            upper: bound_row(upper),
+        })
+    }
+
+    /// Extracts the predicates that can be evaluated on the key of an arrangement.
+    ///
+    /// `exprs` are the key expressions of the arrangement. A predicate is returned
+    /// if every column it references is itself one of the key expressions, and its
+    /// column references are rewritten to refer to positions in the key. Keys for
+    /// which these predicates do not all evaluate to `Datum::True` cannot produce
+    /// output, and so their values need not be visited.
+    pub fn key_predicates(&self, exprs: &[ScalarExpr]) -> Vec<ScalarExpr> {
+        let key_columns = exprs
+            .iter()
+            .enumerate()
+            .filter_map(|(position, expr)| match expr {
+                ScalarExpr::Column(column) => Some((*column, position)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        self.predicates
+            .iter()
+            .filter(|(_pos, predicate)| {
+                let support = predicate.support();
+                !support.is_empty() && support.iter().all(|c| key_columns.contains_key(c))
+            })
+            .map(|(_pos, predicate)| {
+                let mut predicate = predicate.clone();
+                predicate.visit_mut(&mut |e| {
+                    if let ScalarExpr::Column(column) = e {
+                        *column = key_columns[&*column];
+                    }
+                });
+                predicate
+            })
+            .collect()
+    }
+
     /// Extracts any MapFilterProject at the root of the expression.
     ///
     /// The expression will be modified to extract any maps, filters, and
@@ -264,3 +449,192 @@ impl MapFilterProject {
         unimplemented!()
     }
 }
+
+/// A range of the keys of an arrangement.
+///
+/// Each bound is a row of leading datums, against which the corresponding
+/// leading datums of a key are compared. See [`MapFilterProject::key_range`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KeyRange {
+    /// The least key in the range.
+    pub lower: Bound<Row>,
+    /// The greatest key in the range.
+    pub upper: Bound<Row>,
+}
+
+impl KeyRange {
+    /// Determines whether `key` lies within the range.
+    pub fn contains(&self, key: &Row) -> bool {
+        let above_lower = match &self.lower {
+            Bound::Included(row) => compare_prefix(key, row) != Ordering::Less,
+            Bound::Excluded(row) => compare_prefix(key, row) == Ordering::Greater,
+            Bound::Unbounded => true,
+        };
+        let below_upper = match &self.upper {
+            Bound::Included(row) => compare_prefix(key, row) != Ordering::Greater,
+            Bound::Excluded(row) => compare_prefix(key, row) == Ordering::Less,
+            Bound::Unbounded => true,
+        };
+        above_lower && below_upper
+    }
+}
+
+/// Compares the leading datums of `key` with the datums of `bound`.
+fn compare_prefix(key: &Row, bound: &Row) -> Ordering {
+    let mut datums = key.iter();
+    for bound in bound.iter() {
+        match datums.next() {
+            Some(datum) => match datum.cmp(&bound) {
+                Ordering::Equal => (),
+                ordering => return ordering,
+            },
+            None => return Ordering::Less,
+        }
+    }
+    Ordering::Equal
+}
+
+/// Combines `bound` with a new bound at `datum`, keeping whichever is tighter.
+///
+/// A bound is tighter if its datum is further in the direction of `inward`, or
+/// if it is exclusive and the datums are equal.
+fn tighter<'a>(
+    bound: Bound<Datum<'a>>,
+    datum: Datum<'a>,
+    inclusive: bool,
+    inward: Ordering,
+) -> Bound<Datum<'a>> {
+    let new = if inclusive {
+        Bound::Included(datum)
+    } else {
+        Bound::Excluded(datum)
+    };
+    match bound {
+        Bound::Unbounded => new,
+        Bound::Included(old) | Bound::Excluded(old) if datum.cmp(&old) == inward => new,
+        Bound::Included(old) if datum == old && !inclusive => new,
+        bound => bound,
+    }
+}
+
+/// Determines the literal datums that `predicate` equates with `expr`, if it is
+/// a disjunction of such equalities.
+fn literal_disjuncts<'a>(predicate: &'a ScalarExpr, expr: &ScalarExpr) -> Option<Vec<Datum<'a>>> {
+    match predicate {
+        ScalarExpr::CallBinary {
+            func: BinaryFunc::Eq,
+            expr1,
+            expr2,
+        } => {
+            if let Some(Ok(datum1)) = expr1.as_literal() {
+                if &**expr2 == expr {
+                    return Some(vec![datum1]);
+                }
+            }
+            if let Some(Ok(datum2)) = expr2.as_literal() {
+                if &**expr1 == expr {
+                    return Some(vec![datum2]);
+                }
+            }
+            None
+        }
+        ScalarExpr::CallBinary {
+            func: BinaryFunc::Or,
+            expr1,
+            expr2,
+        } => {
+            let mut disjuncts = literal_disjuncts(expr1, expr)?;
+            disjuncts.extend(literal_disjuncts(expr2, expr)?);
+            Some(disjuncts)
+        }
+        _ if predicate.is_literal_false() => Some(vec![]),
+        _ => None,
+    }
+}
+
+/// Determines the comparison, if any, that `predicate` imposes between `expr`
+/// and a non-null literal datum.
+///
+/// The comparison is oriented so that `expr` is on its left-hand side.
+fn literal_bound<'a>(
+    predicate: &'a ScalarExpr,
+    expr: &ScalarExpr,
+) -> Option<(BinaryFunc, Datum<'a>)> {
+    if let ScalarExpr::CallBinary { func, expr1, expr2 } = predicate {
+        let flipped = match func {
+            BinaryFunc::Lt => BinaryFunc::Gt,
+            BinaryFunc::Lte => BinaryFunc::Gte,
+            BinaryFunc::Gt => BinaryFunc::Lt,
+            BinaryFunc::Gte => BinaryFunc::Lte,
+            _ => return None,
+        };
+        if let Some(Ok(datum)) = expr2.as_literal() {
+            if &**expr1 == expr && !datum.is_null() {
+                return Some((func.clone(), datum));
+            }
+        }
+        if let Some(Ok(datum)) = expr1.as_literal() {
+            if &**expr2 == expr && !datum.is_null() {
+                return Some((flipped, datum));
+            }
+        }
+    }
+    None
+}
+
+/// Enumerates the integers that satisfy `bounds`, if they are bounded on both
+/// sides and there are at most `MAX_LITERAL_KEYS` of them.
+fn enumerate_range<'a>(bounds: &[(BinaryFunc, Datum<'a>)]) -> Option<Vec<Datum<'a>>> {
+    let mut lower: Option<i128> = None;
+    let mut upper: Option<i128> = None;
+    let mut wide = None;
+    for (func, bound) in bounds {
+        let (value, is_wide) = match bound {
+            Datum::Int32(i) => (i128::from(*i), false),
+            Datum::Int64(i) => (i128::from(*i), true),
+            _ => continue,
+        };
+        if *wide.get_or_insert(is_wide) != is_wide {
+            return None;
+        }
+        match func {
+            BinaryFunc::Gt => lower = lower.max(Some(value + 1)),
+            BinaryFunc::Gte => lower = lower.max(Some(value)),
+            BinaryFunc::Lt => upper = Some(upper.map_or(value - 1, |u| u.min(value - 1))),
+            BinaryFunc::Lte => upper = Some(upper.map_or(value, |u| u.min(value))),
+            _ => unreachable!(),
+        }
+    }
+    let (lower, upper, wide) = (lower?, upper?, wide?);
+    if upper - lower >= MAX_LITERAL_KEYS as i128 {
+        return None;
+    }
+    Some(
+        (lower..=upper)
+            .map(|i| {
+                if wide {
+                    Datum::Int64(i as i64)
+                } else {
+                    Datum::Int32(i as i32)
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Calls `logic` on each combination of one datum from each of `columns`.
+fn cartesian_product<'a, F>(columns: &[Vec<Datum<'a>>], datums: &mut Vec<Datum<'a>>, logic: &mut F)
+where
+    F: FnMut(&[Datum<'a>]),
+{
+    match columns.split_first() {
+        None => logic(datums),
+        Some((first, rest)) => {
+            for datum in first {
+                datums.push(*datum);
+                cartesian_product(rest, datums, logic);
+                datums.pop();
+            }
+        }
+    }
+}
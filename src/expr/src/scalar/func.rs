@@ -40,12 +40,16 @@ mod format;
 #[derive(Ord, PartialOrd, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum NullaryFunc {
     MzLogicalTimestamp,
+    /// The value of the numbered parameter of a prepared statement, which
+    /// has the given type.
+    Parameter(usize, ScalarType),
 }
 
 impl NullaryFunc {
     pub fn output_type(&self) -> ColumnType {
         match self {
             NullaryFunc::MzLogicalTimestamp => ScalarType::Decimal(38, 0).nullable(false),
+            NullaryFunc::Parameter(_, scalar_type) => scalar_type.clone().nullable(true),
         }
     }
 }
@@ -54,6 +58,7 @@ impl fmt::Display for NullaryFunc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             NullaryFunc::MzLogicalTimestamp => f.write_str("mz_logical_timestamp"),
+            NullaryFunc::Parameter(n, _) => write!(f, "${}", n),
         }
     }
 }
@@ -402,6 +407,46 @@ fn cast_string_to_uuid<'a>(a: Datum<'a>) -> Result<Datum<'a>, EvalError> {
         .err_into()
 }
 
+fn cast_string_to_enum<'a>(a: Datum<'a>, enum_typ: &ScalarType) -> Result<Datum<'a>, EvalError> {
+    let (name, labels) = match enum_typ {
+        ScalarType::Enum { name, labels, .. } => (name, labels),
+        _ => unreachable!("CastStringToEnum to non-enum type {}", enum_typ),
+    };
+    let s = a.unwrap_str();
+    match labels.iter().position(|label| label == s) {
+        Some(i) => Ok(Datum::Int32(i as i32)),
+        None => Err(EvalError::InvalidEnumValue {
+            type_name: name.clone(),
+            value: s.to_owned(),
+        }),
+    }
+}
+
+fn cast_string_to_record<'a>(
+    a: Datum<'a>,
+    cast_exprs: &'a [ScalarExpr],
+    temp_storage: &'a RowArena,
+) -> Result<Datum<'a>, EvalError> {
+    let mut cast_exprs_iter = cast_exprs.iter();
+    let parsed_datums = strconv::parse_record(a.unwrap_str(), cast_exprs.len(), |elem_text| {
+        // `parse_record` never produces more fields than there are cast
+        // expressions.
+        let cast_expr = cast_exprs_iter.next().unwrap();
+        match elem_text {
+            None => Ok(Datum::Null),
+            Some(elem_text) => {
+                let elem_text = match elem_text {
+                    Cow::Owned(s) => temp_storage.push_string(s),
+                    Cow::Borrowed(s) => s,
+                };
+                cast_expr.eval(&[Datum::String(elem_text)], temp_storage)
+            }
+        }
+    })?;
+
+    Ok(temp_storage.make_datum(|packer| packer.push_list(parsed_datums)))
+}
+
 fn cast_date_to_timestamp<'a>(a: Datum<'a>) -> Datum<'a> {
     Datum::Timestamp(a.unwrap_date().and_hms(0, 0, 0))
 }
@@ -560,6 +605,12 @@ fn cast_jsonb_to_bool<'a>(a: Datum<'a>) -> Datum<'a> {
     }
 }
 
+fn cast_enum_to_string<'a>(a: Datum, ty: &ScalarType, temp_storage: &'a RowArena) -> Datum<'a> {
+    let mut buf = String::new();
+    stringify_datum(&mut buf, a, ty);
+    Datum::String(temp_storage.push_string(buf))
+}
+
 fn cast_uuid_to_string<'a>(a: Datum<'a>, temp_storage: &'a RowArena) -> Datum<'a> {
     let mut buf = String::new();
     strconv::format_uuid(&mut buf, a.unwrap_uuid());
@@ -2400,6 +2451,17 @@ pub enum UnaryFunc {
     CastStringToInterval,
     CastStringToDecimal(u8),
     CastStringToUuid,
+    CastStringToEnum {
+        // Target enum's type
+        return_ty: ScalarType,
+    },
+    CastStringToRecord {
+        // Target record's type
+        return_ty: ScalarType,
+        // The expressions to cast the discovered record fields to the types of
+        // the record's fields, in order
+        cast_exprs: Vec<ScalarExpr>,
+    },
     CastDateToTimestamp,
     CastDateToTimestampTz,
     CastDateToString,
@@ -2420,6 +2482,9 @@ pub enum UnaryFunc {
     CastJsonbToFloat64,
     CastJsonbToBool,
     CastUuidToString,
+    CastEnumToString {
+        ty: ScalarType,
+    },
     CastRecordToString {
         ty: ScalarType,
     },
@@ -2539,6 +2604,10 @@ impl UnaryFunc {
             UnaryFunc::CastStringToTimestampTz => cast_string_to_timestamptz(a),
             UnaryFunc::CastStringToInterval => cast_string_to_interval(a),
             UnaryFunc::CastStringToUuid => cast_string_to_uuid(a),
+            UnaryFunc::CastStringToEnum { return_ty } => cast_string_to_enum(a, return_ty),
+            UnaryFunc::CastStringToRecord { cast_exprs, .. } => {
+                cast_string_to_record(a, cast_exprs, temp_storage)
+            }
             UnaryFunc::CastDateToTimestamp => Ok(cast_date_to_timestamp(a)),
             UnaryFunc::CastDateToTimestampTz => Ok(cast_date_to_timestamptz(a)),
             UnaryFunc::CastDateToString => Ok(cast_date_to_string(a, temp_storage)),
@@ -2562,6 +2631,7 @@ impl UnaryFunc {
             UnaryFunc::CastJsonbToFloat64 => Ok(cast_jsonb_to_float64(a)),
             UnaryFunc::CastJsonbToBool => Ok(cast_jsonb_to_bool(a)),
             UnaryFunc::CastUuidToString => Ok(cast_uuid_to_string(a, temp_storage)),
+            UnaryFunc::CastEnumToString { ty } => Ok(cast_enum_to_string(a, ty, temp_storage)),
             UnaryFunc::CastRecordToString { ty }
             | UnaryFunc::CastArrayToString { ty }
             | UnaryFunc::CastListToString { ty } => {
@@ -2658,6 +2728,7 @@ impl UnaryFunc {
             | CastTimestampTzToString
             | CastIntervalToString
             | CastBytesToString
+            | CastEnumToString { .. }
             | CastRecordToString { .. }
             | CastArrayToString { .. }
             | CastListToString { .. }
@@ -2717,6 +2788,10 @@ impl UnaryFunc {
                 (return_ty.clone()).nullable(false)
             }
 
+            CastStringToEnum { return_ty } | CastStringToRecord { return_ty, .. } => {
+                return_ty.clone().nullable(in_nullable)
+            }
+
             CeilFloat32 | FloorFloat32 | RoundFloat32 => ScalarType::Float32.nullable(in_nullable),
             CeilFloat64 | FloorFloat64 | RoundFloat64 => ScalarType::Float64.nullable(in_nullable),
             CeilDecimal(scale) | FloorDecimal(scale) | RoundDecimal(scale) | SqrtDec(scale) => {
@@ -2852,6 +2927,8 @@ impl fmt::Display for UnaryFunc {
             UnaryFunc::CastStringToTimestampTz => f.write_str("strtotstz"),
             UnaryFunc::CastStringToInterval => f.write_str("strtoiv"),
             UnaryFunc::CastStringToUuid => f.write_str("strtouuid"),
+            UnaryFunc::CastStringToEnum { .. } => f.write_str("strtoenum"),
+            UnaryFunc::CastStringToRecord { .. } => f.write_str("strtorecord"),
             UnaryFunc::CastDateToTimestamp => f.write_str("datetots"),
             UnaryFunc::CastDateToTimestampTz => f.write_str("datetotstz"),
             UnaryFunc::CastDateToString => f.write_str("datetostr"),
@@ -2872,6 +2949,7 @@ impl fmt::Display for UnaryFunc {
             UnaryFunc::CastJsonbToFloat64 => f.write_str("jsonbtof64"),
             UnaryFunc::CastJsonbToBool => f.write_str("jsonbtobool"),
             UnaryFunc::CastUuidToString => f.write_str("uuidtostr"),
+            UnaryFunc::CastEnumToString { .. } => f.write_str("enumtostr"),
             UnaryFunc::CastRecordToString { .. } => f.write_str("recordtostr"),
             UnaryFunc::CastArrayToString { .. } => f.write_str("arraytostr"),
             UnaryFunc::CastListToString { .. } => f.write_str("listtostr"),
@@ -3281,6 +3359,7 @@ where
         String => strconv::format_string(buf, d.unwrap_str()),
         Jsonb => strconv::format_jsonb(buf, JsonbRef::from_datum(d)),
         Uuid => strconv::format_uuid(buf, d.unwrap_uuid()),
+        Enum { labels, .. } => strconv::format_string(buf, &labels[d.unwrap_int32() as usize]),
         Record { fields } => {
             let mut fields = fields.iter();
             strconv::format_record(buf, &d.unwrap_list(), |buf, d| {
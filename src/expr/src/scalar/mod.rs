@@ -253,6 +253,20 @@ impl ScalarExpr {
         matches!(self, ScalarExpr::Literal(Err(_), _typ))
     }
 
+    /// Reports whether the expression calls a nullary function.
+    ///
+    /// Nullary functions are placeholders that must be replaced before the
+    /// expression can be evaluated.
+    pub fn calls_nullary(&self) -> bool {
+        let mut calls_nullary = false;
+        self.visit(&mut |e| {
+            if let ScalarExpr::CallNullary(_) = e {
+                calls_nullary = true;
+            }
+        });
+        calls_nullary
+    }
+
     /// Reduces a complex expression where possible.
     ///
     /// ```rust
@@ -581,6 +595,10 @@ pub enum EvalError {
     },
     InvalidArray(InvalidArrayError),
     InvalidEncodingName(String),
+    InvalidEnumValue {
+        type_name: String,
+        value: String,
+    },
     InvalidByteSequence {
         byte_sequence: String,
         encoding_name: String,
@@ -611,6 +629,11 @@ impl fmt::Display for EvalError {
             ),
             EvalError::InvalidArray(e) => e.fmt(f),
             EvalError::InvalidEncodingName(name) => write!(f, "invalid encoding name '{}'", name),
+            EvalError::InvalidEnumValue { type_name, value } => write!(
+                f,
+                "invalid input value for enum {}: \"{}\"",
+                type_name, value
+            ),
             EvalError::InvalidByteSequence {
                 byte_sequence,
                 encoding_name,
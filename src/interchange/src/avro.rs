@@ -2191,6 +2191,32 @@ pub fn encode_debezium_transaction_unchecked(
     buf
 }
 
+/// Extracts the timestamp from a record in a sink's consistency topic, if the
+/// record is an `END` record.
+///
+/// The record must have been decoded with the schema returned by
+/// [`get_debezium_transaction_schema`].
+pub fn decode_debezium_transaction_end(record: Value) -> anyhow::Result<Option<u64>> {
+    let fields = match record {
+        Value::Record(fields) => fields,
+        _ => bail!("consistency record is not an Avro record"),
+    };
+    let mut id = None;
+    let mut status = None;
+    for (name, value) in fields {
+        match (name.as_str(), value) {
+            ("id", Value::String(s)) => id = Some(s),
+            ("status", Value::String(s)) => status = Some(s),
+            _ => (),
+        }
+    }
+    match (id, status.as_deref()) {
+        (Some(id), Some("END")) => Ok(Some(id.parse()?)),
+        (Some(_), Some(_)) => Ok(None),
+        _ => bail!("consistency record is missing id or status"),
+    }
+}
+
 fn encode_avro_header(buf: &mut Vec<u8>, schema_id: i32) {
     // The first byte is a magic byte (0) that indicates the Confluent
     // serialization format version, and the next four bytes are a
@@ -2250,6 +2276,15 @@ impl Encoder {
         }
     }
 
+    /// Builds an encoder for upsert sinks, whose values are just the rows
+    /// themselves, rather than a diff envelope.
+    pub fn new_upsert(desc: RelationDesc, key_indices: Vec<usize>) -> Self {
+        let mut encoder = Encoder::new(desc, false, Some(key_indices));
+        let row_schema = build_row_schema_json(&encoder.columns, "row");
+        encoder.writer_schema = Schema::parse(&row_schema).expect("valid schema constructed");
+        encoder
+    }
+
     pub fn writer_schema(&self) -> &Schema {
         &self.writer_schema
     }
@@ -2300,6 +2335,36 @@ impl Encoder {
         (key_buf, buf)
     }
 
+    /// Encodes the latest `row` for its key in an upsert sink, or a tombstone
+    /// for the key of `row` if `deleted` is set.
+    ///
+    /// The encoder must have been built with [`Encoder::new_upsert`].
+    pub fn encode_upsert_unchecked(
+        &self,
+        key_schema_id: i32,
+        value_schema_id: i32,
+        row: &Row,
+        deleted: bool,
+    ) -> (Vec<u8>, Option<Vec<u8>>) {
+        let (avro_key, avro_value) = self.row_to_avro(row.iter());
+        let avro_key = avro_key.expect("upsert encoders have a key");
+        let key_schema = self
+            .key_writer_schema()
+            .expect("upsert encoders have a key");
+        let mut key_buf = vec![];
+        encode_avro_header(&mut key_buf, key_schema_id);
+        debug_assert!(avro_key.validate(key_schema.top_node()));
+        mz_avro::encode_unchecked(&avro_key, key_schema, &mut key_buf);
+        if deleted {
+            return (key_buf, None);
+        }
+        let mut buf = vec![];
+        encode_avro_header(&mut buf, value_schema_id);
+        debug_assert!(avro_value.validate(self.writer_schema.top_node()));
+        mz_avro::encode_unchecked(&avro_value, &self.writer_schema, &mut buf);
+        (key_buf, Some(buf))
+    }
+
     pub fn diff_pair_to_avro(
         &self,
         diff_pair: DiffPair<&Row>,
@@ -2508,6 +2573,11 @@ impl<'a> mz_avro::types::ToAvro for TypedDatum<'a> {
                 ScalarType::String => Value::String(datum.unwrap_str().to_owned()),
                 ScalarType::Jsonb => Value::Json(JsonbRef::from_datum(datum).to_serde_json()),
                 ScalarType::Uuid => Value::Uuid(datum.unwrap_uuid()),
+                // Enum labels need not be valid Avro names, so enums are
+                // encoded as their labels rather than as Avro enums.
+                ScalarType::Enum { labels, .. } => {
+                    Value::String(labels[datum.unwrap_int32() as usize].clone())
+                }
                 ScalarType::Array(_t) => unimplemented!("array types"),
                 ScalarType::List(_t) => unimplemented!("list types"),
                 ScalarType::Record { .. } => unimplemented!("record types"),
@@ -2619,7 +2689,7 @@ fn build_row_schema_json(
                 "logicalType": "duration"
             }),
             ScalarType::Bytes => json!("bytes"),
-            ScalarType::String => json!("string"),
+            ScalarType::String | ScalarType::Enum { .. } => json!("string"),
             ScalarType::Jsonb => json!({
                 "type": "string",
                 "connect.name": "io.debezium.data.Json",
@@ -2992,6 +3062,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_debezium_transaction_roundtrip() -> anyhow::Result<()> {
+        let schema = get_debezium_transaction_schema();
+        for (status, count, expected) in vec![("BEGIN", None, None), ("END", Some(3), Some(42))] {
+            let buf = encode_debezium_transaction_unchecked(1, "42", status, count);
+            let record = mz_avro::from_avro_datum(schema, &mut &buf[5..])?;
+            assert_eq!(decode_debezium_transaction_end(record)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsert_encoding() -> anyhow::Result<()> {
+        let desc = RelationDesc::empty()
+            .with_column("a", ScalarType::Int64.nullable(false))
+            .with_column("b", ScalarType::String.nullable(false));
+        let encoder = Encoder::new_upsert(desc, vec![0]);
+        let row = Row::pack(&[Datum::Int64(1), Datum::String("one")]);
+
+        let (key, value) = encoder.encode_upsert_unchecked(1, 2, &row, false);
+        let key = mz_avro::from_avro_datum(encoder.key_writer_schema().unwrap(), &mut &key[5..])?;
+        assert_eq!(key, Value::Record(vec![("a".into(), Value::Long(1))]));
+        let value = mz_avro::from_avro_datum(encoder.writer_schema(), &mut &value.unwrap()[5..])?;
+        assert_eq!(
+            value,
+            Value::Record(vec![
+                ("a".into(), Value::Long(1)),
+                ("b".into(), Value::String("one".into())),
+            ])
+        );
+
+        let (_, value) = encoder.encode_upsert_unchecked(1, 2, &row, true);
+        assert_eq!(value, None);
+        Ok(())
+    }
+
     #[test]
     /// Test that primitive Avro Schema types are allow Datums to be correctly
     /// serialized into Avro Values.
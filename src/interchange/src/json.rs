@@ -0,0 +1,227 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! JSON sink encoding.
+//!
+//! Each update is encoded as a single JSON object of the form:
+//!
+//! ```json
+//! {"before": null, "after": {"a": 1, "b": "x"}, "timestamp": 1602706218934, "diff": 1}
+//! ```
+//!
+//! Insertions populate `after` and retractions populate `before`. `diff` is
+//! the signed multiplicity of the update, so an update with a multiplicity
+//! other than one is still encoded as exactly one message.
+
+use serde_json::{json, Map, Number, Value};
+
+use repr::adt::jsonb::JsonbRef;
+use repr::{
+    strconv, ColumnName, ColumnType, Datum, Diff, RelationDesc, Row, ScalarType, Timestamp,
+};
+
+use crate::avro::{column_names_and_types, DiffPair};
+
+/// Manages the metadata required to encode rows as JSON.
+#[derive(Debug)]
+pub struct Encoder {
+    columns: Vec<(ColumnName, ColumnType)>,
+    key_indices: Option<Vec<usize>>,
+}
+
+impl Encoder {
+    pub fn new(desc: RelationDesc, key_indices: Option<Vec<usize>>) -> Self {
+        Encoder {
+            columns: column_names_and_types(desc),
+            key_indices,
+        }
+    }
+
+    /// Encodes an update as a (key, value) pair of JSON documents.
+    ///
+    /// The key is only present if the encoder was constructed with key
+    /// indices, in which case it is an object containing just the key columns
+    /// of whichever of `before` or `after` is present.
+    pub fn encode_unchecked(
+        &self,
+        diff_pair: DiffPair<&Row>,
+        timestamp: Timestamp,
+        diff: Diff,
+    ) -> (Option<Vec<u8>>, Vec<u8>) {
+        let key = self.key_indices.as_ref().map(|key_indices| {
+            let row = diff_pair
+                .after
+                .or(diff_pair.before)
+                .expect("diff pair must contain a row");
+            self.encode_key(row, key_indices)
+        });
+        let value = json!({
+            "before": diff_pair.before.map(|row| self.row_to_json(row)),
+            "after": diff_pair.after.map(|row| self.row_to_json(row)),
+            "timestamp": timestamp,
+            "diff": diff,
+        });
+        let value = serde_json::to_vec(&value).expect("json serialization cannot fail");
+        (key, value)
+    }
+
+    /// Encodes the latest `row` for its key in an upsert sink, or a tombstone
+    /// for the key of `row` if `deleted` is set.
+    ///
+    /// The encoder must have been constructed with key indices.
+    pub fn encode_upsert_unchecked(&self, row: &Row, deleted: bool) -> (Vec<u8>, Option<Vec<u8>>) {
+        let key_indices = self
+            .key_indices
+            .as_ref()
+            .expect("upsert encoders have a key");
+        let key = self.encode_key(row, key_indices);
+        let value = if deleted {
+            None
+        } else {
+            Some(
+                serde_json::to_vec(&self.row_to_json(row)).expect("json serialization cannot fail"),
+            )
+        };
+        (key, value)
+    }
+
+    fn encode_key(&self, row: &Row, key_indices: &[usize]) -> Vec<u8> {
+        let datums = row.unpack();
+        let mut key = Map::new();
+        for i in key_indices {
+            let (name, typ) = &self.columns[*i];
+            key.insert(
+                name.to_string(),
+                datum_to_json(datums[*i], &typ.scalar_type),
+            );
+        }
+        serde_json::to_vec(&Value::Object(key)).expect("json serialization cannot fail")
+    }
+
+    /// Converts a row into a JSON object keyed by column name.
+    pub fn row_to_json(&self, row: &Row) -> Value {
+        let mut object = Map::new();
+        for (datum, (name, typ)) in row.iter().zip(self.columns.iter()) {
+            object.insert(name.to_string(), datum_to_json(datum, &typ.scalar_type));
+        }
+        Value::Object(object)
+    }
+}
+
+/// Converts a datum of type `typ` into the closest native JSON value.
+///
+/// Lists and records become JSON arrays and objects. Decimals are rendered as
+/// strings, so that they do not lose precision, as are types without a native
+/// JSON representation, like dates and intervals, in their PostgreSQL text
+/// format.
+pub fn datum_to_json(datum: Datum, typ: &ScalarType) -> Value {
+    if datum.is_null() {
+        return Value::Null;
+    }
+    match typ {
+        ScalarType::Bool => Value::Bool(datum.unwrap_bool()),
+        ScalarType::Int32 | ScalarType::Oid => Value::Number(datum.unwrap_int32().into()),
+        ScalarType::Int64 => Value::Number(datum.unwrap_int64().into()),
+        ScalarType::Float32 => float_to_json(datum.unwrap_float32().into()),
+        ScalarType::Float64 => float_to_json(datum.unwrap_float64()),
+        ScalarType::Decimal(_, scale) => {
+            Value::String(datum.unwrap_decimal().with_scale(*scale).to_string())
+        }
+        ScalarType::Bytes => {
+            let mut buf = String::new();
+            strconv::format_bytes(&mut buf, datum.unwrap_bytes());
+            Value::String(buf)
+        }
+        ScalarType::String => Value::String(datum.unwrap_str().to_owned()),
+        ScalarType::Enum { labels, .. } => {
+            Value::String(labels[datum.unwrap_int32() as usize].clone())
+        }
+        ScalarType::Jsonb => JsonbRef::from_datum(datum).to_serde_json(),
+        ScalarType::List(elem_type) => Value::Array(
+            datum
+                .unwrap_list()
+                .iter()
+                .map(|elem| datum_to_json(elem, elem_type))
+                .collect(),
+        ),
+        ScalarType::Record { fields } => {
+            let mut object = Map::new();
+            for (datum, (name, typ)) in datum.unwrap_list().iter().zip(fields.iter()) {
+                object.insert(name.to_string(), datum_to_json(datum, typ));
+            }
+            Value::Object(object)
+        }
+        ScalarType::Date
+        | ScalarType::Time
+        | ScalarType::Timestamp
+        | ScalarType::TimestampTz
+        | ScalarType::Interval
+        | ScalarType::Uuid
+        | ScalarType::Array(_) => Value::String(datum.to_string()),
+    }
+}
+
+// JSON numbers don't support NaN or infinity, so those are rendered as
+// strings.
+fn float_to_json(f: f64) -> Value {
+    match Number::from_f64(f) {
+        Some(n) => Value::Number(n),
+        None => Value::String(f.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use repr::adt::decimal::Significand;
+    use repr::{Datum, RelationDesc, RelationType, Row, ScalarType};
+
+    use super::Encoder;
+    use crate::avro::DiffPair;
+
+    #[test]
+    fn test_json_envelope() {
+        let desc = RelationDesc::new(
+            RelationType::new(vec![
+                ScalarType::Int64.nullable(false),
+                ScalarType::String.nullable(true),
+                ScalarType::Decimal(10, 2).nullable(false),
+            ]),
+            vec![Some("a"), Some("b"), None],
+        );
+        let encoder = Encoder::new(desc, Some(vec![0]));
+        let row = Row::pack(&[
+            Datum::Int64(1),
+            Datum::Null,
+            Datum::Decimal(Significand::new(12345)),
+        ]);
+
+        let (key, value) = encoder.encode_unchecked(
+            DiffPair {
+                before: Some(&row),
+                after: None,
+            },
+            42,
+            -2,
+        );
+        let key: serde_json::Value = serde_json::from_slice(&key.unwrap()).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&value).unwrap();
+        assert_eq!(key, json!({"a": 1}));
+        assert_eq!(
+            value,
+            json!({
+                "before": {"a": 1, "b": null, "column3": "123.45"},
+                "after": null,
+                "timestamp": 42,
+                "diff": -2,
+            })
+        );
+    }
+}
@@ -12,4 +12,5 @@
 #![deny(missing_debug_implementations)]
 
 pub mod avro;
+pub mod json;
 pub mod protobuf;
@@ -7,7 +7,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-//! Protobuf source connector
+//! Protobuf encoding and decoding
+
+use std::convert::TryFrom;
 
 use anyhow::{anyhow, bail, Context, Result};
 use num_traits::ToPrimitive;
@@ -190,6 +192,165 @@ impl Decoder {
     }
 }
 
+/// Manages required metadata to write protobuf.
+///
+/// Each update is encoded as an envelope message equivalent to:
+///
+/// ```protobuf
+/// message Envelope {
+///     Row before = 1;
+///     Row after = 2;
+///     uint64 timestamp = 3;
+///     int64 diff = 4;
+/// }
+/// ```
+///
+/// where `Row` is the user-provided message type. Insertions populate `after`,
+/// retractions populate `before`, and `diff` is the signed multiplicity of the
+/// update.
+///
+/// The envelope is not published anywhere, so consumers must declare it in
+/// their own schema, as described in the `CREATE SINK` documentation. Its field
+/// numbers and types must therefore never change.
+#[derive(Debug)]
+pub struct Encoder {
+    descriptors: Descriptors,
+    message_name: String,
+}
+
+impl Encoder {
+    /// Build an encoder for rows described by `desc`.
+    ///
+    /// The message `message_name` must exist in the descriptor set, and its
+    /// fields must correspond, in order, to the columns of `desc`.
+    pub fn new(
+        descriptors: Descriptors,
+        message_name: &str,
+        desc: &RelationDesc,
+    ) -> Result<Encoder> {
+        let message_desc = validate_descriptors(message_name, &descriptors)?;
+        if message_desc.arity() != desc.arity() {
+            bail!(
+                "protobuf message {} has {} fields, but the sink has {} columns",
+                message_name,
+                message_desc.arity(),
+                desc.arity()
+            );
+        }
+        for ((field_name, field_type), (column_name, column_type)) in
+            message_desc.iter().zip(desc.iter())
+        {
+            let compatible = match (&field_type.scalar_type, &column_type.scalar_type) {
+                // Nested and repeated fields are decoded as jsonb, but there
+                // is no sensible way to encode arbitrary jsonb as a message.
+                (ScalarType::Jsonb, _) => bail!(
+                    "protobuf sinks do not support repeated or nested message fields: {}",
+                    field_name.map(|n| n.as_str()).unwrap_or("?")
+                ),
+                (ScalarType::Decimal(_, _), ScalarType::Decimal(_, 0)) => true,
+                (f, c) => f == c,
+            };
+            if !compatible {
+                bail!(
+                    "protobuf field {} of type {} cannot hold column {} of type {}",
+                    field_name.map(|n| n.as_str()).unwrap_or("?"),
+                    field_type.scalar_type,
+                    column_name.map(|n| n.as_str()).unwrap_or("?"),
+                    column_type.scalar_type,
+                );
+            }
+        }
+        Ok(Encoder {
+            descriptors,
+            message_name: proto_message_name(message_name),
+        })
+    }
+
+    pub fn encode(
+        &self,
+        before: Option<&Row>,
+        after: Option<&Row>,
+        timestamp: u64,
+        diff: isize,
+    ) -> Result<Vec<u8>> {
+        let mut buf = vec![];
+        let mut os = protobuf::CodedOutputStream::vec(&mut buf);
+        if let Some(before) = before {
+            os.write_bytes(1, &self.encode_row(before)?)?;
+        }
+        if let Some(after) = after {
+            os.write_bytes(2, &self.encode_row(after)?)?;
+        }
+        os.write_uint64(3, timestamp)?;
+        os.write_int64(4, diff as i64)?;
+        os.flush()?;
+        drop(os);
+        Ok(buf)
+    }
+
+    fn encode_row(&self, row: &Row) -> Result<Vec<u8>> {
+        let message = self
+            .descriptors
+            .message_by_name(&self.message_name)
+            .ok_or_else(|| anyhow!("Message {:?} not found", self.message_name))?;
+        let mut buf = vec![];
+        let mut os = protobuf::CodedOutputStream::vec(&mut buf);
+        for (f, datum) in message.fields().iter().zip(row.iter()) {
+            // Absent fields are how protobuf represents nulls.
+            if datum.is_null() {
+                continue;
+            }
+            let n = f.number() as u32;
+            match f.field_type(&self.descriptors) {
+                FieldType::Bool => os.write_bool(n, datum.unwrap_bool())?,
+                FieldType::Int32 => os.write_int32(n, datum.unwrap_int32())?,
+                FieldType::SInt32 => os.write_sint32(n, datum.unwrap_int32())?,
+                FieldType::SFixed32 => os.write_sfixed32(n, datum.unwrap_int32())?,
+                FieldType::Int64 => os.write_int64(n, datum.unwrap_int64())?,
+                FieldType::SInt64 => os.write_sint64(n, datum.unwrap_int64())?,
+                FieldType::SFixed64 => os.write_sfixed64(n, datum.unwrap_int64())?,
+                FieldType::Float => os.write_float(n, datum.unwrap_float32())?,
+                FieldType::Double => os.write_double(n, datum.unwrap_float64())?,
+                FieldType::UInt32 | FieldType::Fixed32 => {
+                    let d = datum.unwrap_decimal().as_i128();
+                    let u = u32::try_from(d)
+                        .map_err(|_| anyhow!("{} out of range for field {}", d, f.name()))?;
+                    match f.field_type(&self.descriptors) {
+                        FieldType::UInt32 => os.write_uint32(n, u)?,
+                        _ => os.write_fixed32(n, u)?,
+                    }
+                }
+                FieldType::UInt64 | FieldType::Fixed64 => {
+                    let d = datum.unwrap_decimal().as_i128();
+                    let u = u64::try_from(d)
+                        .map_err(|_| anyhow!("{} out of range for field {}", d, f.name()))?;
+                    match f.field_type(&self.descriptors) {
+                        FieldType::UInt64 => os.write_uint64(n, u)?,
+                        _ => os.write_fixed64(n, u)?,
+                    }
+                }
+                FieldType::Enum(e) => {
+                    let name = datum.unwrap_str();
+                    let value = e.value_by_name(name).ok_or_else(|| {
+                        anyhow!(
+                            "{:?} is not a valid value for enum field {}",
+                            name,
+                            f.name()
+                        )
+                    })?;
+                    os.write_enum(n, value.number())?
+                }
+                FieldType::String => os.write_string(n, datum.unwrap_str())?,
+                FieldType::Bytes => os.write_bytes(n, datum.unwrap_bytes())?,
+                ft => bail!("Unsupported field type {:?} in protobuf sink", ft),
+            }
+        }
+        os.flush()?;
+        drop(os);
+        Ok(buf)
+    }
+}
+
 fn extract_row_into(
     deserialized_message: SerdeValue,
     descriptors: &Descriptors,
@@ -455,7 +616,7 @@ mod tests {
     };
 
     use repr::adt::decimal::Significand;
-    use repr::{Datum, DatumList, RelationDesc, ScalarType};
+    use repr::{Datum, DatumList, RelationDesc, RelationType, Row, ScalarType};
 
     use gen::fuzz::{
         Color, TestNestedRecord, TestRecord, TestRepeatedNestedRecord, TestRepeatedRecord,
@@ -853,4 +1014,44 @@ mod tests {
             assert_eq!(d, Datum::List(DatumList::empty()));
         }
     }
+
+    #[test]
+    fn test_encoder_roundtrip() {
+        let build_descriptors = || {
+            let mut descriptors = Descriptors::new();
+            let mut m1 = MessageDescriptor::new(".test.message1");
+            m1.add_field(FieldDescriptor::new(
+                "name",
+                1,
+                FieldLabel::Optional,
+                InternalFieldType::String,
+                None,
+            ));
+            m1.add_field(FieldDescriptor::new(
+                "age",
+                2,
+                FieldLabel::Optional,
+                InternalFieldType::UInt32,
+                None,
+            ));
+            descriptors.add_message(m1);
+            descriptors
+        };
+
+        let desc = RelationDesc::new(
+            RelationType::new(vec![
+                ScalarType::String.nullable(true),
+                ScalarType::Decimal(10, 0).nullable(true),
+            ]),
+            vec![Some("name"), Some("age")],
+        );
+        let encoder = super::Encoder::new(build_descriptors(), ".test.message1", &desc)
+            .expect("message matches relation");
+        let row = Row::pack(&[Datum::String("alice"), Datum::Decimal(Significand::new(42))]);
+        let bytes = encoder.encode_row(&row).expect("encoding row");
+
+        let mut decoder = super::Decoder::new(build_descriptors(), ".test.message1");
+        let decoded = decoder.decode(&bytes).expect("decoding row").unwrap();
+        assert_eq!(decoded, row);
+    }
 }
@@ -143,6 +143,17 @@ fn run() -> Result<(), anyhow::Error> {
         "PATH",
     );
     opts.optopt("", "tls-key", "private key for TLS connections", "PATH");
+    opts.optopt(
+        "",
+        "statement-timeout",
+        "default maximum time that a query waits for its results (default off)",
+        "DURATION/\"off\"",
+    );
+    opts.optflag(
+        "",
+        "http-tail",
+        "serve TAIL results over HTTP to unauthenticated clients",
+    );
 
     // Storage options.
     opts.optopt(
@@ -259,6 +270,10 @@ fn run() -> Result<(), anyhow::Error> {
 
     // Configure connections.
     let listen_addr = popts.opt_get("listen-addr")?;
+    let statement_timeout = match popts.opt_str("statement-timeout").as_deref() {
+        None | Some("off") => Duration::from_secs(0),
+        Some(d) => parse_duration::parse(&d)?,
+    };
     let tls = match (popts.opt_str("tls-cert"), popts.opt_str("tls-key")) {
         (None, None) => None,
         (None, Some(_)) | (Some(_), None) => {
@@ -448,6 +463,8 @@ swap: {swap_total}KB total, {swap_used}KB used",
         persistence,
         listen_addr,
         tls,
+        statement_timeout,
+        http_tail: popts.opt_present("http-tail"),
         data_directory: Some(data_directory),
         symbiosis_url,
         experimental_mode,
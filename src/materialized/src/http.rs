@@ -13,6 +13,7 @@
 //! process. At the moment, its primary exports are Prometheus metrics, heap
 //! profiles, and catalog dumps.
 
+use std::sync::Arc;
 use std::time::Instant;
 
 use futures::future::{FutureExt, TryFutureExt};
@@ -21,6 +22,7 @@ use openssl::ssl::SslAcceptor;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 use ore::netio::SniffedStream;
+use pgwire::IdAllocator;
 
 mod catalog;
 mod memory;
@@ -28,6 +30,7 @@ mod metrics;
 mod prof;
 mod root;
 mod sql;
+mod tail;
 mod util;
 
 const METHODS: &[&[u8]] = &[
@@ -45,6 +48,11 @@ pub struct Server {
     coord_client: coord::Client,
     /// When this server started
     start_time: Instant,
+    /// Allocates connection IDs for the sessions that service streaming
+    /// requests.
+    conn_ids: Arc<IdAllocator>,
+    /// Whether to serve the `/tail` endpoint.
+    http_tail: bool,
 }
 
 impl Server {
@@ -53,6 +61,7 @@ impl Server {
         coord_client: coord::Client,
         start_time: Instant,
         worker_count: &str,
+        http_tail: bool,
     ) -> Server {
         // just set this so it shows up in metrics
         metrics::WORKER_COUNT
@@ -62,6 +71,10 @@ impl Server {
             tls,
             coord_client,
             start_time,
+            // The pgwire server allocates connection IDs up to 1 << 16, so
+            // use the range above that to avoid collisions.
+            conn_ids: Arc::new(IdAllocator::new((1 << 16) + 1, 1 << 17)),
+            http_tail,
         }
     }
 
@@ -102,6 +115,7 @@ impl Server {
             (&Method::GET, "/memory") => self.handle_memory(req).boxed(),
             (&Method::POST, "/prof") => self.handle_prof(req).boxed(),
             (&Method::POST, "/sql") => self.handle_sql(req).boxed(),
+            (&Method::GET, "/tail") => self.handle_tail(req).boxed(),
             (&Method::GET, "/internal/catalog") => self.handle_internal_catalog(req).boxed(),
             _ => self.handle_static(req).boxed(),
         });
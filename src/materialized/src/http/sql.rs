@@ -13,14 +13,14 @@ use std::future::Future;
 use anyhow::bail;
 use hyper::{header, Body, Request, Response, StatusCode};
 use serde::Serialize;
-use serde_json::{Number, Value};
+use serde_json::Value;
 use url::form_urlencoded;
 
 use crate::http::{util, Server};
 use coord::ExecuteResponse;
 use dataflow_types::PeekResponse;
+use interchange::json::datum_to_json;
 use ore::collections::CollectionExt;
-use repr::Datum;
 use sql::plan::Params;
 use sql_parser::parser::parse_statements;
 
@@ -78,36 +78,23 @@ async fn query_sql_as_system(
         PeekResponse::Error(e) => bail!("{}", e),
         _ => bail!("unexpected PeekResponse type"),
     };
+    let desc = match res.desc {
+        Some(desc) => desc,
+        None => bail!("statement does not produce rows"),
+    };
     let mut sql_rows: Vec<Vec<Value>> = vec![];
     for row in rows {
-        let datums = row.unpack();
         sql_rows.push(
-            datums
-                .iter()
-                .map(|datum| match datum {
-                    // Convert some common things to a native JSON value. This doesn't need to be
-                    // too exhaustive because the SQL-over-HTTP interface is currently not hooked
-                    // up to arbitrary external user queries.
-                    Datum::Null | Datum::JsonNull => Value::Null,
-                    Datum::False => Value::Bool(false),
-                    Datum::True => Value::Bool(true),
-                    Datum::Int32(n) => Value::Number(Number::from(*n)),
-                    Datum::Int64(n) => Value::Number(Number::from(*n)),
-                    Datum::Float32(n) => float_to_json(n.into_inner() as f64),
-                    Datum::Float64(n) => float_to_json(n.into_inner()),
-                    Datum::String(s) => Value::String(s.to_string()),
-                    _ => Value::String(datum.to_string()),
-                })
+            row.iter()
+                .zip(&desc.typ().column_types)
+                .map(|(datum, typ)| datum_to_json(datum, &typ.scalar_type))
                 .collect(),
         );
     }
-    let col_names = match res.desc {
-        Some(desc) => desc
-            .iter_names()
-            .map(|name| name.map(|name| name.to_string()))
-            .collect(),
-        None => vec![],
-    };
+    let col_names = desc
+        .iter_names()
+        .map(|name| name.map(|name| name.to_string()))
+        .collect();
     Ok(SqlResult {
         rows: sql_rows,
         col_names,
@@ -119,12 +106,3 @@ struct SqlResult {
     rows: Vec<Vec<Value>>,
     col_names: Vec<Option<String>>,
 }
-
-// Convert most floats to a JSON Number. JSON Numbers don't support NaN or
-// Infinity, so those will still be rendered as strings.
-fn float_to_json(f: f64) -> Value {
-    match Number::from_f64(f) {
-        Some(n) => Value::Number(n),
-        None => Value::String(f.to_string()),
-    }
-}
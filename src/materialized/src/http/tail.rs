@@ -0,0 +1,239 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Streaming `TAIL` results over HTTP as server-sent events.
+//!
+//! A `GET /tail?sql=TAIL ...` request runs the `TAIL` statement in a session
+//! of its own, and streams each row of its output as an event:
+//!
+//!   * Each update is sent as a `data` event whose payload is a JSON object
+//!     containing the `timestamp` of the update, its `diff` (or `deleted`, for
+//!     `ENVELOPE UPSERT`), and the updated `row` as an array.
+//!   * If the `PROGRESS` option is specified, a `progress` event with the
+//!     `timestamp` is sent whenever no more updates will appear at earlier
+//!     timestamps.
+//!
+//! The session, and the dataflow that services the `TAIL`, live until the
+//! client disconnects.
+//!
+//! Like the rest of the HTTP server, the endpoint does not authenticate its
+//! clients, and it places no limit on the `TAIL`s that they run, each of which
+//! builds a dataflow. It is therefore only served if `materialized` is started
+//! with `--http-tail`.
+
+use std::convert::TryFrom;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail};
+use futures::stream::StreamExt;
+use hyper::body::{Bytes, Sender};
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde_json::{Map, Number, Value};
+use tokio::time::{self, Duration};
+use url::form_urlencoded;
+
+use coord::session::Session;
+use coord::{ExecuteResponse, SessionClient};
+use interchange::json::datum_to_json;
+use ore::collections::CollectionExt;
+use pgwire::IdAllocator;
+use repr::{Datum, RelationDesc, Row, ScalarType};
+use sql::ast::Statement;
+use sql::plan::Params;
+use sql_parser::parser::parse_statements;
+
+use crate::http::{util, Server};
+
+/// How long to wait for updates before sending a keepalive comment.
+///
+/// Writing the comment is what detects that the client has disconnected, so
+/// this bounds how long an abandoned `TAIL` can outlive its client.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+impl Server {
+    pub fn handle_tail(
+        &self,
+        req: Request<Body>,
+    ) -> impl Future<Output = anyhow::Result<Response<Body>>> {
+        let coord_client = self.coord_client.clone();
+        let conn_ids = Arc::clone(&self.conn_ids);
+        let http_tail = self.http_tail;
+        async move {
+            if !http_tail {
+                return Ok(util::error_response(
+                    StatusCode::FORBIDDEN,
+                    "the /tail endpoint is disabled; start materialized with --http-tail to enable it",
+                ));
+            }
+            let res = async {
+                let sql = req.uri().query().and_then(|query| {
+                    form_urlencoded::parse(query.as_bytes())
+                        .find(|(key, _)| key == "sql")
+                        .map(|(_, sql)| sql.into_owned())
+                });
+                let sql = match sql {
+                    Some(sql) => sql,
+                    None => bail!("expected `sql` parameter"),
+                };
+                let stmts = parse_statements(&sql)?;
+                if stmts.len() != 1 {
+                    bail!("expected exactly 1 statement");
+                }
+                let stmt = stmts.into_element();
+                if !matches!(stmt, Statement::Tail(_)) {
+                    bail!("expected a TAIL statement");
+                }
+
+                let conn_id = conn_ids.alloc()?;
+                let mut client = coord_client.for_session(Session::new(conn_id));
+                let (desc, rx) = match start_tail(&mut client, stmt).await {
+                    Ok(res) => res,
+                    Err(e) => {
+                        client.terminate().await;
+                        conn_ids.free(conn_id);
+                        return Err(e);
+                    }
+                };
+
+                let (mut tx, body) = Body::channel();
+                tokio::spawn(async move {
+                    // An error here means that the client has gone away, or
+                    // that the TAIL has failed. Either way, there is nobody
+                    // left to tell.
+                    let _ = stream_tail(&mut tx, &desc, rx).await;
+                    client.terminate().await;
+                    conn_ids.free(conn_id);
+                });
+
+                Ok(Response::builder()
+                    .header(header::CONTENT_TYPE, "text/event-stream")
+                    .header(header::CACHE_CONTROL, "no-cache")
+                    .body(body)
+                    .unwrap())
+            }
+            .await;
+            match res {
+                Ok(res) => Ok(res),
+                Err(e) => Ok(util::error_response(StatusCode::BAD_REQUEST, e.to_string())),
+            }
+        }
+    }
+}
+
+/// Starts running `stmt` in the session bound to `client`, returning the shape
+/// of its output and the stream of its output.
+async fn start_tail(
+    client: &mut SessionClient,
+    stmt: Statement,
+) -> anyhow::Result<(RelationDesc, comm::mpsc::Receiver<Result<Vec<Row>, String>>)> {
+    client.startup().await?;
+    let name = String::from("");
+    client
+        .describe(name.clone(), Some(stmt.clone()), vec![])
+        .await?;
+    let desc = client
+        .session()
+        .get_prepared_statement(&name)
+        .expect("statement was just described")
+        .desc()
+        .clone();
+    let relation_desc = desc
+        .relation_desc
+        .clone()
+        .expect("TAIL statements produce rows");
+    client
+        .session()
+        .declare_cursor(name.clone(), stmt, desc, Params::empty());
+    match client.execute(name).await? {
+        ExecuteResponse::Tailing { rx } => Ok((relation_desc, rx)),
+        _ => bail!("unexpected ExecuteResponse type"),
+    }
+}
+
+/// Writes each row received on `rx` to `tx` as an event, until either `rx` is
+/// exhausted or the client disconnects.
+async fn stream_tail(
+    tx: &mut Sender,
+    desc: &RelationDesc,
+    mut rx: comm::mpsc::Receiver<Result<Vec<Row>, String>>,
+) -> anyhow::Result<()> {
+    let names: Vec<String> = desc
+        .iter_names()
+        .map(|name| name.map(|name| name.to_string()).unwrap_or_default())
+        .collect();
+    let types: Vec<ScalarType> = desc
+        .typ()
+        .column_types
+        .iter()
+        .map(|typ| typ.scalar_type.clone())
+        .collect();
+    // The output of a TAIL starts with the `timestamp` column, optionally
+    // followed by the `progressed` column, and then the `diff` or `deleted`
+    // column. The remaining columns are the row itself.
+    let row_start = names
+        .iter()
+        .position(|name| name == "diff" || name == "deleted")
+        .expect("TAIL output has a diff column")
+        + 1;
+    let progress = names[1] == "progressed";
+
+    loop {
+        match time::timeout(KEEPALIVE_INTERVAL, rx.next()).await {
+            Ok(None) => return Ok(()),
+            Ok(Some(rows)) => {
+                for row in rows?.map_err(|e| anyhow!(e))? {
+                    let event = encode_event(&names, &types, row_start, progress, &row);
+                    tx.send_data(Bytes::from(event)).await?;
+                }
+            }
+            Err(time::Elapsed { .. }) => tx.send_data(Bytes::from_static(b":\n\n")).await?,
+        }
+    }
+}
+
+/// Encodes one row of `TAIL` output, whose columns have the given `names` and
+/// `types`, as a server-sent event.
+fn encode_event(
+    names: &[String],
+    types: &[ScalarType],
+    row_start: usize,
+    progress: bool,
+    row: &Row,
+) -> String {
+    let datums = row.unpack();
+    let timestamp = match datums[0] {
+        Datum::Decimal(d) => match u64::try_from(d.as_i128()) {
+            Ok(ts) => Value::Number(Number::from(ts)),
+            Err(_) => datum_to_json(datums[0], &types[0]),
+        },
+        _ => datum_to_json(datums[0], &types[0]),
+    };
+    let mut event = Map::new();
+    event.insert("timestamp".into(), timestamp);
+    if progress && datums[1] == Datum::True {
+        return format!("event: progress\ndata: {}\n\n", Value::Object(event));
+    }
+    for ((name, datum), typ) in names[..row_start].iter().zip(&datums).zip(types).skip(1) {
+        if name != "progressed" {
+            event.insert(name.clone(), datum_to_json(*datum, typ));
+        }
+    }
+    event.insert(
+        "row".into(),
+        Value::Array(
+            datums[row_start..]
+                .iter()
+                .zip(&types[row_start..])
+                .map(|(datum, typ)| datum_to_json(*datum, typ))
+                .collect(),
+        ),
+    );
+    format!("data: {}\n\n", Value::Object(event))
+}
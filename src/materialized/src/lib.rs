@@ -119,6 +119,12 @@ pub struct Config {
     pub listen_addr: Option<SocketAddr>,
     /// TLS encryption configuration.
     pub tls: Option<TlsConfig>,
+    /// The default `statement_timeout` of SQL sessions, which bounds how long
+    /// a query waits for its results. A zero duration disables the timeout.
+    pub statement_timeout: Duration,
+    /// Whether to serve the `/tail` endpoint of the HTTP server, which runs
+    /// arbitrary `TAIL` statements on behalf of unauthenticated clients.
+    pub http_tail: bool,
 
     // === Storage options. ===
     /// The directory in which `materialized` should store its own metadata.
@@ -169,6 +175,8 @@ pub async fn serve(mut config: Config) -> Result<Server, anyhow::Error> {
     let coord_client = coord::Client::new(cmdq_tx);
 
     // Extract timely dataflow parameters.
+    let http_tail = config.http_tail;
+    let statement_timeout = config.statement_timeout;
     let is_primary = config.process == 0;
     let num_timely_workers = config.num_timely_workers();
 
@@ -215,12 +223,17 @@ pub async fn serve(mut config: Config) -> Result<Server, anyhow::Error> {
             if is_primary {
                 let mut mux = Mux::new();
                 mux.add_handler(switchboard.clone());
-                mux.add_handler(pgwire::Server::new(tls.clone(), coord_client.clone()));
+                mux.add_handler(pgwire::Server::new(
+                    tls.clone(),
+                    coord_client.clone(),
+                    statement_timeout,
+                ));
                 mux.add_handler(http::Server::new(
                     tls,
                     coord_client,
                     start_time,
                     &num_timely_workers.to_string(),
+                    http_tail,
                 ));
                 mux.serve(incoming.take_until(drain_tripwire)).await;
             }
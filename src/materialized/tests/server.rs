@@ -12,6 +12,7 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 use reqwest::{blocking::Client, StatusCode, Url};
@@ -38,7 +39,9 @@ fn test_persistence() -> Result<(), Box<dyn Error>> {
              CREATE MATERIALIZED VIEW mat AS SELECT 'a', data, 'c' AS c, data FROM src; \
              CREATE DATABASE d; \
              CREATE SCHEMA d.s; \
-             CREATE VIEW d.s.v AS SELECT 1;",
+             CREATE VIEW d.s.v AS SELECT 1; \
+             COMMENT ON VIEW constant IS 'a constant'; \
+             COMMENT ON COLUMN mat.c IS 'always c';",
             temp_file.display(),
         ))?;
     }
@@ -75,6 +78,19 @@ fn test_persistence() -> Result<(), Box<dyn Error>> {
             &["v"]
         );
 
+        assert_eq!(
+            client
+                .query(
+                    "SELECT objsubid, description FROM pg_description \
+                     JOIN mz_views ON objoid = oid ORDER BY 1",
+                    &[],
+                )?
+                .into_iter()
+                .map(|row| (row.get(0), row.get(1)))
+                .collect::<Vec<(i32, String)>>(),
+            &[(0, "a constant".into()), (3, "always c".into())],
+        );
+
         // Test that catalog recovery correctly populates `mz_catalog_names`.
         assert_eq!(
             client
@@ -89,10 +105,12 @@ fn test_persistence() -> Result<(), Box<dyn Error>> {
                 "s1027", "s2001", "s2002", "s2003", "s2004", "s2005", "s2006", "s2007", "s2008",
                 "s2009", "s2010", "s2011", "s2012", "s2013", "s2014", "s2015", "s2016", "s2017",
                 "s2018", "s2019", "s2020", "s2021", "s2022", "s2023", "s2024", "s2025", "s2026",
-                "s2027", "s2028", "s2029", "s2030", "s2031", "s2032", "s3000", "s3001", "s3002",
-                "s3003", "s3004", "s3005", "s3006", "s3007", "s3008", "s3009", "s3010", "s3011",
-                "s3012", "s3013", "s3014", "s3015", "s3016", "s3017", "s3018", "s3019", "s3020",
-                "s3021", "s3022", "s3023", "s3024", "u1", "u2", "u3", "u4", "u5", "u6"
+                "s2027", "s2028", "s2029", "s2030", "s2031", "s2032", "s2033", "s2034", "s2035",
+                "s2036", "s2037", "s2038", "s2039", "s2040", "s2041", "s2042", "s3000", "s3001",
+                "s3002", "s3003", "s3004", "s3005", "s3006", "s3007", "s3008", "s3009", "s3010",
+                "s3011", "s3012", "s3013", "s3014", "s3015", "s3016", "s3017", "s3018", "s3019",
+                "s3020", "s3021", "s3022", "s3023", "s3024", "s3025", "s3026", "s3027", "s3028",
+                "u1", "u2", "u3", "u4", "u5", "u6"
             ]
         );
     }
@@ -191,6 +209,13 @@ fn test_http_sql() -> Result<(), Box<dyn Error>> {
             status: StatusCode::OK,
             body: r#"{"rows":[[3]],"col_names":["col"]}"#,
         },
+        // Lists are encoded as arrays, and decimals as strings, so that they
+        // do not lose precision.
+        TestCase {
+            query: "select list[1, 2] as l, 1.50::decimal(3, 2) as d, null::int as n",
+            status: StatusCode::OK,
+            body: r#"{"rows":[[[1,2],"1.50",null]],"col_names":["l","d","n"]}"#,
+        },
         // Only one query at a time.
         TestCase {
             query: "select 1; select 2",
@@ -214,3 +239,134 @@ fn test_http_sql() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+// Test the /tail GET endpoint of the HTTP server.
+#[test]
+fn test_http_tail() -> Result<(), Box<dyn Error>> {
+    let (server, mut client) = util::start_server(util::Config::default().enable_http_tail())?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+    client.batch_execute("INSERT INTO t VALUES (1)")?;
+
+    let mut url = Url::parse(&format!("http://{}/tail", server.inner.local_addr()))?;
+    url.query_pairs_mut()
+        .append_pair("sql", "TAIL t WITH (PROGRESS)");
+    // The response never ends, so it must not be subject to a timeout.
+    let http_client = Client::builder().timeout(None).build()?;
+    let res = http_client.get(url).send()?;
+    assert_eq!(res.status(), StatusCode::OK);
+    let mut lines = BufReader::new(res).lines();
+
+    // Returns the next event and its data, skipping keepalive comments.
+    let mut next_event = || -> Result<(String, serde_json::Value), Box<dyn Error>> {
+        let mut kind = String::from("data");
+        loop {
+            let line = lines.next().unwrap()?;
+            if let Some(k) = line.strip_prefix("event: ") {
+                kind = k.into();
+            } else if let Some(data) = line.strip_prefix("data: ") {
+                return Ok((kind, serde_json::from_str(data)?));
+            }
+        }
+    };
+    // Returns the next update, skipping progress events.
+    let mut next_update = || -> Result<serde_json::Value, Box<dyn Error>> {
+        loop {
+            let (kind, data) = next_event()?;
+            if kind == "data" {
+                return Ok(data);
+            }
+            assert_eq!(kind, "progress");
+            assert!(data["timestamp"].is_u64());
+        }
+    };
+
+    let update = next_update()?;
+    assert!(update["timestamp"].is_u64());
+    assert_eq!(update["diff"], 1);
+    assert_eq!(update["row"], serde_json::json!([1]));
+
+    client.batch_execute("INSERT INTO t VALUES (2)")?;
+    let update = next_update()?;
+    assert_eq!(update["diff"], 1);
+    assert_eq!(update["row"], serde_json::json!([2]));
+
+    // Only TAIL statements can be streamed.
+    let mut url = Url::parse(&format!("http://{}/tail", server.inner.local_addr()))?;
+    url.query_pairs_mut().append_pair("sql", "SELECT 1");
+    let res = http_client.get(url).send()?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.text()?, "expected a TAIL statement");
+
+    Ok(())
+}
+
+// Test that the /tail endpoint is only served when enabled.
+#[test]
+fn test_http_tail_disabled() -> Result<(), Box<dyn Error>> {
+    let (server, _client) = util::start_server(util::Config::default())?;
+    let mut url = Url::parse(&format!("http://{}/tail", server.inner.local_addr()))?;
+    url.query_pairs_mut().append_pair("sql", "TAIL mz_views");
+    let res = Client::new().get(url).send()?;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        res.text()?,
+        "the /tail endpoint is disabled; start materialized with --http-tail to enable it"
+    );
+
+    Ok(())
+}
+
+// Test that prepared statements reuse their plans, even with different
+// parameters, that the reuse is reported on the /metrics endpoint of the HTTP
+// server, and that plans are invalidated by changes to the catalog.
+#[test]
+fn test_plan_cache() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    let url = Url::parse(&format!("http://{}/metrics", server.inner.local_addr()))?;
+    let metric = |name: &str| -> Result<u64, Box<dyn Error>> {
+        let body = Client::new().get(url.clone()).send()?.text()?;
+        let value = body
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("{} ", name)))
+            .ok_or_else(|| format!("metric {} not found", name))?;
+        Ok(value.parse()?)
+    };
+
+    client.batch_execute("CREATE TABLE t (a int, b int)")?;
+    client.batch_execute("INSERT INTO t VALUES (1, 2), (3, 4)")?;
+    let stmt = client.prepare("SELECT b FROM t WHERE a = $1")?;
+    let query = |client: &mut postgres::Client, a: i32| -> Result<Vec<i32>, Box<dyn Error>> {
+        Ok(client
+            .query(&stmt, &[&a])?
+            .into_iter()
+            .map(|row| row.get(0))
+            .collect())
+    };
+
+    // The plan is cached before the parameters are bound, so executions with
+    // different parameters reuse it. The metrics are shared with the other
+    // tests in this process, which can only increase them.
+    let hits = metric("mz_plan_cache_hits")?;
+    let misses = metric("mz_plan_cache_misses")?;
+    for a in 0..100 {
+        let expected: &[i32] = match a {
+            1 => &[2],
+            3 => &[4],
+            _ => &[],
+        };
+        assert_eq!(query(&mut client, a)?, expected);
+    }
+    assert!(metric("mz_plan_cache_hits")? >= hits + 99);
+    assert!(metric("mz_plan_cache_misses")? < misses + 100);
+
+    // A new index invalidates the plan, and the new plan must still be
+    // correct.
+    let misses = metric("mz_plan_cache_misses")?;
+    client.batch_execute("CREATE INDEX t_a ON t (a)")?;
+    assert_eq!(query(&mut client, 3)?, &[4]);
+    assert!(metric("mz_plan_cache_misses")? > misses);
+
+    Ok(())
+}
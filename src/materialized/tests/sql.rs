@@ -16,7 +16,7 @@
 use std::error::Error;
 use std::fs::{self, File};
 use std::io::{BufRead, Write};
-use std::net::TcpListener;
+use std::net::{TcpListener, TcpStream};
 use std::path::Path;
 use std::str;
 use std::thread;
@@ -320,6 +320,73 @@ fn test_tail_empty_upper_frontier() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_tail_query() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default();
+    let (_server, mut client) = util::start_server(config)?;
+
+    let temp_dir = tempfile::tempdir()?;
+    let path = Path::join(temp_dir.path(), "dynamic.csv");
+    let mut file = File::create(&path)?;
+    let mut append = |data| -> Result<_, Box<dyn Error>> {
+        file.write_all(data)?;
+        file.sync_all()?;
+        Ok(())
+    };
+
+    client.batch_execute(&*format!(
+        "CREATE MATERIALIZED SOURCE dynamic_csv FROM FILE '{}' WITH (tail = true)
+         FORMAT CSV WITH 3 COLUMNS",
+        path.display()
+    ))?;
+
+    // Tailing a query should not require a view to be created first, and
+    // should only produce the rows that the query selects.
+    let cancel_token = client.cancel_token();
+    let mut tail_reader = client
+        .copy_out(
+            "COPY (TAIL (SELECT column1, mz_line_no FROM dynamic_csv WHERE column3 <> '00002'))
+             TO STDOUT",
+        )?
+        .split(b'\n');
+
+    append(b"City 1,ST,00001\n")?;
+    let next = tail_reader.next().unwrap()?;
+    assert!(next.ends_with(&b"1\tCity 1\t1"[..]));
+
+    append(b"City 2,ST,00002\nCity 3,ST,00003\n")?;
+    let next = tail_reader.next().unwrap()?;
+    assert!(next.ends_with(&b"1\tCity 3\t3"[..]));
+
+    cancel_token.cancel_query(postgres::NoTls)?;
+    assert!(tail_reader.next().is_none());
+    drop(tail_reader);
+
+    // The query's dependencies are tracked, so that they cannot be dropped
+    // out from under a sink that reads from the query.
+    client.batch_execute(&*format!(
+        "CREATE SINK query_sink FROM (SELECT column1 FROM dynamic_csv)
+         INTO AVRO OCF '{}'",
+        Path::join(temp_dir.path(), "query-sink.ocf").display()
+    ))?;
+    let err = client
+        .batch_execute("DROP SOURCE dynamic_csv")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("still depended upon by catalog item"),
+        "{}",
+        err
+    );
+    client.batch_execute("DROP SOURCE dynamic_csv CASCADE")?;
+    let sinks = client.query("SHOW SINKS", &[])?;
+    assert!(sinks.is_empty());
+
+    Ok(())
+}
+
 #[test]
 fn test_tail_unmaterialized() -> Result<(), Box<dyn Error>> {
     ore::test::init_logging();
@@ -406,6 +473,138 @@ fn test_tail_shutdown() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Tests that TAIL can be consumed incrementally through a cursor.
+#[test]
+fn test_tail_cursor() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+    client.batch_execute("INSERT INTO t VALUES (1), (2), (3)")?;
+    client.batch_execute("DECLARE c CURSOR FOR TAIL t")?;
+
+    // The snapshot may arrive in several batches, and FETCH only returns the
+    // rows that are ready, so keep fetching until all of them have arrived.
+    let mut values = vec![];
+    while values.len() < 3 {
+        for row in client.query("FETCH ALL c WITH (TIMEOUT = '10s')", &[])? {
+            assert_eq!(row.get::<_, i64>(1), 1);
+            values.push(row.get::<_, i32>(2));
+        }
+    }
+    values.sort();
+    assert_eq!(values, &[1, 2, 3]);
+
+    // There are no further changes, so a FETCH with a timeout should return
+    // without any rows.
+    let rows = client.query("FETCH c WITH (TIMEOUT = '100ms')", &[])?;
+    assert!(rows.is_empty());
+
+    // Canceling a FETCH that is waiting for rows interrupts the FETCH, but
+    // leaves the cursor open.
+    let cancel_token = client.cancel_token();
+    let canceler = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(500));
+        cancel_token.cancel_query(postgres::NoTls)
+    });
+    let err = client.query("FETCH c", &[]).unwrap_err().to_string();
+    assert!(
+        err.contains("canceling statement due to user request"),
+        "{}",
+        err
+    );
+    canceler.join().unwrap()?;
+
+    // A FETCH without a timeout waits for the next change.
+    client.batch_execute("INSERT INTO t VALUES (4)")?;
+    let rows = client.query("FETCH c", &[])?;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(2), 4);
+
+    // Closing the cursor tears down the dataflow that services the TAIL.
+    fn count_tail_dataflows(client: &mut postgres::Client) -> Result<i64, Box<dyn Error>> {
+        let row = client.query_one(
+            "SELECT count(*) FROM mz_dataflow_names WHERE name LIKE 'Dataflow: tail-%'",
+            &[],
+        )?;
+        Ok(row.get(0))
+    }
+    let mut attempts = 0;
+    while count_tail_dataflows(&mut client)? == 0 && attempts < 100 {
+        thread::sleep(Duration::from_millis(100));
+        attempts += 1;
+    }
+    assert!(count_tail_dataflows(&mut client)? > 0);
+    client.batch_execute("CLOSE c")?;
+    let mut attempts = 0;
+    while count_tail_dataflows(&mut client)? > 0 && attempts < 100 {
+        thread::sleep(Duration::from_millis(100));
+        attempts += 1;
+    }
+    assert_eq!(count_tail_dataflows(&mut client)?, 0);
+
+    let err = client.query("FETCH c", &[]).unwrap_err().to_string();
+    assert!(err.contains("cursor \"c\" does not exist"), "{}", err);
+
+    Ok(())
+}
+
+// Tests that TAIL with ENVELOPE UPSERT produces one row per key and timestamp.
+#[test]
+fn test_tail_upsert() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (id int, v int)")?;
+    client.batch_execute(
+        "CREATE MATERIALIZED VIEW v AS SELECT id, sum(v) AS v FROM t GROUP BY id HAVING sum(v) < 10",
+    )?;
+    client.batch_execute("INSERT INTO t VALUES (1, 1), (2, 2)")?;
+    client
+        .batch_execute("DECLARE c CURSOR FOR TAIL v WITH (SNAPSHOT, ENVELOPE UPSERT (KEY (id)))")?;
+
+    let mut fetch = |n| -> Result<Vec<(bool, i32, i64)>, Box<dyn Error>> {
+        let mut rows = vec![];
+        while rows.len() < n {
+            for row in client.query("FETCH ALL c WITH (TIMEOUT = '10s')", &[])? {
+                rows.push((row.get("deleted"), row.get("id"), row.get("v")));
+            }
+        }
+        rows.sort();
+        Ok(rows)
+    };
+
+    // The snapshot contains one row per key.
+    assert_eq!(fetch(2)?, &[(false, 1, 1), (false, 2, 2)]);
+
+    // An update to a key produces only its new value, not a retraction of the
+    // old value followed by an insertion of the new value.
+    client.batch_execute("INSERT INTO t VALUES (1, 3)")?;
+    assert_eq!(fetch(1)?, &[(false, 1, 4)]);
+
+    // A key that disappears is marked as deleted, with its last value.
+    client.batch_execute("INSERT INTO t VALUES (2, 20)")?;
+    assert_eq!(fetch(1)?, &[(true, 2, 2)]);
+
+    let err = client
+        .batch_execute("TAIL v WITH (ENVELOPE UPSERT (KEY (nope)))")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("No such column: nope"), "{}", err);
+
+    let err = client
+        .batch_execute("TAIL v WITH (ENVELOPE UPSERT (KEY (v)))")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("ENVELOPE UPSERT requires a KEY that uniquely identifies the rows"),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
+
 // Tests that temporary views created by one connection cannot be viewed
 // by another connection.
 #[test]
@@ -437,3 +636,437 @@ fn test_temporary_views() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+// Tests that every query in a transaction reads at the same timestamp, so that
+// writes committed by other connections in the meantime are not observed.
+#[test]
+fn test_transaction_snapshot() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client_a) = util::start_server(util::Config::default())?;
+    let mut client_b = server.connect()?;
+    client_a.batch_execute("CREATE TABLE t (a int)")?;
+    client_a.batch_execute("INSERT INTO t VALUES (1)")?;
+
+    let query = "SELECT count(*) AS count FROM t";
+
+    client_a.batch_execute("BEGIN")?;
+    let count: i64 = client_a.query_one(query, &[])?.get("count");
+    assert_eq!(count, 1);
+
+    client_b.batch_execute("INSERT INTO t VALUES (2)")?;
+    let count: i64 = client_b.query_one(query, &[])?.get("count");
+    assert_eq!(count, 2);
+
+    // The transaction continues to observe the state of the table as of its
+    // first query.
+    let count: i64 = client_a.query_one(query, &[])?.get("count");
+    assert_eq!(count, 1);
+
+    client_a.batch_execute("COMMIT")?;
+    let count: i64 = client_a.query_one(query, &[])?.get("count");
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+// Tests that the writes in a transaction become visible to other connections
+// only when, and all at once when, the transaction commits.
+#[test]
+fn test_transaction_writes() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client_a) = util::start_server(util::Config::default())?;
+    let mut client_b = server.connect()?;
+    client_a.batch_execute("CREATE TABLE a (x int)")?;
+    client_a.batch_execute("CREATE TABLE b (x int)")?;
+
+    let query = "SELECT (SELECT count(*) FROM a) AS a, (SELECT count(*) FROM b) AS b";
+    let mut counts = |client: &mut postgres::Client| -> Result<(i64, i64), Box<dyn Error>> {
+        let row = client.query_one(query, &[])?;
+        Ok((row.get("a"), row.get("b")))
+    };
+
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO a VALUES (1)")?;
+    client_a.batch_execute("INSERT INTO b VALUES (1), (2)")?;
+    assert_eq!(counts(&mut client_b)?, (0, 0));
+    client_a.batch_execute("COMMIT")?;
+    assert_eq!(counts(&mut client_b)?, (1, 2));
+
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO a VALUES (2)")?;
+    client_a.batch_execute("ROLLBACK")?;
+    assert_eq!(counts(&mut client_a)?, (1, 2));
+    assert_eq!(counts(&mut client_b)?, (1, 2));
+
+    // Reads in a transaction do not observe its own buffered writes.
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO a VALUES (2)")?;
+    assert_eq!(counts(&mut client_a)?, (1, 2));
+    client_a.batch_execute("COMMIT")?;
+    assert_eq!(counts(&mut client_a)?, (2, 2));
+
+    // A transaction fails to commit if a table that it wrote to was altered or
+    // dropped in the meantime.
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO a VALUES (3)")?;
+    client_b.batch_execute("ALTER TABLE a ADD COLUMN y int")?;
+    let err = client_a.batch_execute("COMMIT").unwrap_err().to_string();
+    assert!(
+        err.contains("table materialize.public.a was altered"),
+        "{}",
+        err
+    );
+
+    // Altering the table is detected even if it leaves the table with as
+    // many columns as before.
+    client_a.batch_execute("CREATE TABLE c (x int, y text)")?;
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO c VALUES (1, 'a')")?;
+    client_b.batch_execute("ALTER TABLE c DROP COLUMN x")?;
+    client_b.batch_execute("ALTER TABLE c ADD COLUMN z int")?;
+    let err = client_a.batch_execute("COMMIT").unwrap_err().to_string();
+    assert!(
+        err.contains("table materialize.public.c was altered"),
+        "{}",
+        err
+    );
+
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO b VALUES (3)")?;
+    client_b.batch_execute("DROP TABLE b")?;
+    let err = client_a.batch_execute("COMMIT").unwrap_err().to_string();
+    assert!(err.contains("was dropped before it committed"), "{}", err);
+
+    let row = client_b.query_one("SELECT count(*) FROM a", &[])?;
+    assert_eq!(row.get::<_, i64>(0), 2);
+    let row = client_b.query_one("SELECT count(*) FROM c", &[])?;
+    assert_eq!(row.get::<_, i64>(0), 0);
+
+    // The values of a transaction's inserts are evaluated at the timestamp
+    // at which it commits.
+    client_a.batch_execute("CREATE TABLE ts (ts int8)")?;
+    client_a.batch_execute("BEGIN")?;
+    client_a.batch_execute("INSERT INTO ts VALUES (mz_logical_timestamp()::int8)")?;
+    thread::sleep(Duration::from_millis(100));
+    client_a.batch_execute("INSERT INTO ts VALUES (mz_logical_timestamp()::int8)")?;
+    client_a.batch_execute("COMMIT")?;
+    let row = client_b.query_one("SELECT count(DISTINCT ts) FROM ts", &[])?;
+    assert_eq!(row.get::<_, i64>(0), 1);
+
+    Ok(())
+}
+
+// Tests that, under strict serializability, the timestamps at which queries
+// read and write never go backwards, even when consecutive queries go through
+// different indexes whose inputs have advanced to different times.
+#[test]
+fn test_strict_serializable_timestamps() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("data.csv");
+    fs::write(&path, "1\n")?;
+    client.batch_execute(&*format!(
+        "CREATE MATERIALIZED SOURCE src FROM FILE '{}' WITH (tail = true)
+         FORMAT CSV WITH 1 COLUMNS",
+        path.display()
+    ))?;
+    client.batch_execute("CREATE TABLE t (ts int8)")?;
+    client.batch_execute("CREATE MATERIALIZED VIEW v AS SELECT count(*) FROM t")?;
+    client.batch_execute("SET transaction_isolation = 'strict serializable'")?;
+
+    let read_ts = |client: &mut postgres::Client, relation: &str| -> Result<i64, Box<dyn Error>> {
+        let query = format!(
+            "SELECT mz_logical_timestamp()::int8 FROM (SELECT count(*) FROM {})",
+            relation
+        );
+        Ok(client.query_one(&*query, &[])?.get(0))
+    };
+
+    let mut timestamps: Vec<i64> = vec![];
+    for _ in 0..10 {
+        client.batch_execute("INSERT INTO t VALUES (mz_logical_timestamp()::int8)")?;
+        timestamps.push(client.query_one("SELECT max(ts) FROM t", &[])?.get(0));
+        for relation in &["src", "v", "t", "src"] {
+            timestamps.push(read_ts(&mut client, *relation)?);
+        }
+    }
+    for window in timestamps.windows(2) {
+        assert!(
+            window[0] <= window[1],
+            "timestamp went backwards: {:?}",
+            timestamps
+        );
+    }
+
+    Ok(())
+}
+
+// Tests that a query that runs for longer than `statement_timeout` is canceled,
+// and that the session remains usable afterwards.
+#[test]
+fn test_statement_timeout() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (_server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+    client.batch_execute("INSERT INTO t VALUES (1), (2)")?;
+    client.batch_execute("SET statement_timeout = '100ms'")?;
+
+    // A timestamp far in the future will not be complete for a long time, so
+    // these queries cannot finish. The second requires a transient dataflow.
+    for query in &[
+        "SELECT * FROM t AS OF 99999999999999",
+        "SELECT count(*) FROM t AS OF 99999999999999",
+    ] {
+        let err = client.query(*query, &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("canceling statement due to statement timeout"));
+    }
+
+    let count: i64 = client.query_one("SELECT count(*) FROM t", &[])?.get(0);
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+// Tests that the server's default `statement_timeout` applies to new sessions,
+// and to sessions that are reset.
+#[test]
+fn test_statement_timeout_default() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().statement_timeout(Duration::from_millis(100));
+    let (_server, mut client) = util::start_server(config)?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+
+    let timeout: String = client.query_one("SHOW statement_timeout", &[])?.get(0);
+    assert_eq!(timeout, "100ms");
+    let err = client
+        .query("SELECT * FROM t AS OF 99999999999999", &[])
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("canceling statement due to statement timeout"));
+
+    client.batch_execute("SET statement_timeout = '200ms'")?;
+    let timeout: String = client.query_one("SHOW statement_timeout", &[])?.get(0);
+    assert_eq!(timeout, "200ms");
+    client.batch_execute("DISCARD ALL")?;
+    let timeout: String = client.query_one("SHOW statement_timeout", &[])?.get(0);
+    assert_eq!(timeout, "100ms");
+
+    Ok(())
+}
+
+// Tests that a query is canceled when its client disconnects while waiting
+// for its results, which tears down the dataflow built to answer it.
+#[test]
+fn test_cancel_on_disconnect() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client) = util::start_server(util::Config::default())?;
+    client.batch_execute("CREATE TABLE t (a int)")?;
+
+    fn count_peek_dataflows(client: &mut postgres::Client) -> Result<i64, Box<dyn Error>> {
+        let row = client.query_one(
+            "SELECT count(*) FROM mz_dataflow_names WHERE name LIKE 'Dataflow: temp-view-%'",
+            &[],
+        )?;
+        Ok(row.get(0))
+    }
+
+    // Speak just enough of the protocol to start a query that cannot finish,
+    // as a timestamp far in the future will not be complete for a long time,
+    // without waiting for any responses.
+    let mut conn = TcpStream::connect(server.inner.local_addr())?;
+    let params = b"user\0root\0\0";
+    let query = b"SELECT count(*) FROM t AS OF 99999999999999\0";
+    let mut buf = vec![];
+    buf.extend(&(8 + params.len() as i32).to_be_bytes());
+    buf.extend(&196_608_i32.to_be_bytes());
+    buf.extend(params);
+    buf.push(b'Q');
+    buf.extend(&(4 + query.len() as i32).to_be_bytes());
+    buf.extend(query);
+    conn.write_all(&buf)?;
+
+    let mut attempts = 0;
+    while count_peek_dataflows(&mut client)? == 0 && attempts < 100 {
+        thread::sleep(Duration::from_millis(100));
+        attempts += 1;
+    }
+    assert!(count_peek_dataflows(&mut client)? > 0);
+
+    drop(conn);
+    let mut attempts = 0;
+    while count_peek_dataflows(&mut client)? > 0 && attempts < 100 {
+        thread::sleep(Duration::from_millis(100));
+        attempts += 1;
+    }
+    assert_eq!(count_peek_dataflows(&mut client)?, 0);
+
+    Ok(())
+}
+
+// Tests that a transaction can go on to read any relation in the schemas that
+// its first query read, at the same timestamp, and that it fails clearly when
+// reading from elsewhere.
+#[test]
+fn test_transaction_schema_reads() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let (server, mut client_a) = util::start_server(util::Config::default())?;
+    let mut client_b = server.connect()?;
+    client_a.batch_execute(
+        "CREATE TABLE t (a int);
+         CREATE TABLE u (a int);
+         CREATE SCHEMA other;
+         CREATE TABLE other.v (a int);
+         INSERT INTO t VALUES (1);
+         INSERT INTO u VALUES (1);",
+    )?;
+
+    client_a.batch_execute("BEGIN")?;
+    let count: i64 = client_a.query_one("SELECT count(*) FROM t", &[])?.get(0);
+    assert_eq!(count, 1);
+
+    client_b.batch_execute("INSERT INTO u VALUES (2)")?;
+
+    // `u` was not read by the first query, but it is in the same schema, and
+    // so is read at the transaction's timestamp.
+    let count: i64 = client_a.query_one("SELECT count(*) FROM u", &[])?.get(0);
+    assert_eq!(count, 1);
+
+    let err = client_a
+        .query_one("SELECT count(*) FROM other.v", &[])
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("transaction cannot read materialize.other.v"),
+        "unexpected error: {}",
+        err
+    );
+    client_a.batch_execute("ROLLBACK")?;
+
+    let count: i64 = client_a.query_one("SELECT count(*) FROM u", &[])?.get(0);
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+// Ensures that lookups against an index, which may be answered by seeking to
+// particular keys on a single worker, return the same results as scans that
+// consult every key on every worker.
+#[test]
+fn test_point_lookups() -> Result<(), Box<dyn Error>> {
+    ore::test::init_logging();
+
+    let config = util::Config::default().threads(4);
+    let (_server, mut client) = util::start_server(config)?;
+
+    client.batch_execute(
+        "CREATE TABLE t (a int, b text);
+         CREATE INDEX t_a ON t (a);
+         INSERT INTO t SELECT generate_series(1, 100), 'row';
+         INSERT INTO t VALUES (42, 'dup');",
+    )?;
+
+    for a in 1..=100 {
+        let rows = client.query(&*format!("SELECT b FROM t WHERE a = {} ORDER BY b", a), &[])?;
+        let expected: &[&str] = if a == 42 { &["dup", "row"] } else { &["row"] };
+        let actual: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+        assert_eq!(actual, expected, "lookup of a = {}", a);
+    }
+
+    let count: i64 = client
+        .query_one("SELECT count(*) FROM t WHERE a = 101", &[])?
+        .get(0);
+    assert_eq!(count, 0);
+
+    let count: i64 = client.query_one("SELECT count(*) FROM t", &[])?.get(0);
+    assert_eq!(count, 101);
+
+    // Lookups of several keys, and of ranges of keys, should also agree with
+    // a scan of the whole index.
+    for (predicate, expected) in &[
+        ("a IN (1, 42, 99, 1000)", 4),
+        ("a = 7 OR a = 8 OR a = 7", 2),
+        ("a BETWEEN 40 AND 45", 7),
+        ("a > 95 AND a <= 100", 5),
+        ("a >= 98", 3),
+        ("a BETWEEN 45 AND 40", 0),
+        ("a IN (3, 4) AND a BETWEEN 4 AND 10", 1),
+        ("a::text LIKE '4%'", 12),
+    ] {
+        let count: i64 = client
+            .query_one(&*format!("SELECT count(*) FROM t WHERE {}", predicate), &[])?
+            .get(0);
+        assert_eq!(count, *expected, "lookup of {}", predicate);
+    }
+
+    // Ranges too wide to enumerate, and ranges of non-integer keys, skip the
+    // keys outside of the range, and must agree with a scan as well.
+    client.batch_execute(
+        "CREATE TABLE events (ts timestamp, n int, k text);
+         CREATE INDEX events_ts ON events (ts);
+         CREATE INDEX events_n ON events (n);
+         CREATE INDEX events_k_n ON events (k, n);
+         INSERT INTO events SELECT
+             '2020-01-01'::timestamp + (n::text || ' minutes')::interval,
+             n,
+             CASE WHEN n % 2 = 0 THEN 'even' ELSE 'odd' END
+         FROM generate_series(-3000, 3000) AS n;",
+    )?;
+    for (predicate, expected) in &[
+        ("n BETWEEN -2000 AND 1999", 4000),
+        ("n > -10 AND n < 10", 19),
+        ("n < -2990", 10),
+        ("n >= 2990", 11),
+        ("ts BETWEEN '2020-01-01 00:00' AND '2020-01-01 10:00'", 601),
+        ("ts > '2020-01-02' AND ts < '2020-01-02 00:10'", 9),
+        ("ts < '2019-12-30 00:00'", 120),
+        ("k = 'even' AND n BETWEEN -1500 AND 1500", 1501),
+        ("k = 'odd' AND n > 2900", 50),
+        ("k = 'odd'", 3000),
+        ("k >= 'e' AND k < 'f'", 3001),
+        ("n BETWEEN 3001 AND 5000", 0),
+    ] {
+        let count: i64 = client
+            .query_one(
+                &*format!("SELECT count(*) FROM events WHERE {}", predicate),
+                &[],
+            )?
+            .get(0);
+        assert_eq!(count, *expected, "lookup of {}", predicate);
+    }
+
+    // Errors are not partitioned by key, so a lookup of any key in a view that
+    // contains an error must report that error, no matter which worker holds
+    // the key.
+    client.batch_execute(
+        "CREATE TABLE divisors (a int, b int);
+         INSERT INTO divisors SELECT generate_series(1, 100), 1;
+         INSERT INTO divisors VALUES (101, 0);
+         CREATE MATERIALIZED VIEW quotients AS SELECT a, 1 / b AS q FROM divisors;
+         CREATE INDEX quotients_a ON quotients (a);",
+    )?;
+    for a in 1..=101 {
+        let err = client
+            .query(&*format!("SELECT q FROM quotients WHERE a = {}", a), &[])
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("division by zero"),
+            "lookup of a = {}: {}",
+            a,
+            err
+        );
+    }
+
+    Ok(())
+}
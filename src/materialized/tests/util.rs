@@ -28,6 +28,8 @@ pub struct Config {
     logging_granularity: Option<Duration>,
     tls: Option<materialized::TlsConfig>,
     experimental_mode: bool,
+    statement_timeout: Duration,
+    http_tail: bool,
     threads: usize,
 }
 
@@ -38,6 +40,8 @@ impl Default for Config {
             logging_granularity: Some(Duration::from_millis(10)),
             tls: None,
             experimental_mode: false,
+            statement_timeout: Duration::from_secs(0),
+            http_tail: false,
             threads: 1,
         }
     }
@@ -71,6 +75,16 @@ impl Config {
         self
     }
 
+    pub fn statement_timeout(mut self, statement_timeout: Duration) -> Self {
+        self.statement_timeout = statement_timeout;
+        self
+    }
+
+    pub fn enable_http_tail(mut self) -> Self {
+        self.http_tail = true;
+        self
+    }
+
     pub fn threads(mut self, threads: usize) -> Self {
         self.threads = threads;
         self
@@ -96,6 +110,8 @@ pub fn start_server(config: Config) -> Result<(Server, postgres::Client), Box<dy
         symbiosis_url: None,
         listen_addr: None,
         tls: config.tls,
+        statement_timeout: config.statement_timeout,
+        http_tail: config.http_tail,
         experimental_mode: config.experimental_mode,
         telemetry_url: None,
     }))?;
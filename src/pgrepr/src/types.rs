@@ -51,6 +51,15 @@ pub enum Type {
     Uuid,
     /// An object identifier.
     Oid,
+    /// A user-defined enum type.
+    Enum {
+        /// The OID of the type.
+        oid: u32,
+        /// The name of the type.
+        name: String,
+        /// The labels of the type, in declaration order.
+        labels: Vec<String>,
+    },
 }
 
 lazy_static! {
@@ -90,6 +99,10 @@ impl Type {
         }
     }
 
+    /// Returns the `postgres_types` type that corresponds to this type.
+    ///
+    /// Panics if called on a user-defined type, as `postgres_types` only knows
+    /// about built-in types.
     pub(crate) fn inner(&self) -> &'static postgres_types::Type {
         match self {
             Type::Bool => &postgres_types::Type::BOOL,
@@ -123,6 +136,9 @@ impl Type {
                 Type::Timestamp => &postgres_types::Type::TIMESTAMP_ARRAY,
                 Type::TimestampTz => &postgres_types::Type::TIMESTAMPTZ_ARRAY,
                 Type::Uuid => &postgres_types::Type::UUID_ARRAY,
+                // Enum types do not have corresponding array types, so
+                // arrays of enums are described as arrays of an unknown type.
+                Type::Enum { .. } => &postgres_types::Type::ANYARRAY,
                 Type::List(_) | Type::Array(_) => unreachable!(),
                 Type::Record(_) => &postgres_types::Type::RECORD_ARRAY,
                 Type::Oid => &postgres_types::Type::OID_ARRAY,
@@ -130,19 +146,26 @@ impl Type {
             Type::List(_) => &LIST,
             Type::Record(_) => &postgres_types::Type::RECORD,
             Type::Oid => &postgres_types::Type::OID,
+            Type::Enum { .. } => unreachable!("user-defined types have no postgres_types type"),
         }
     }
 
     /// Returns the name that PostgreSQL uses for this type.
-    pub fn name(&self) -> &'static str {
-        self.inner().name()
+    pub fn name(&self) -> &str {
+        match self {
+            Type::Enum { name, .. } => name,
+            _ => self.inner().name(),
+        }
     }
 
     /// Returns the [OID] of this type.
     ///
     /// [OID]: https://www.postgresql.org/docs/current/datatype-oid.html
     pub fn oid(&self) -> u32 {
-        self.inner().oid()
+        match self {
+            Type::Enum { oid, .. } => *oid,
+            _ => self.inner().oid(),
+        }
     }
 
     /// Returns the number of bytes in the binary representation of this
@@ -168,6 +191,7 @@ impl Type {
             Type::List(_) => -1,
             Type::Record(_) => -1,
             Type::Oid => 4,
+            Type::Enum { .. } => 4,
         }
     }
 }
@@ -196,6 +220,11 @@ impl From<&ScalarType> for Type {
                 Type::Record(fields.iter().map(|(_name, ty)| Type::from(ty)).collect())
             }
             ScalarType::Oid => Type::Oid,
+            ScalarType::Enum { oid, name, labels } => Type::Enum {
+                oid: *oid,
+                name: name.clone(),
+                labels: labels.clone(),
+            },
         }
     }
 }
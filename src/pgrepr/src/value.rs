@@ -90,6 +90,9 @@ impl Value {
             (Datum::False, ScalarType::Bool) => Some(Value::Bool(false)),
             (Datum::Int32(i), ScalarType::Int32) => Some(Value::Int4(i)),
             (Datum::Int32(i), ScalarType::Oid) => Some(Value::Int4(i)),
+            (Datum::Int32(i), ScalarType::Enum { labels, .. }) => {
+                Some(Value::Text(labels[i as usize].clone()))
+            }
             (Datum::Int64(i), ScalarType::Int64) => Some(Value::Int8(i)),
             (Datum::Float32(f), ScalarType::Float32) => Some(Value::Float4(*f)),
             (Datum::Float64(f), ScalarType::Float64) => Some(Value::Float8(*f)),
@@ -160,7 +163,18 @@ impl Value {
                 ScalarType::Decimal(MAX_DECIMAL_PRECISION, d.0.scale()),
             ),
             Value::Bytea(b) => (Datum::Bytes(buf.push_bytes(b)), ScalarType::Bytes),
-            Value::Text(s) => (Datum::String(buf.push_string(s)), ScalarType::String),
+            Value::Text(s) => match typ {
+                Type::Enum { labels, .. } => {
+                    // `Value::decode` has already checked that the label is
+                    // valid.
+                    let i = labels
+                        .iter()
+                        .position(|label| *label == s)
+                        .expect("enum label known to be valid");
+                    (Datum::Int32(i as i32), null_datum(typ).1)
+                }
+                _ => (Datum::String(buf.push_string(s)), ScalarType::String),
+            },
             Value::Jsonb(js) => (buf.push_unary_row(js.0.into_row()), ScalarType::Jsonb),
             Value::Uuid(u) => (Datum::Uuid(u), ScalarType::Uuid),
             Value::Array { .. } => {
@@ -328,6 +342,15 @@ impl Value {
             Type::Numeric => Value::Numeric(Numeric(strconv::parse_decimal(raw)?)),
             Type::Jsonb => Value::Jsonb(Jsonb(strconv::parse_jsonb(raw)?)),
             Type::Uuid => Value::Uuid(Uuid::parse_str(raw)?),
+            Type::Enum { name, labels, .. } => {
+                if !labels.iter().any(|label| label == raw) {
+                    return Err(Box::new(DecodeError::new(format!(
+                        "invalid input value for enum {}: \"{}\"",
+                        name, raw
+                    ))));
+                }
+                Value::Text(raw.to_owned())
+            }
             Type::Array(_) => {
                 return Err(Box::new(DecodeError::new(
                     "input of array types is not implemented",
@@ -361,6 +384,8 @@ impl Value {
             Type::Timestamp => NaiveDateTime::from_sql(ty.inner(), raw).map(Value::Timestamp),
             Type::TimestampTz => DateTime::<Utc>::from_sql(ty.inner(), raw).map(Value::TimestampTz),
             Type::Uuid => Uuid::from_sql(ty.inner(), raw).map(Value::Uuid),
+            // The binary representation of an enum value is its label.
+            Type::Enum { .. } => Value::decode_text(ty, raw),
             Type::Array(_) => Err(Box::new(DecodeError::new(
                 "input of array types is not implemented",
             ))),
@@ -479,6 +504,11 @@ pub fn null_datum(ty: &Type) -> (Datum<'static>, ScalarType) {
         Type::Timestamp => ScalarType::Timestamp,
         Type::TimestampTz => ScalarType::TimestampTz,
         Type::Uuid => ScalarType::Uuid,
+        Type::Enum { oid, name, labels } => ScalarType::Enum {
+            oid: *oid,
+            name: name.clone(),
+            labels: labels.clone(),
+        },
         Type::Array(t) => {
             let (_, elem_type) = null_datum(t);
             ScalarType::Array(Box::new(elem_type))
@@ -14,6 +14,7 @@
 //!
 //! [1]: https://www.postgresql.org/docs/11/protocol-message-formats.html
 
+use std::collections::VecDeque;
 use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
@@ -67,6 +68,12 @@ impl fmt::Display for CodecError {
 pub struct FramedConn<A> {
     conn_id: u32,
     inner: sink::Buffer<Framed<A, Codec>, BackendMessage>,
+    /// Messages received by [`FramedConn::wait_closed`], which are returned by
+    /// [`FramedConn::recv`] before any further messages are read.
+    pending: VecDeque<FrontendMessage>,
+    /// The outcome of the read that ended [`FramedConn::wait_closed`], which
+    /// is returned by [`FramedConn::recv`] once `pending` is exhausted.
+    closed: Option<Result<(), io::Error>>,
 }
 
 impl<A> FramedConn<A>
@@ -85,6 +92,8 @@ where
         FramedConn {
             conn_id,
             inner: Framed::new(inner, Codec::new()).buffer(32),
+            pending: VecDeque::new(),
+            closed: None,
         }
     }
 
@@ -94,6 +103,13 @@ where
     /// terminates the stream, returns `None`. Returns an error if the client
     /// sends a malformatted message or if the connection underlying is broken.
     pub async fn recv(&mut self) -> Result<Option<FrontendMessage>, io::Error> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message));
+        }
+        if let Some(closed) = self.closed.take() {
+            closed?;
+            return Ok(None);
+        }
         let message = self.inner.try_next().await?;
         match &message {
             Some(message) => trace!("cid={} recv={:?}", self.conn_id, message),
@@ -102,6 +118,37 @@ where
         Ok(message)
     }
 
+    /// Waits for the client to close the connection, or for the connection to
+    /// break.
+    ///
+    /// This allows noticing that a client has gone away while waiting for
+    /// something other than the client. Any messages that the client sends in
+    /// the meantime are kept, to be returned by later calls to
+    /// [`FramedConn::recv`]. Dropping the returned future before it completes
+    /// loses no messages.
+    pub async fn wait_closed(&mut self) {
+        if self.closed.is_some() {
+            return;
+        }
+        loop {
+            match self.inner.try_next().await {
+                Ok(Some(message)) => {
+                    trace!("cid={} recv={:?}", self.conn_id, message);
+                    self.pending.push_back(message);
+                }
+                Ok(None) => {
+                    trace!("cid={} recv=<eof>", self.conn_id);
+                    self.closed = Some(Ok(()));
+                    return;
+                }
+                Err(e) => {
+                    self.closed = Some(Err(e));
+                    return;
+                }
+            }
+        }
+    }
+
     /// Encodes and sends one backend message to the client.
     ///
     /// Note that the connection is not flushed after calling this method. You
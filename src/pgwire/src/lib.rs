@@ -29,5 +29,6 @@ mod protocol;
 mod secrets;
 mod server;
 
+pub use id_alloc::{IdAllocator, IdExhaustionError};
 pub use protocol::match_handshake;
 pub use server::Server;
@@ -15,6 +15,7 @@ use std::mem;
 use std::time::Instant;
 
 use byteorder::{ByteOrder, NetworkEndian};
+use futures::future::{self, Either, FutureExt};
 use futures::stream::{self, StreamExt, TryStreamExt};
 use itertools::izip;
 use lazy_static::lazy_static;
@@ -24,11 +25,11 @@ use prometheus::{register_histogram_vec, register_uint_counter};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::time::{self, Duration};
 
-use coord::{session::RowBatchStream, ExecuteResponse, StartupMessage};
+use coord::{session::RowBatchStream, ExecuteResponse, RowsFuture, StartupMessage};
 use dataflow_types::PeekResponse;
 use ore::cast::CastFrom;
 use repr::{Datum, RelationDesc, RelationType, Row, RowArena};
-use sql::ast::Statement;
+use sql::ast::{FetchDirection, Statement};
 use sql::plan::{CopyFormat, StatementDesc};
 
 use crate::codec::FramedConn;
@@ -492,11 +493,11 @@ where
         portal_name: String,
         max_rows: usize,
     ) -> Result<State, comm::Error> {
-        let session = self.coord_client.session();
-        let row_desc = session
+        let row_desc = self
+            .coord_client
+            .session()
             .get_portal(&portal_name)
-            .and_then(|portal| session.get_prepared_statement(&portal.statement_name))
-            .and_then(|stmt| stmt.desc().relation_desc.clone());
+            .and_then(|portal| portal.desc.relation_desc.clone());
         let portal = match self.coord_client.session().get_portal_mut(&portal_name) {
             Some(portal) => portal,
             None => {
@@ -562,12 +563,9 @@ where
 
     async fn describe_portal(&mut self, name: String) -> Result<State, comm::Error> {
         let session = self.coord_client.session();
-        let row_desc = session.get_portal(&name).map(|portal| {
-            let stmt = session
-                .get_prepared_statement(&portal.statement_name)
-                .expect("statement name known to be valid");
-            describe_rows(stmt.desc(), &portal.result_formats)
-        });
+        let row_desc = session
+            .get_portal(&name)
+            .map(|portal| describe_rows(&portal.desc, &portal.result_formats));
         match row_desc {
             Some(row_desc) => {
                 self.conn.send(row_desc).await?;
@@ -661,6 +659,7 @@ where
                 created!(existed, SqlState::DUPLICATE_OBJECT, "view")
             }
             ExecuteResponse::CreatedType => command_complete!("CREATE TYPE"),
+            ExecuteResponse::CreatedFunction => command_complete!("CREATE FUNCTION"),
             ExecuteResponse::Deleted(n) => command_complete!("DELETE {}", n),
             ExecuteResponse::DiscardedTemp => command_complete!("DISCARD TEMP"),
             ExecuteResponse::DiscardedAll => command_complete!("DISCARD ALL"),
@@ -672,6 +671,7 @@ where
             ExecuteResponse::DroppedTable => command_complete!("DROP TABLE"),
             ExecuteResponse::DroppedView => command_complete!("DROP VIEW"),
             ExecuteResponse::DroppedType => command_complete!("DROP TYPE"),
+            ExecuteResponse::DroppedFunction => command_complete!("DROP FUNCTION"),
             ExecuteResponse::EmptyQuery => {
                 self.conn.send(BackendMessage::EmptyQueryResponse).await?;
                 Ok(State::Ready)
@@ -689,19 +689,8 @@ where
             ExecuteResponse::SendingRows(rx) => {
                 let row_desc =
                     row_desc.expect("missing row description for ExecuteResponse::SendingRows");
-                match rx.await? {
-                    PeekResponse::Canceled => {
-                        self.error(ErrorResponse::error(
-                            SqlState::QUERY_CANCELED,
-                            "canceling statement due to user request",
-                        ))
-                        .await
-                    }
-                    PeekResponse::Error(text) => {
-                        self.error(ErrorResponse::error(SqlState::INTERNAL_ERROR, text))
-                            .await
-                    }
-                    PeekResponse::Rows(rows) => {
+                match self.recv_peek_rows(rx).await? {
+                    Ok(rows) => {
                         self.send_rows(
                             row_desc,
                             portal_name,
@@ -710,6 +699,7 @@ where
                         )
                         .await
                     }
+                    Err(err) => self.error(err).await,
                 }
             }
             ExecuteResponse::SetVariable { name } => {
@@ -738,30 +728,17 @@ where
             ExecuteResponse::Tailing { rx } => {
                 let row_desc =
                     row_desc.expect("missing row description for ExecuteResponse::Tailing");
-                self.send_rows(row_desc, portal_name, Box::new(rx), max_rows)
+                self.send_rows(row_desc, portal_name, tail_rows(rx), max_rows)
                     .await
             }
             ExecuteResponse::CopyTo { format, resp } => {
                 let row_desc =
                     row_desc.expect("missing row description for ExecuteResponse::CopyTo");
                 let rows: RowBatchStream = match *resp {
-                    ExecuteResponse::Tailing { rx } => Box::new(rx),
-                    ExecuteResponse::SendingRows(rx) => match rx.await? {
-                        // TODO(mjibson): This logic is duplicated from SendingRows. Dedup?
-                        PeekResponse::Canceled => {
-                            return self
-                                .error(ErrorResponse::error(
-                                    SqlState::QUERY_CANCELED,
-                                    "canceling statement due to user request",
-                                ))
-                                .await;
-                        }
-                        PeekResponse::Error(text) => {
-                            return self
-                                .error(ErrorResponse::error(SqlState::INTERNAL_ERROR, text))
-                                .await;
-                        }
-                        PeekResponse::Rows(rows) => Box::new(stream::iter(vec![Ok(rows)])),
+                    ExecuteResponse::Tailing { rx } => tail_rows(rx),
+                    ExecuteResponse::SendingRows(rx) => match self.recv_peek_rows(rx).await? {
+                        Ok(rows) => Box::new(stream::iter(vec![Ok(rows)])),
+                        Err(err) => return self.error(err).await,
                     },
                     _ => {
                         return self
@@ -777,7 +754,236 @@ where
             ExecuteResponse::Updated(n) => command_complete!("UPDATE {}", n),
             ExecuteResponse::AlteredObject(o) => command_complete!("ALTER {}", o),
             ExecuteResponse::AlteredIndexLogicalCompaction => command_complete!("ALTER INDEX"),
+            ExecuteResponse::DeclaredCursor => command_complete!("DECLARE CURSOR"),
+            ExecuteResponse::ClosedCursor => command_complete!("CLOSE CURSOR"),
+            ExecuteResponse::Commented => command_complete!("COMMENT"),
+            ExecuteResponse::Fetch {
+                name,
+                count,
+                timeout,
+            } => {
+                let row_desc =
+                    row_desc.expect("missing row description for ExecuteResponse::Fetch");
+                self.fetch(name, count, timeout, row_desc, portal_name)
+                    .await
+            }
+        }
+    }
+
+    /// Sends up to `count` rows from the cursor named `name`.
+    ///
+    /// If the cursor has no rows available, waits up to `timeout` for some to
+    /// arrive, or indefinitely if no timeout is specified. Once at least one
+    /// row is available, only the rows that are immediately available are
+    /// sent, so that a `FETCH` against a `TAIL` does not block waiting for
+    /// `count` rows to trickle in. Any rows that are not sent remain buffered
+    /// in the cursor for the next `FETCH`.
+    async fn fetch(
+        &mut self,
+        name: String,
+        count: Option<FetchDirection>,
+        timeout: Option<Duration>,
+        row_desc: RelationDesc,
+        portal_name: String,
+    ) -> Result<State, comm::Error> {
+        let count = match count {
+            None => 1,
+            Some(FetchDirection::ForwardAll) => usize::MAX,
+            Some(FetchDirection::ForwardCount(n)) => usize::cast_from(n),
+        };
+
+        let remaining_rows = match self.coord_client.session().get_portal_mut(&name) {
+            Some(cursor) => cursor.remaining_rows.take(),
+            None => {
+                return self
+                    .error(ErrorResponse::error(
+                        SqlState::INVALID_CURSOR_NAME,
+                        format!("cursor \"{}\" does not exist", name),
+                    ))
+                    .await;
+            }
+        };
+        // The first FETCH against a cursor is what actually starts the
+        // underlying query running.
+        let mut rows: RowBatchStream = match remaining_rows {
+            Some(rows) => rows,
+            None => match self.coord_client.execute(name.clone()).await {
+                Ok(ExecuteResponse::Tailing { rx }) => tail_rows(rx),
+                Ok(ExecuteResponse::SendingRows(rx)) => match self.recv_peek_rows(rx).await? {
+                    Ok(rows) => Box::new(stream::iter(vec![Ok(rows)])),
+                    Err(err) => return self.error(err).await,
+                },
+                Ok(_) => {
+                    return self
+                        .error(ErrorResponse::error(
+                            SqlState::INTERNAL_ERROR,
+                            "unsupported cursor response type".to_string(),
+                        ))
+                        .await;
+                }
+                Err(e) => {
+                    return self
+                        .error(ErrorResponse::error(
+                            SqlState::INTERNAL_ERROR,
+                            format!("{:#}", e),
+                        ))
+                        .await;
+                }
+            },
+        };
+
+        // Cancellation requests that arrived before this FETCH do not apply
+        // to it.
+        let cancel_rx = self.coord_client.session().cancel_rx();
+        while let Ok(Some(())) = cancel_rx.try_next() {}
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut batch_rows = vec![];
+        let mut exhausted = false;
+        let mut canceled = false;
+        let mut error = None;
+        while batch_rows.len() < count {
+            let next = if batch_rows.is_empty() {
+                let timeout = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+                let next = Box::pin(async {
+                    match timeout {
+                        None => Ok(rows.next().await),
+                        Some(timeout) => time::timeout(timeout, rows.next()).await,
+                    }
+                });
+                let cancel = self.coord_client.session().cancel_rx().next();
+                match future::select(next, cancel).await {
+                    Either::Left((Ok(next), _)) => next,
+                    Either::Left((Err(time::Elapsed { .. }), _)) => break,
+                    Either::Right(_) => {
+                        canceled = true;
+                        break;
+                    }
+                }
+            } else {
+                match rows.next().now_or_never() {
+                    Some(next) => next,
+                    None => break,
+                }
+            };
+            match next {
+                Some(Ok(batch)) => batch_rows.extend(batch),
+                Some(Err(e)) => {
+                    error = Some(e);
+                    exhausted = true;
+                    break;
+                }
+                None => {
+                    exhausted = true;
+                    break;
+                }
+            }
+        }
+        if exhausted {
+            rows = Box::new(stream::empty());
+        }
+        if canceled || error.is_some() {
+            if let Some(cursor) = self.coord_client.session().get_portal_mut(&name) {
+                cursor.set_remaining_rows(Box::new(rows));
+            }
+            return self
+                .error(match error {
+                    Some(error) => ErrorResponse::error(SqlState::INTERNAL_ERROR, error),
+                    None => ErrorResponse::error(
+                        SqlState::QUERY_CANCELED,
+                        "canceling statement due to user request",
+                    ),
+                })
+                .await;
+        }
+        if batch_rows.len() > count {
+            let leftover = batch_rows.split_off(count);
+            rows = Box::new(stream::iter(vec![Ok(leftover)]).chain(rows));
+        }
+
+        let result_formats = self
+            .coord_client
+            .session()
+            .get_portal(&portal_name)
+            .map(|portal| portal.result_formats.clone())
+            .expect("valid portal name for fetch");
+        self.conn.set_encode_state(
+            row_desc
+                .typ()
+                .column_types
+                .iter()
+                .map(|ty| pgrepr::Type::from(&ty.scalar_type))
+                .zip(result_formats)
+                .collect(),
+        );
+        let n = batch_rows.len();
+        self.conn
+            .send_all(
+                batch_rows.into_iter().map(|row| {
+                    BackendMessage::DataRow(pgrepr::values_from_row(row, row_desc.typ()))
+                }),
+            )
+            .await?;
+        ROWS_RETURNED.inc_by(u64::cast_from(n));
+
+        if let Some(cursor) = self.coord_client.session().get_portal_mut(&name) {
+            cursor.set_remaining_rows(Box::new(rows));
         }
+        self.conn
+            .send(BackendMessage::CommandComplete {
+                tag: format!("FETCH {}", n),
+            })
+            .await?;
+        Ok(State::Ready)
+    }
+
+    /// Waits for the rows produced by a peek.
+    ///
+    /// If the rows do not arrive within the session's `statement_timeout`, or
+    /// if the client disconnects while waiting for them, the peek is canceled,
+    /// which also tears down any transient dataflow that was built to service
+    /// it. A peek that fails or is canceled produces an error to report to the
+    /// client.
+    async fn recv_peek_rows(
+        &mut self,
+        mut rx: RowsFuture,
+    ) -> Result<Result<Vec<Row>, ErrorResponse>, comm::Error> {
+        let timeout = self.coord_client.session().vars().statement_timeout();
+        let interrupt = {
+            let timed_out = if timeout == Duration::from_secs(0) {
+                future::pending::<()>().left_future()
+            } else {
+                time::delay_for(timeout).right_future()
+            };
+            let timed_out = timed_out.map(|()| true).boxed();
+            let disconnected = self.conn.wait_closed().map(|()| false).boxed();
+            match future::select(&mut rx, future::select(timed_out, disconnected)).await {
+                Either::Left((resp, _)) => Either::Left(resp?),
+                Either::Right((Either::Left((timed_out, _)), _))
+                | Either::Right((Either::Right((timed_out, _)), _)) => Either::Right(timed_out),
+            }
+        };
+        let (resp, timed_out) = match interrupt {
+            Either::Left(resp) => (resp, false),
+            Either::Right(timed_out) => {
+                self.coord_client.cancel().await;
+                // The peek may have completed in the meantime, in which case
+                // its rows are still returned.
+                (rx.await?, timed_out)
+            }
+        };
+        Ok(match resp {
+            PeekResponse::Rows(rows) => Ok(rows),
+            PeekResponse::Error(text) => Err(ErrorResponse::error(SqlState::INTERNAL_ERROR, text)),
+            PeekResponse::Canceled if timed_out => Err(ErrorResponse::error(
+                SqlState::QUERY_CANCELED,
+                "canceling statement due to statement timeout",
+            )),
+            PeekResponse::Canceled => Err(ErrorResponse::error(
+                SqlState::QUERY_CANCELED,
+                "canceling statement due to user request",
+            )),
+        })
     }
 
     async fn send_rows(
@@ -793,7 +999,14 @@ where
             .get_portal_mut(&portal_name)
             .expect("valid portal name for send rows");
 
-        let mut batch = rows.try_next().await?;
+        let mut batch = match rows.try_next().await {
+            Ok(batch) => batch,
+            Err(e) => {
+                return self
+                    .error(ErrorResponse::error(SqlState::INTERNAL_ERROR, e))
+                    .await
+            }
+        };
         if let Some([row, ..]) = batch.as_deref() {
             let datums = row.unpack();
             let col_types = &row_desc.typ().column_types;
@@ -857,7 +1070,14 @@ where
                 break;
             }
             self.conn.flush().await?;
-            batch = rows.try_next().await?;
+            batch = match rows.try_next().await {
+                Ok(batch) => batch,
+                Err(e) => {
+                    return self
+                        .error(ErrorResponse::error(SqlState::INTERNAL_ERROR, e))
+                        .await
+                }
+            };
         }
 
         ROWS_RETURNED.inc_by(u64::cast_from(total_sent_rows));
@@ -938,8 +1158,12 @@ where
         loop {
             match time::timeout(Duration::from_secs(1), stream.next()).await {
                 Ok(None) => break,
-                Ok(Some(rows)) => {
-                    let rows = rows?;
+                Ok(Some(Err(e))) => {
+                    return self
+                        .error(ErrorResponse::error(SqlState::INTERNAL_ERROR, e))
+                        .await
+                }
+                Ok(Some(Ok(rows))) => {
                     count += rows.len();
                     for row in rows {
                         encode_fn(row, typ, &mut out)?;
@@ -1012,6 +1236,13 @@ where
     }
 }
 
+/// Adapts the batches produced by a `TAIL` into a stream of rows, which ends
+/// with an error if either the `TAIL` or the channel that carries its batches
+/// fails.
+fn tail_rows(rx: comm::mpsc::Receiver<Result<Vec<Row>, String>>) -> RowBatchStream {
+    Box::new(rx.map(|batch| batch.map_err(|e| e.to_string()).and_then(|batch| batch)))
+}
+
 fn pad_formats(formats: Vec<pgrepr::Format>, n: usize) -> Result<Vec<pgrepr::Format>, String> {
     match (formats.len(), n) {
         (0, e) => Ok(vec![pgrepr::Format::Text; e]),
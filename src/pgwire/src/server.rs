@@ -10,6 +10,7 @@
 use std::fmt;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use anyhow::bail;
 use openssl::ssl::SslAcceptor;
@@ -29,15 +30,23 @@ pub struct Server {
     secrets: SecretManager,
     tls: Option<SslAcceptor>,
     coord_client: coord::Client,
+    /// The default `statement_timeout` of sessions, which applies whenever a
+    /// session has not set its own.
+    statement_timeout: Duration,
 }
 
 impl Server {
-    pub fn new(tls: Option<SslAcceptor>, coord_client: coord::Client) -> Server {
+    pub fn new(
+        tls: Option<SslAcceptor>,
+        coord_client: coord::Client,
+        statement_timeout: Duration,
+    ) -> Server {
         Server {
             id_alloc: IdAllocator::new(1, 1 << 16),
             secrets: SecretManager::new(),
             tls,
             coord_client,
+            statement_timeout,
         }
     }
 
@@ -57,7 +66,11 @@ impl Server {
                     };
                     self.secrets.generate(conn_id);
 
-                    let coord_client = self.coord_client.for_session(Session::new(conn_id));
+                    let mut session = Session::new(conn_id);
+                    session
+                        .vars_mut()
+                        .set_statement_timeout_default(self.statement_timeout);
+                    let coord_client = self.coord_client.for_session(session);
 
                     let machine = StateMachine {
                         conn: FramedConn::new(conn_id, conn),
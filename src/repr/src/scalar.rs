@@ -366,6 +366,9 @@ impl<'a> Datum<'a> {
                     (Datum::True, _) => false,
                     (Datum::Int32(_), ScalarType::Int32) => true,
                     (Datum::Int32(_), ScalarType::Oid) => true,
+                    (Datum::Int32(i), ScalarType::Enum { labels, .. }) => {
+                        i >= 0 && (i as usize) < labels.len()
+                    }
                     (Datum::Int32(_), _) => false,
                     (Datum::Int64(_), ScalarType::Int64) => true,
                     (Datum::Int64(_), _) => false,
@@ -696,6 +699,19 @@ pub enum ScalarType {
     },
     /// A PostgreSQL object identifier.
     Oid,
+    /// A user-defined enum type.
+    ///
+    /// Values of an enum type are represented as [`Datum::Int32`]s that hold
+    /// the position of the value's label in `labels`, so that values sort in
+    /// the order in which their labels were declared.
+    Enum {
+        /// The OID of the type in the catalog.
+        oid: u32,
+        /// The name of the type.
+        name: String,
+        /// The labels of the type, in declaration order.
+        labels: Vec<String>,
+    },
 }
 
 impl<'a> ScalarType {
@@ -761,6 +777,11 @@ impl<'a> ScalarType {
             ScalarType::Decimal(..) => ScalarType::Decimal(0, 0),
             ScalarType::List(..) => ScalarType::List(Box::new(ScalarType::String)),
             ScalarType::Record { .. } => ScalarType::Record { fields: vec![] },
+            ScalarType::Enum { .. } => ScalarType::Enum {
+                oid: 0,
+                name: "".into(),
+                labels: vec![],
+            },
             _ => self.clone(),
         }
     }
@@ -809,6 +830,18 @@ impl PartialEq for ScalarType {
 
             (List(a), List(b)) | (Array(a), Array(b)) => a.eq(b),
             (Record { fields: fields_a }, Record { fields: fields_b }) => fields_a.eq(fields_b),
+            (
+                Enum {
+                    oid: oid_a,
+                    name: name_a,
+                    labels: labels_a,
+                },
+                Enum {
+                    oid: oid_b,
+                    name: name_b,
+                    labels: labels_b,
+                },
+            ) => oid_a == oid_b && name_a == name_b && labels_a == labels_b,
 
             (Bool, _)
             | (Int32, _)
@@ -828,7 +861,8 @@ impl PartialEq for ScalarType {
             | (Array(_), _)
             | (List(_), _)
             | (Record { .. }, _)
-            | (Oid, _) => false,
+            | (Oid, _)
+            | (Enum { .. }, _) => false,
         }
     }
 }
@@ -870,6 +904,12 @@ impl Hash for ScalarType {
             }
             Uuid => state.write_u8(16),
             Oid => state.write_u8(17),
+            Enum { oid, name, labels } => {
+                state.write_u8(18);
+                oid.hash(state);
+                name.hash(state);
+                labels.hash(state);
+            }
         }
     }
 }
@@ -906,6 +946,7 @@ impl fmt::Display for ScalarType {
                 f.write_str(")")
             }
             Oid => f.write_str("oid"),
+            Enum { name, .. } => f.write_str(name),
         }
     }
 }
@@ -872,6 +872,104 @@ where
     }
 }
 
+/// Parses a record with `nfields` fields from `s`.
+///
+/// `gen_elem` is called once per field, in order, with the text of the field,
+/// or `None` if the field is NULL.
+pub fn parse_record<'a, T, E>(
+    s: &'a str,
+    nfields: usize,
+    gen_elem: impl FnMut(Option<Cow<'a, str>>) -> Result<T, E>,
+) -> Result<Vec<T>, ParseError>
+where
+    E: fmt::Display,
+{
+    parse_record_inner(s, nfields, gen_elem)
+        .map_err(|details| ParseError::new("record", s).with_details(details))
+}
+
+fn parse_record_inner<'a, T, E>(
+    s: &'a str,
+    nfields: usize,
+    mut gen_elem: impl FnMut(Option<Cow<'a, str>>) -> Result<T, E>,
+) -> Result<Vec<T>, String>
+where
+    E: fmt::Display,
+{
+    let mut elems = vec![];
+    let buf = &mut LexBuf::new(s);
+
+    buf.take_while(|ch| ch.is_ascii_whitespace());
+    if !buf.consume('(') {
+        bail!("missing left parenthesis")
+    }
+
+    // Consume elements. Unlike list elements, record elements are not
+    // trimmed, and an element with no characters at all is NULL.
+    loop {
+        if elems.len() == nfields {
+            bail!("too many columns")
+        }
+        let elem = record_lex_element(buf)?;
+        elems.push(gen_elem(elem).map_err(|e| e.to_string())?);
+        match buf.next() {
+            Some(',') => (),
+            Some(')') => break,
+            _ => bail!("unexpected end of input"),
+        }
+    }
+
+    if elems.len() < nfields {
+        bail!("too few columns")
+    }
+
+    buf.take_while(|ch| ch.is_ascii_whitespace());
+    if let Some(c) = buf.next() {
+        bail!(
+            "malformed record literal; contains '{}' after terminal ')'",
+            c
+        )
+    }
+
+    Ok(elems)
+}
+
+// Result of `None` indicates element is NULL.
+fn record_lex_element<'a>(buf: &mut LexBuf<'a>) -> Result<Option<Cow<'a, str>>, String> {
+    let s = buf.take_while(|ch| !matches!(ch, ',' | ')' | '"' | '\\'));
+
+    // `Cow::Borrowed` optimization for elements without quotes or escapes.
+    match buf.peek() {
+        Some(',') | Some(')') if s.is_empty() => return Ok(None),
+        Some(',') | Some(')') => return Ok(Some(s.into())),
+        _ => (),
+    }
+
+    let mut s = s.to_string();
+    let mut in_quotes = false;
+    loop {
+        match buf.next() {
+            Some('\\') => match buf.next() {
+                Some(c) => s.push(c),
+                None => bail!("unterminated element"),
+            },
+            // Within quotes, a doubled quote is a literal quote.
+            Some('"') if in_quotes && buf.peek() == Some('"') => {
+                buf.next();
+                s.push('"');
+            }
+            Some('"') => in_quotes = !in_quotes,
+            Some(',') | Some(')') if !in_quotes => {
+                buf.prev();
+                break;
+            }
+            Some(c) => s.push(c),
+            None => bail!("unterminated element"),
+        }
+    }
+    Ok(Some(s.into()))
+}
+
 pub fn format_record<F, T>(
     buf: &mut F,
     elems: impl IntoIterator<Item = T>,
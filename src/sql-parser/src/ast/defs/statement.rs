@@ -20,7 +20,8 @@
 
 use crate::ast::display::{self, AstDisplay, AstFormatter};
 use crate::ast::{
-    ColumnDef, Connector, Envelope, Expr, Format, Ident, ObjectName, Query, TableConstraint, Value,
+    ColumnDef, Connector, DataType, Envelope, Expr, Format, Ident, ObjectName, Query,
+    TableConstraint, Value,
 };
 
 /// A top-level statement (SELECT, INSERT, CREATE, etc.)
@@ -40,8 +41,14 @@ pub enum Statement {
     CreateTable(CreateTableStatement),
     CreateIndex(CreateIndexStatement),
     CreateMapType(CreateMapTypeStatement),
+    CreateEnumType(CreateEnumTypeStatement),
+    CreateCompositeType(CreateCompositeTypeStatement),
+    CreateFunction(CreateFunctionStatement),
     AlterObjectRename(AlterObjectRenameStatement),
     AlterIndexOptions(AlterIndexOptionsStatement),
+    AlterTable(AlterTableStatement),
+    AlterSource(AlterSourceStatement),
+    Comment(CommentStatement),
     Discard(DiscardStatement),
     DropDatabase(DropDatabaseStatement),
     DropObjects(DropObjectsStatement),
@@ -55,6 +62,7 @@ pub enum Statement {
     ShowCreateTable(ShowCreateTableStatement),
     ShowCreateSink(ShowCreateSinkStatement),
     ShowCreateIndex(ShowCreateIndexStatement),
+    ShowCreateAll(ShowCreateAllStatement),
     ShowVariable(ShowVariableStatement),
     StartTransaction(StartTransactionStatement),
     SetTransaction(SetTransactionStatement),
@@ -62,6 +70,9 @@ pub enum Statement {
     Rollback(RollbackStatement),
     Tail(TailStatement),
     Explain(ExplainStatement),
+    Declare(DeclareStatement),
+    Fetch(FetchStatement),
+    Close(CloseStatement),
 }
 
 impl AstDisplay for Statement {
@@ -80,8 +91,14 @@ impl AstDisplay for Statement {
             Statement::CreateTable(stmt) => f.write_node(stmt),
             Statement::CreateIndex(stmt) => f.write_node(stmt),
             Statement::CreateMapType(stmt) => f.write_node(stmt),
+            Statement::CreateEnumType(stmt) => f.write_node(stmt),
+            Statement::CreateCompositeType(stmt) => f.write_node(stmt),
+            Statement::CreateFunction(stmt) => f.write_node(stmt),
             Statement::AlterObjectRename(stmt) => f.write_node(stmt),
             Statement::AlterIndexOptions(stmt) => f.write_node(stmt),
+            Statement::AlterTable(stmt) => f.write_node(stmt),
+            Statement::AlterSource(stmt) => f.write_node(stmt),
+            Statement::Comment(stmt) => f.write_node(stmt),
             Statement::Discard(stmt) => f.write_node(stmt),
             Statement::DropDatabase(stmt) => f.write_node(stmt),
             Statement::DropObjects(stmt) => f.write_node(stmt),
@@ -95,6 +112,7 @@ impl AstDisplay for Statement {
             Statement::ShowCreateTable(stmt) => f.write_node(stmt),
             Statement::ShowCreateSink(stmt) => f.write_node(stmt),
             Statement::ShowCreateIndex(stmt) => f.write_node(stmt),
+            Statement::ShowCreateAll(stmt) => f.write_node(stmt),
             Statement::ShowVariable(stmt) => f.write_node(stmt),
             Statement::StartTransaction(stmt) => f.write_node(stmt),
             Statement::SetTransaction(stmt) => f.write_node(stmt),
@@ -102,6 +120,9 @@ impl AstDisplay for Statement {
             Statement::Rollback(stmt) => f.write_node(stmt),
             Statement::Tail(stmt) => f.write_node(stmt),
             Statement::Explain(stmt) => f.write_node(stmt),
+            Statement::Declare(stmt) => f.write_node(stmt),
+            Statement::Fetch(stmt) => f.write_node(stmt),
+            Statement::Close(stmt) => f.write_node(stmt),
         }
     }
 }
@@ -375,14 +396,38 @@ impl AstDisplay for CreateSourceStatement {
 }
 impl_display!(CreateSourceStatement);
 
+/// The relation that a `CREATE SINK` or `TAIL` statement reads from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SinkRelation {
+    /// A named source, table, or view.
+    Name(ObjectName),
+    /// A parenthesized query, like `(SELECT ...)`.
+    Query(Query),
+}
+
+impl AstDisplay for SinkRelation {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            SinkRelation::Name(name) => f.write_node(name),
+            SinkRelation::Query(query) => {
+                f.write_str("(");
+                f.write_node(query);
+                f.write_str(")");
+            }
+        }
+    }
+}
+impl_display!(SinkRelation);
+
 /// `CREATE SINK`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CreateSinkStatement {
     pub name: ObjectName,
-    pub from: ObjectName,
+    pub from: SinkRelation,
     pub connector: Connector,
     pub with_options: Vec<SqlOption>,
     pub format: Option<Format>,
+    pub envelope: Option<Envelope>,
     pub with_snapshot: bool,
     pub as_of: Option<Expr>,
     pub if_not_exists: bool,
@@ -408,6 +453,10 @@ impl AstDisplay for CreateSinkStatement {
             f.write_str(" FORMAT ");
             f.write_node(format);
         }
+        if let Some(envelope) = &self.envelope {
+            f.write_str(" ENVELOPE ");
+            f.write_node(envelope);
+        }
         if self.with_snapshot {
             f.write_str(" WITH SNAPSHOT");
         } else {
@@ -573,6 +622,130 @@ impl AstDisplay for CreateMapTypeStatement {
 }
 impl_display!(CreateMapTypeStatement);
 
+/// `CREATE TYPE .. AS ENUM`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateEnumTypeStatement {
+    /// Name of the created type.
+    pub name: ObjectName,
+    /// The labels of the enum, in sort order.
+    pub labels: Vec<String>,
+}
+
+impl AstDisplay for CreateEnumTypeStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("CREATE TYPE ");
+        f.write_node(&self.name);
+        f.write_str(" AS ENUM (");
+        for (i, label) in self.labels.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ");
+            }
+            f.write_str("'");
+            f.write_node(&display::escape_single_quote_string(label));
+            f.write_str("'");
+        }
+        f.write_str(")");
+    }
+}
+impl_display!(CreateEnumTypeStatement);
+
+/// `CREATE TYPE .. AS (<field> <type>, ...)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateCompositeTypeStatement {
+    /// Name of the created type.
+    pub name: ObjectName,
+    /// The names and types of the fields.
+    pub fields: Vec<ColumnDef>,
+}
+
+impl AstDisplay for CreateCompositeTypeStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("CREATE TYPE ");
+        f.write_node(&self.name);
+        f.write_str(" AS (");
+        f.write_node(&display::comma_separated(&self.fields));
+        f.write_str(")");
+    }
+}
+impl_display!(CreateCompositeTypeStatement);
+
+/// `CREATE FUNCTION .. RETURNS .. LANGUAGE SQL AS ..`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateFunctionStatement {
+    /// Name of the created function.
+    pub name: ObjectName,
+    /// The parameters of the function.
+    pub params: Vec<FunctionParameter>,
+    /// The type of the value that the function returns.
+    pub returns: FunctionReturnType,
+    /// The query that computes the function's result.
+    pub body: Query,
+}
+
+impl AstDisplay for CreateFunctionStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("CREATE FUNCTION ");
+        f.write_node(&self.name);
+        f.write_str("(");
+        f.write_node(&display::comma_separated(&self.params));
+        f.write_str(") RETURNS ");
+        f.write_node(&self.returns);
+        f.write_str(" LANGUAGE SQL AS '");
+        // The body is formatted in the same mode as the statement, so that
+        // the names within it are quoted when the statement is persisted.
+        let body = if f.stable() {
+            self.body.to_ast_string_stable()
+        } else {
+            self.body.to_ast_string()
+        };
+        f.write_node(&display::escape_single_quote_string(&body));
+        f.write_str("'");
+    }
+}
+impl_display!(CreateFunctionStatement);
+
+/// A parameter in a `CREATE FUNCTION` statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionParameter {
+    /// The name of the parameter, if it has one.
+    pub name: Option<Ident>,
+    pub data_type: DataType,
+}
+
+impl AstDisplay for FunctionParameter {
+    fn fmt(&self, f: &mut AstFormatter) {
+        if let Some(name) = &self.name {
+            f.write_node(name);
+            f.write_str(" ");
+        }
+        f.write_node(&self.data_type);
+    }
+}
+impl_display!(FunctionParameter);
+
+/// The return type in a `CREATE FUNCTION` statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FunctionReturnType {
+    /// `RETURNS <type>`
+    Scalar(DataType),
+    /// `RETURNS TABLE (<column> <type>, ...)`
+    Table(Vec<ColumnDef>),
+}
+
+impl AstDisplay for FunctionReturnType {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            FunctionReturnType::Scalar(data_type) => f.write_node(data_type),
+            FunctionReturnType::Table(columns) => {
+                f.write_str("TABLE (");
+                f.write_node(&display::comma_separated(columns));
+                f.write_str(")");
+            }
+        }
+    }
+}
+impl_display!(FunctionReturnType);
+
 /// `ALTER <OBJECT> ... RENAME TO`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AlterObjectRenameStatement {
@@ -637,6 +810,140 @@ impl AstDisplay for AlterIndexOptionsStatement {
 
 impl_display!(AlterIndexOptionsStatement);
 
+/// `ALTER TABLE ... {ADD, DROP} COLUMN`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterTableStatement {
+    pub name: ObjectName,
+    pub if_exists: bool,
+    pub action: AlterTableAction,
+}
+
+impl AstDisplay for AlterTableStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER TABLE ");
+        if self.if_exists {
+            f.write_str("IF EXISTS ");
+        }
+        f.write_node(&self.name);
+        f.write_str(" ");
+        f.write_node(&self.action);
+    }
+}
+impl_display!(AlterTableStatement);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AlterTableAction {
+    /// `ADD [ COLUMN ] [ IF NOT EXISTS ] <column_def>`
+    AddColumn {
+        if_not_exists: bool,
+        column_def: ColumnDef,
+    },
+    /// `DROP [ COLUMN ] [ IF EXISTS ] <column_name>`
+    DropColumn { if_exists: bool, name: Ident },
+}
+
+impl AstDisplay for AlterTableAction {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            AlterTableAction::AddColumn {
+                if_not_exists,
+                column_def,
+            } => {
+                f.write_str("ADD COLUMN ");
+                if *if_not_exists {
+                    f.write_str("IF NOT EXISTS ");
+                }
+                f.write_node(column_def);
+            }
+            AlterTableAction::DropColumn { if_exists, name } => {
+                f.write_str("DROP COLUMN ");
+                if *if_exists {
+                    f.write_str("IF EXISTS ");
+                }
+                f.write_node(name);
+            }
+        }
+    }
+}
+impl_display!(AlterTableAction);
+
+/// `ALTER SOURCE ... SET`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlterSourceStatement {
+    pub name: ObjectName,
+    pub if_exists: bool,
+    pub options: Vec<SqlOption>,
+}
+
+impl AstDisplay for AlterSourceStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("ALTER SOURCE ");
+        if self.if_exists {
+            f.write_str("IF EXISTS ");
+        }
+        f.write_node(&self.name);
+        f.write_str(" SET (");
+        f.write_node(&display::comma_separated(&self.options));
+        f.write_str(")");
+    }
+}
+impl_display!(AlterSourceStatement);
+
+/// `COMMENT ON ... IS ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommentStatement {
+    pub object: CommentObject,
+    /// The new comment, or `None` to remove the existing comment.
+    pub comment: Option<String>,
+}
+
+impl AstDisplay for CommentStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("COMMENT ON ");
+        f.write_node(&self.object);
+        f.write_str(" IS ");
+        match &self.comment {
+            Some(comment) => {
+                f.write_str("'");
+                f.write_node(&display::escape_single_quote_string(comment));
+                f.write_str("'");
+            }
+            None => f.write_str("NULL"),
+        }
+    }
+}
+impl_display!(CommentStatement);
+
+/// The object that a `COMMENT ON` statement applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CommentObject {
+    /// A catalog item, like a table or an index.
+    Item {
+        object_type: ObjectType,
+        name: ObjectName,
+    },
+    /// A column of a relation, named by the relation's name followed by the
+    /// column's name.
+    Column { name: ObjectName },
+}
+
+impl AstDisplay for CommentObject {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            CommentObject::Item { object_type, name } => {
+                f.write_node(object_type);
+                f.write_str(" ");
+                f.write_node(name);
+            }
+            CommentObject::Column { name } => {
+                f.write_str("COLUMN ");
+                f.write_node(name);
+            }
+        }
+    }
+}
+impl_display!(CommentObject);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DiscardStatement {
     pub target: DiscardTarget,
@@ -672,7 +979,7 @@ impl_display!(DiscardTarget);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DropDatabaseStatement {
-    pub name: Ident,
+    pub names: Vec<Ident>,
     pub if_exists: bool,
 }
 
@@ -682,7 +989,7 @@ impl AstDisplay for DropDatabaseStatement {
         if self.if_exists {
             f.write_str("IF EXISTS ");
         }
-        f.write_node(&self.name);
+        f.write_node(&display::comma_separated(&self.names));
     }
 }
 impl_display!(DropDatabaseStatement);
@@ -811,6 +1118,7 @@ impl AstDisplay for ShowObjectsStatement {
             ObjectType::Source => "SOURCES",
             ObjectType::Sink => "SINKS",
             ObjectType::Type => "TYPES",
+            ObjectType::Function => "FUNCTIONS",
             ObjectType::Index => unreachable!(),
         });
         if let Some(from) = &self.from {
@@ -949,6 +1257,17 @@ impl AstDisplay for ShowCreateIndexStatement {
 }
 impl_display!(ShowCreateIndexStatement);
 
+/// `SHOW CREATE ALL`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShowCreateAllStatement;
+
+impl AstDisplay for ShowCreateAllStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("SHOW CREATE ALL");
+    }
+}
+impl_display!(ShowCreateAllStatement);
+
 /// `{ BEGIN [ TRANSACTION | WORK ] | START TRANSACTION } ...`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct StartTransactionStatement {
@@ -1018,18 +1337,26 @@ impl_display!(RollbackStatement);
 /// `TAIL`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TailStatement {
-    pub name: ObjectName,
+    pub relation: SinkRelation,
     pub options: Vec<WithOption>,
+    pub envelope: Option<TailEnvelope>,
     pub as_of: Option<Expr>,
 }
 
 impl AstDisplay for TailStatement {
     fn fmt(&self, f: &mut AstFormatter) {
         f.write_str("TAIL ");
-        f.write_node(&self.name);
-        if !self.options.is_empty() {
+        f.write_node(&self.relation);
+        if !self.options.is_empty() || self.envelope.is_some() {
             f.write_str(" WITH (");
             f.write_node(&display::comma_separated(&self.options));
+            if let Some(envelope) = &self.envelope {
+                if !self.options.is_empty() {
+                    f.write_str(", ");
+                }
+                f.write_str("ENVELOPE ");
+                f.write_node(envelope);
+            }
             f.write_str(")");
         }
         if let Some(as_of) = &self.as_of {
@@ -1040,6 +1367,98 @@ impl AstDisplay for TailStatement {
 }
 impl_display!(TailStatement);
 
+/// How the output of a `TAIL` represents changes to its input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TailEnvelope {
+    /// One row per key and timestamp, holding the latest value for the key.
+    Upsert { key: Vec<Ident> },
+}
+
+impl AstDisplay for TailEnvelope {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            TailEnvelope::Upsert { key } => {
+                f.write_str("UPSERT (KEY (");
+                f.write_node(&display::comma_separated(key));
+                f.write_str("))");
+            }
+        }
+    }
+}
+impl_display!(TailEnvelope);
+
+/// `DECLARE ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeclareStatement {
+    pub name: Ident,
+    pub stmt: Box<Statement>,
+}
+
+impl AstDisplay for DeclareStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("DECLARE ");
+        f.write_node(&self.name);
+        f.write_str(" CURSOR FOR ");
+        f.write_node(&self.stmt);
+    }
+}
+impl_display!(DeclareStatement);
+
+/// `CLOSE ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CloseStatement {
+    pub name: Ident,
+}
+
+impl AstDisplay for CloseStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("CLOSE ");
+        f.write_node(&self.name);
+    }
+}
+impl_display!(CloseStatement);
+
+/// `FETCH ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FetchStatement {
+    pub name: Ident,
+    pub count: Option<FetchDirection>,
+    pub options: Vec<WithOption>,
+}
+
+impl AstDisplay for FetchStatement {
+    fn fmt(&self, f: &mut AstFormatter) {
+        f.write_str("FETCH ");
+        if let Some(count) = &self.count {
+            f.write_node(count);
+            f.write_str(" ");
+        }
+        f.write_node(&self.name);
+        if !self.options.is_empty() {
+            f.write_str(" WITH (");
+            f.write_node(&display::comma_separated(&self.options));
+            f.write_str(")");
+        }
+    }
+}
+impl_display!(FetchStatement);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FetchDirection {
+    ForwardAll,
+    ForwardCount(u64),
+}
+
+impl AstDisplay for FetchDirection {
+    fn fmt(&self, f: &mut AstFormatter) {
+        match self {
+            FetchDirection::ForwardAll => f.write_str("ALL"),
+            FetchDirection::ForwardCount(count) => f.write_str(count),
+        }
+    }
+}
+impl_display!(FetchDirection);
+
 /// `EXPLAIN ...`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ExplainStatement {
@@ -1086,6 +1505,7 @@ pub enum ObjectType {
     Sink,
     Index,
     Type,
+    Function,
 }
 
 impl AstDisplay for ObjectType {
@@ -1098,6 +1518,7 @@ impl AstDisplay for ObjectType {
             ObjectType::Sink => "SINK",
             ObjectType::Index => "INDEX",
             ObjectType::Type => "TYPE",
+            ObjectType::Function => "FUNCTION",
         })
     }
 }
@@ -23,6 +23,7 @@ use std::fmt;
 use repr::adt::datetime::DateTimeField;
 
 use crate::ast::display::{self, AstDisplay, AstFormatter};
+use crate::ast::ObjectName;
 
 #[derive(Debug)]
 pub struct ValueError(String);
@@ -233,7 +234,7 @@ pub enum DataType {
     /// Object ID
     Oid,
     /// User-defined type
-    Custom(String),
+    Custom(ObjectName),
 }
 
 impl AstDisplay for DataType {
@@ -301,7 +302,7 @@ impl AstDisplay for DataType {
             }
             DataType::Jsonb => f.write_str("jsonb"),
             DataType::Oid => f.write_str("oid"),
-            DataType::Custom(c) => f.write_str(c),
+            DataType::Custom(name) => f.write_node(name),
         }
     }
 }
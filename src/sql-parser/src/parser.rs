@@ -49,6 +49,20 @@ pub fn parse_statements(sql: &str) -> Result<Vec<Statement>, ParserError> {
     Parser::new(sql, tokens).parse_statements()
 }
 
+/// Parses the body of a SQL-language function, which must contain exactly one
+/// query, optionally followed by a semicolon.
+fn parse_function_body(sql: &str) -> Result<Query, ParserError> {
+    let tokens = lexer::lex(sql)?;
+    let mut parser = Parser::new(sql, tokens);
+    let query = parser.parse_query()?;
+    parser.consume_token(&Token::Semicolon);
+    if parser.next_token().is_some() {
+        parser_err!(parser, parser.peek_prev_pos(), "extra token after query")
+    } else {
+        Ok(query)
+    }
+}
+
 /// Parses a SQL string containing one SQL expression.
 pub fn parse_expr(sql: &str) -> Result<Expr, ParserError> {
     let tokens = lexer::lex(sql)?;
@@ -201,6 +215,7 @@ impl<'a> Parser<'a> {
                 Token::Keyword(INSERT) => Ok(self.parse_insert()?),
                 Token::Keyword(UPDATE) => Ok(self.parse_update()?),
                 Token::Keyword(ALTER) => Ok(self.parse_alter()?),
+                Token::Keyword(COMMENT) => Ok(self.parse_comment()?),
                 Token::Keyword(COPY) => Ok(self.parse_copy()?),
                 Token::Keyword(SET) => Ok(self.parse_set()?),
                 Token::Keyword(SHOW) => Ok(self.parse_show()?),
@@ -213,6 +228,9 @@ impl<'a> Parser<'a> {
                 Token::Keyword(ROLLBACK) => Ok(self.parse_rollback()?),
                 Token::Keyword(TAIL) => Ok(self.parse_tail()?),
                 Token::Keyword(EXPLAIN) => Ok(self.parse_explain()?),
+                Token::Keyword(DECLARE) => Ok(self.parse_declare()?),
+                Token::Keyword(FETCH) => Ok(self.parse_fetch()?),
+                Token::Keyword(CLOSE) => Ok(self.parse_close()?),
                 Token::Keyword(kw) => parser_err!(
                     self,
                     self.peek_prev_pos(),
@@ -1317,6 +1335,8 @@ impl<'a> Parser<'a> {
             self.parse_create_index()
         } else if self.parse_keyword(TYPE) {
             self.parse_create_type()
+        } else if self.parse_keyword(FUNCTION) {
+            self.parse_create_function()
         } else {
             self.expected(
                 self.peek_pos(),
@@ -1517,7 +1537,7 @@ impl<'a> Parser<'a> {
         let if_not_exists = self.parse_if_not_exists()?;
         let name = self.parse_object_name()?;
         self.expect_keyword(FROM)?;
-        let from = self.parse_object_name()?;
+        let from = self.parse_sink_relation()?;
         self.expect_keyword(INTO)?;
         let connector = self.parse_connector()?;
         let mut with_options = vec![];
@@ -1533,6 +1553,11 @@ impl<'a> Parser<'a> {
         } else {
             None
         };
+        let envelope = if self.parse_keyword(ENVELOPE) {
+            Some(self.parse_envelope()?)
+        } else {
+            None
+        };
         let with_snapshot = if self.parse_keyword(WITH) {
             self.expect_keyword(SNAPSHOT)?;
             true
@@ -1551,6 +1576,7 @@ impl<'a> Parser<'a> {
             connector,
             with_options,
             format,
+            envelope,
             with_snapshot,
             as_of,
             if_not_exists,
@@ -1668,14 +1694,144 @@ impl<'a> Parser<'a> {
     fn parse_create_type(&mut self) -> Result<Statement, ParserError> {
         let name = self.parse_object_name()?;
         self.expect_keyword(AS)?;
-        self.expect_keyword(MAP)?;
+        if self.parse_keyword(MAP) {
+            Ok(Statement::CreateMapType(CreateMapTypeStatement {
+                name,
+                with_options: self.parse_options()?,
+            }))
+        } else if self.parse_keyword(ENUM) {
+            self.expect_token(&Token::LParen)?;
+            let labels = if self.consume_token(&Token::RParen) {
+                vec![]
+            } else {
+                let labels = self.parse_comma_separated(Parser::parse_literal_string)?;
+                self.expect_token(&Token::RParen)?;
+                labels
+            };
+            Ok(Statement::CreateEnumType(CreateEnumTypeStatement {
+                name,
+                labels,
+            }))
+        } else if self.consume_token(&Token::LParen) {
+            let fields = self.parse_comma_separated(|parser| {
+                let name = parser.parse_identifier()?;
+                let data_type = parser.parse_data_type()?;
+                let collation = if parser.parse_keyword(COLLATE) {
+                    Some(parser.parse_object_name()?)
+                } else {
+                    None
+                };
+                Ok(ColumnDef {
+                    name,
+                    data_type,
+                    collation,
+                    options: vec![],
+                })
+            })?;
+            self.expect_token(&Token::RParen)?;
+            Ok(Statement::CreateCompositeType(
+                CreateCompositeTypeStatement { name, fields },
+            ))
+        } else {
+            self.expected(
+                self.peek_pos(),
+                "MAP, ENUM, or left parenthesis",
+                self.peek_token(),
+            )
+        }
+    }
 
-        Ok(Statement::CreateMapType(CreateMapTypeStatement {
+    fn parse_create_function(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_object_name()?;
+        self.expect_token(&Token::LParen)?;
+        let params = if self.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let params = self.parse_comma_separated(Parser::parse_function_parameter)?;
+            self.expect_token(&Token::RParen)?;
+            params
+        };
+        self.expect_keyword(RETURNS)?;
+        let returns = if self.parse_keyword(TABLE) {
+            self.expect_token(&Token::LParen)?;
+            let columns = self.parse_comma_separated(|parser| {
+                Ok(ColumnDef {
+                    name: parser.parse_identifier()?,
+                    data_type: parser.parse_data_type()?,
+                    collation: None,
+                    options: vec![],
+                })
+            })?;
+            self.expect_token(&Token::RParen)?;
+            FunctionReturnType::Table(columns)
+        } else {
+            FunctionReturnType::Scalar(self.parse_data_type()?)
+        };
+
+        // As in PostgreSQL, the LANGUAGE and AS clauses may appear in either
+        // order.
+        let mut language = false;
+        let mut body = None;
+        while !language || body.is_none() {
+            match self.expect_one_of_keywords(&[LANGUAGE, AS])? {
+                LANGUAGE if !language => {
+                    self.expect_keyword(SQL)?;
+                    language = true;
+                }
+                AS if body.is_none() => {
+                    let pos = self.peek_pos();
+                    let sql = self.parse_literal_string()?;
+                    // Errors within the body are reported at the start of
+                    // the string literal that contains it.
+                    let query = parse_function_body(&sql).map_err(|e| {
+                        self.error(pos, format!("invalid function body: {}", e.message))
+                    })?;
+                    body = Some(query);
+                }
+                kw => {
+                    return parser_err!(
+                        self,
+                        self.peek_prev_pos(),
+                        "{} specified more than once",
+                        kw.as_str()
+                    )
+                }
+            }
+        }
+
+        Ok(Statement::CreateFunction(CreateFunctionStatement {
             name,
-            with_options: self.parse_options()?,
+            params,
+            returns,
+            body: body.unwrap(),
         }))
     }
 
+    fn parse_function_parameter(&mut self) -> Result<FunctionParameter, ParserError> {
+        // A parameter is either a name followed by a type, or just a type.
+        // Since a type can itself be a bare identifier, try the named form
+        // first, and fall back to the unnamed form if the parameter does not
+        // end after the type.
+        let named = self.maybe_parse(|parser| {
+            let name = parser.parse_identifier()?;
+            let data_type = parser.parse_data_type()?;
+            match parser.peek_token() {
+                Some(Token::Comma) | Some(Token::RParen) => Ok(FunctionParameter {
+                    name: Some(name),
+                    data_type,
+                }),
+                other => parser.expected(parser.peek_pos(), "comma or right parenthesis", other),
+            }
+        });
+        match named {
+            Some(param) => Ok(param),
+            None => Ok(FunctionParameter {
+                name: None,
+                data_type: self.parse_data_type()?,
+            }),
+        }
+    }
+
     fn parse_if_exists(&mut self) -> Result<bool, ParserError> {
         if self.parse_keyword(IF) {
             self.expect_keyword(EXISTS)?;
@@ -1706,13 +1862,13 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_drop(&mut self) -> Result<Statement, ParserError> {
-        let object_type = match self
-            .parse_one_of_keywords(&[DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, TYPE])
-        {
+        let object_type = match self.parse_one_of_keywords(&[
+            DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, TYPE, FUNCTION,
+        ]) {
             Some(DATABASE) => {
                 return Ok(Statement::DropDatabase(DropDatabaseStatement {
                     if_exists: self.parse_if_exists()?,
-                    name: self.parse_identifier()?,
+                    names: self.parse_comma_separated(Parser::parse_identifier)?,
                 }));
             }
             Some(SCHEMA) => ObjectType::Schema,
@@ -1722,13 +1878,12 @@ impl<'a> Parser<'a> {
             Some(SINK) => ObjectType::Sink,
             Some(INDEX) => ObjectType::Index,
             Some(TYPE) => ObjectType::Type,
-            _ => {
-                return self.expected(
-                    self.peek_pos(),
-                    "DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, or TYPE after DROP",
-                    self.peek_token(),
-                )
-            }
+            Some(FUNCTION) => ObjectType::Function,
+            _ => return self.expected(
+                self.peek_pos(),
+                "DATABASE, SCHEMA, TABLE, VIEW, SOURCE, SINK, INDEX, TYPE, or FUNCTION after DROP",
+                self.peek_token(),
+            ),
         };
 
         let if_exists = self.parse_if_exists()?;
@@ -1779,27 +1934,8 @@ impl<'a> Parser<'a> {
         loop {
             if let Some(constraint) = self.parse_optional_table_constraint()? {
                 constraints.push(constraint);
-            } else if let Some(column_name) = self.consume_identifier() {
-                let data_type = self.parse_data_type()?;
-                let collation = if self.parse_keyword(COLLATE) {
-                    Some(self.parse_object_name()?)
-                } else {
-                    None
-                };
-                let mut options = vec![];
-                loop {
-                    match self.peek_token() {
-                        None | Some(Token::Comma) | Some(Token::RParen) => break,
-                        _ => options.push(self.parse_column_option_def()?),
-                    }
-                }
-
-                columns.push(ColumnDef {
-                    name: column_name,
-                    data_type,
-                    collation,
-                    options,
-                });
+            } else if let Some(Token::Keyword(_)) | Some(Token::Ident(_)) = self.peek_token() {
+                columns.push(self.parse_column_def()?);
             } else {
                 return self.expected(
                     self.peek_pos(),
@@ -1823,6 +1959,30 @@ impl<'a> Parser<'a> {
         Ok((columns, constraints))
     }
 
+    fn parse_column_def(&mut self) -> Result<ColumnDef, ParserError> {
+        let name = self.parse_identifier()?;
+        let data_type = self.parse_data_type()?;
+        let collation = if self.parse_keyword(COLLATE) {
+            Some(self.parse_object_name()?)
+        } else {
+            None
+        };
+        let mut options = vec![];
+        loop {
+            match self.peek_token() {
+                None | Some(Token::Comma) | Some(Token::RParen) | Some(Token::Semicolon) => break,
+                _ => options.push(self.parse_column_option_def()?),
+            }
+        }
+
+        Ok(ColumnDef {
+            name,
+            data_type,
+            collation,
+            options,
+        })
+    }
+
     fn parse_column_option_def(&mut self) -> Result<ColumnOptionDef, ParserError> {
         let name = if self.parse_keyword(CONSTRAINT) {
             Some(self.parse_identifier()?)
@@ -1987,6 +2147,34 @@ impl<'a> Parser<'a> {
         Ok(WithOption { key, value })
     }
 
+    fn parse_comment(&mut self) -> Result<Statement, ParserError> {
+        self.expect_keyword(ON)?;
+        let object =
+            match self.expect_one_of_keywords(&[COLUMN, INDEX, SINK, SOURCE, TABLE, VIEW])? {
+                COLUMN => CommentObject::Column {
+                    name: self.parse_object_name()?,
+                },
+                kw => CommentObject::Item {
+                    object_type: match kw {
+                        INDEX => ObjectType::Index,
+                        SINK => ObjectType::Sink,
+                        SOURCE => ObjectType::Source,
+                        TABLE => ObjectType::Table,
+                        VIEW => ObjectType::View,
+                        _ => unreachable!(),
+                    },
+                    name: self.parse_object_name()?,
+                },
+            };
+        self.expect_keyword(IS)?;
+        let comment = if self.parse_keyword(NULL) {
+            None
+        } else {
+            Some(self.parse_literal_string()?)
+        };
+        Ok(Statement::Comment(CommentStatement { object, comment }))
+    }
+
     fn parse_alter(&mut self) -> Result<Statement, ParserError> {
         let object_type = match self.expect_one_of_keywords(&[INDEX, SINK, SOURCE, VIEW, TABLE])? {
             INDEX => ObjectType::Index,
@@ -2030,6 +2218,47 @@ impl<'a> Parser<'a> {
             }
         }
 
+        // We support `ALTER SOURCE ... SET`
+        if object_type == ObjectType::Source && self.parse_keyword(SET) {
+            let options = self.parse_options()?;
+            return Ok(Statement::AlterSource(AlterSourceStatement {
+                name,
+                if_exists,
+                options,
+            }));
+        }
+
+        // We support `ALTER TABLE ... {ADD, DROP} COLUMN`
+        if object_type == ObjectType::Table {
+            let action = match self.parse_one_of_keywords(&[ADD, DROP]) {
+                Some(ADD) => {
+                    let _ = self.parse_keyword(COLUMN);
+                    let if_not_exists = self.parse_if_not_exists()?;
+                    let column_def = self.parse_column_def()?;
+                    Some(AlterTableAction::AddColumn {
+                        if_not_exists,
+                        column_def,
+                    })
+                }
+                Some(DROP) => {
+                    let _ = self.parse_keyword(COLUMN);
+                    let if_exists = self.parse_if_exists()?;
+                    let name = self.parse_identifier()?;
+                    Some(AlterTableAction::DropColumn { if_exists, name })
+                }
+                Some(_) => unreachable!(),
+                None => None,
+            };
+
+            if let Some(action) = action {
+                return Ok(Statement::AlterTable(AlterTableStatement {
+                    name,
+                    if_exists,
+                    action,
+                }));
+            }
+        }
+
         self.expect_keywords(&[RENAME, TO])?;
         let to_item_name = self.parse_identifier()?;
 
@@ -2256,7 +2485,13 @@ impl<'a> Parser<'a> {
                     Some(Token::Keyword(kw)),
                 )?,
             },
-            Some(Token::Ident(id)) => DataType::Custom(id),
+            Some(Token::Ident(id)) => {
+                let mut idents = vec![Ident::new(id)];
+                while self.consume_token(&Token::Dot) {
+                    idents.push(self.parse_identifier()?);
+                }
+                DataType::Custom(ObjectName(idents))
+            }
             other => self.expected(self.peek_prev_pos(), "a data type name", other)?,
         };
         loop {
@@ -2902,6 +3137,8 @@ impl<'a> Parser<'a> {
             Ok(Statement::ShowCreateIndex(ShowCreateIndexStatement {
                 index_name: self.parse_object_name()?,
             }))
+        } else if self.parse_keywords(&[CREATE, ALL]) {
+            Ok(Statement::ShowCreateAll(ShowCreateAllStatement))
         } else {
             let variable = if self.parse_keywords(&[TRANSACTION, ISOLATION, LEVEL]) {
                 Ident::new("transaction_isolation")
@@ -3303,16 +3540,96 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_tail(&mut self) -> Result<Statement, ParserError> {
-        let name = self.parse_object_name()?;
-        let options = self.parse_opt_with_options()?;
+        let relation = self.parse_sink_relation()?;
+        let mut options = vec![];
+        let mut envelope = None;
+        if self.parse_keyword(WITH) {
+            self.expect_token(&Token::LParen)?;
+            self.parse_comma_separated(|parser| {
+                if parser.parse_keyword(ENVELOPE) {
+                    if envelope.is_some() {
+                        return parser_err!(parser, parser.peek_prev_pos(), "duplicate ENVELOPE");
+                    }
+                    envelope = Some(parser.parse_tail_envelope()?);
+                } else {
+                    options.push(parser.parse_with_option(true)?);
+                }
+                Ok(())
+            })?;
+            self.expect_token(&Token::RParen)?;
+        }
         let as_of = self.parse_optional_as_of()?;
         Ok(Statement::Tail(TailStatement {
-            name,
+            relation,
             options,
+            envelope,
             as_of,
         }))
     }
 
+    /// Parses the envelope of a `TAIL` statement, assuming that the
+    /// `ENVELOPE` token has already been consumed.
+    fn parse_tail_envelope(&mut self) -> Result<TailEnvelope, ParserError> {
+        self.expect_keyword(UPSERT)?;
+        self.expect_token(&Token::LParen)?;
+        self.expect_keyword(KEY)?;
+        let key = self.parse_parenthesized_column_list(Mandatory)?;
+        self.expect_token(&Token::RParen)?;
+        Ok(TailEnvelope::Upsert { key })
+    }
+
+    /// Parses the relation that a `CREATE SINK` or `TAIL` statement reads
+    /// from: either an object name or a parenthesized query.
+    fn parse_sink_relation(&mut self) -> Result<SinkRelation, ParserError> {
+        if self.consume_token(&Token::LParen) {
+            let query = self.parse_query()?;
+            self.expect_token(&Token::RParen)?;
+            Ok(SinkRelation::Query(query))
+        } else {
+            Ok(SinkRelation::Name(self.parse_object_name()?))
+        }
+    }
+
+    /// Parses a `DECLARE` statement, assuming that the `DECLARE` token
+    /// has already been consumed.
+    fn parse_declare(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_identifier()?;
+        self.expect_keywords(&[CURSOR, FOR])?;
+        let stmt = self.parse_statement()?;
+        Ok(Statement::Declare(DeclareStatement {
+            name,
+            stmt: Box::new(stmt),
+        }))
+    }
+
+    /// Parses a `FETCH` statement, assuming that the `FETCH` token
+    /// has already been consumed.
+    fn parse_fetch(&mut self) -> Result<Statement, ParserError> {
+        let _ = self.parse_keyword(FORWARD);
+        let count = if let Some(count) = self.maybe_parse(Parser::parse_literal_uint) {
+            Some(FetchDirection::ForwardCount(count))
+        } else if self.parse_keyword(ALL) {
+            Some(FetchDirection::ForwardAll)
+        } else {
+            None
+        };
+        let _ = self.parse_one_of_keywords(&[FROM, IN]);
+        let name = self.parse_identifier()?;
+        let options = self.parse_opt_with_options()?;
+        Ok(Statement::Fetch(FetchStatement {
+            name,
+            count,
+            options,
+        }))
+    }
+
+    /// Parses a `CLOSE` statement, assuming that the `CLOSE` token
+    /// has already been consumed.
+    fn parse_close(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_identifier()?;
+        Ok(Statement::Close(CloseStatement { name }))
+    }
+
     /// Parse an `EXPLAIN` statement, assuming that the `EXPLAIN` token
     /// has already been consumed.
     fn parse_explain(&mut self) -> Result<Statement, ParserError> {
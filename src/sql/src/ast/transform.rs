@@ -14,8 +14,9 @@ use std::collections::{HashMap, HashSet};
 use crate::ast::visit::{self, Visit};
 use crate::ast::visit_mut::{self, VisitMut};
 use crate::ast::{
-    CreateIndexStatement, CreateSinkStatement, CreateSourceStatement, CreateTableStatement,
-    CreateViewStatement, Expr, Ident, ObjectName, Query, Statement,
+    CreateFunctionStatement, CreateIndexStatement, CreateSinkStatement, CreateSourceStatement,
+    CreateTableStatement, CreateViewStatement, Expr, Ident, ObjectName, Query, SinkRelation,
+    Statement,
 };
 use crate::names::FullName;
 
@@ -67,10 +68,12 @@ pub fn create_stmt_rename_refs(
         Statement::CreateIndex(CreateIndexStatement { on_name, .. }) => {
             maybe_update_object_name(on_name);
         }
-        Statement::CreateSink(CreateSinkStatement { from, .. }) => {
-            maybe_update_object_name(from);
-        }
-        Statement::CreateView(CreateViewStatement { query, .. }) => {
+        Statement::CreateSink(CreateSinkStatement { from, .. }) => match from {
+            SinkRelation::Name(from) => maybe_update_object_name(from),
+            SinkRelation::Query(query) => rewrite_query(from_name, to_item_name, query)?,
+        },
+        Statement::CreateView(CreateViewStatement { query, .. })
+        | Statement::CreateFunction(CreateFunctionStatement { body: query, .. }) => {
             rewrite_query(from_name, to_item_name, query)?;
         }
         Statement::CreateSource(_) | Statement::CreateTable(_) => {}
@@ -17,11 +17,11 @@ use std::path::Path;
 use std::time::SystemTime;
 
 use expr::{GlobalId, ScalarExpr};
-use repr::RelationDesc;
+use repr::{ColumnName, RelationDesc, ScalarType};
 use uuid::Uuid;
 
 use crate::names::{DatabaseSpecifier, FullName, PartialName, SchemaSpecifier};
-use crate::plan::PlanContext;
+use crate::plan::{FunctionReturns, PlanContext};
 
 /// A catalog keeps track of SQL objects available to the planner.
 ///
@@ -192,6 +192,14 @@ pub trait CatalogItem {
     /// Returns the index details associated with the catalog item, if the
     /// catalog item is an index.
     fn index_details(&self) -> Option<(&[ScalarExpr], GlobalId)>;
+
+    /// Returns the scalar type that the catalog item describes, if the catalog
+    /// item is a user-defined type that can be used to type a column or cast.
+    fn scalar_type(&self) -> Option<ScalarType>;
+
+    /// Returns the parameters and return type of the catalog item, if the
+    /// catalog item is a user-defined function.
+    fn function_details(&self) -> Option<(&[(Option<ColumnName>, ScalarType)], &FunctionReturns)>;
 }
 
 /// A type in a [`Catalog`].
@@ -212,6 +220,8 @@ pub enum CatalogItemType {
     Index,
     /// A type.
     Type,
+    /// A function.
+    Func,
 }
 
 impl fmt::Display for CatalogItemType {
@@ -223,6 +233,7 @@ impl fmt::Display for CatalogItemType {
             CatalogItemType::View => f.write_str("view"),
             CatalogItemType::Index => f.write_str("index"),
             CatalogItemType::Type => f.write_str("type"),
+            CatalogItemType::Func => f.write_str("function"),
         }
     }
 }
@@ -242,6 +253,8 @@ pub enum CatalogError {
     InvalidIndexDependency(String),
     /// Invalid attempt to depend on a type.
     InvalidTypeDependency(String),
+    /// Invalid attempt to depend on a function as if it were a relation.
+    InvalidFunctionDependency(String),
 }
 
 impl fmt::Display for CatalogError {
@@ -265,6 +278,11 @@ impl fmt::Display for CatalogError {
                 "catalog item '{}' is a type and so cannot be depended upon",
                 name
             ),
+            Self::InvalidFunctionDependency(name) => write!(
+                f,
+                "catalog item '{}' is a function and so cannot be depended upon",
+                name
+            ),
         }
     }
 }
@@ -311,8 +329,8 @@ impl Catalog for DummyCatalog {
         unimplemented!();
     }
 
-    fn resolve_item(&self, _: &PartialName) -> Result<FullName, CatalogError> {
-        unimplemented!();
+    fn resolve_item(&self, name: &PartialName) -> Result<FullName, CatalogError> {
+        Err(CatalogError::UnknownItem(name.to_string()))
     }
 
     fn list_items<'a>(
@@ -107,6 +107,41 @@ fn extract(
     Ok(out)
 }
 
+// The Kafka cluster configurations that users can supply.
+fn configs() -> Vec<Config> {
+    vec![
+        Config::string("client_id"),
+        Config::new(
+            "statistics_interval_ms",
+            // The range of values comes from `statistics.interval.ms` in
+            // https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md
+            ValType::Number(0, 86_400_000),
+        ),
+        Config::new(
+            "topic_metadata_refresh_interval_ms",
+            // The range of values comes from `topic.metadata.refresh.interval.ms` in
+            // https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md
+            ValType::Number(0, 3_600_000),
+        ),
+        Config::string("security_protocol"),
+        Config::path("sasl_kerberos_keytab"),
+        Config::string("sasl_username"),
+        Config::string("sasl_password"),
+        Config::string("sasl_kerberos_kinit_cmd"),
+        Config::string("sasl_kerberos_min_time_before_relogin"),
+        Config::string("sasl_kerberos_principal"),
+        Config::string("sasl_kerberos_service_name"),
+        // For historical reasons, we allow `sasl_mechanisms` to be lowercase or
+        // mixed case, while librdkafka requires all uppercase (e.g., `PLAIN`,
+        // not `plain`).
+        Config::string("sasl_mechanisms").transform(|s| s.to_uppercase()),
+        Config::path("ssl_ca_location"),
+        Config::path("ssl_certificate_location"),
+        Config::path("ssl_key_location"),
+        Config::string("ssl_key_password"),
+    ]
+}
+
 /// Parse the `with_options` from a `CREATE SOURCE` statement to determine
 /// user-supplied config options, e.g. security options.
 ///
@@ -119,40 +154,13 @@ fn extract(
 pub fn extract_config(
     with_options: &HashMap<String, Value>,
 ) -> Result<HashMap<String, String>, anyhow::Error> {
-    extract(
-        with_options,
-        &[
-            Config::string("client_id"),
-            Config::new(
-                "statistics_interval_ms",
-                // The range of values comes from `statistics.interval.ms` in
-                // https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md
-                ValType::Number(0, 86_400_000),
-            ),
-            Config::new(
-                "topic_metadata_refresh_interval_ms",
-                // The range of values comes from `topic.metadata.refresh.interval.ms` in
-                // https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md
-                ValType::Number(0, 3_600_000),
-            ),
-            Config::string("security_protocol"),
-            Config::path("sasl_kerberos_keytab"),
-            Config::string("sasl_username"),
-            Config::string("sasl_password"),
-            Config::string("sasl_kerberos_kinit_cmd"),
-            Config::string("sasl_kerberos_min_time_before_relogin"),
-            Config::string("sasl_kerberos_principal"),
-            Config::string("sasl_kerberos_service_name"),
-            // For historical reasons, we allow `sasl_mechanisms` to be lowercase or
-            // mixed case, while librdkafka requires all uppercase (e.g., `PLAIN`,
-            // not `plain`).
-            Config::string("sasl_mechanisms").transform(|s| s.to_uppercase()),
-            Config::path("ssl_ca_location"),
-            Config::path("ssl_certificate_location"),
-            Config::path("ssl_key_location"),
-            Config::string("ssl_key_password"),
-        ],
-    )
+    extract(with_options, &configs())
+}
+
+/// Reports whether `name` is a `WITH` option that is passed through to the
+/// Kafka client, as opposed to one that Materialize interprets itself.
+pub fn is_config_option(name: &str) -> bool {
+    configs().iter().any(|config| config.name == name)
 }
 
 /// Create a new `rdkafka::ClientConfig` with the provided
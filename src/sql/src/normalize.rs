@@ -13,13 +13,16 @@ use repr::ColumnName;
 use sql_parser::ast::display::AstDisplay;
 use sql_parser::ast::visit_mut::{self, VisitMut};
 use sql_parser::ast::{
+    CreateCompositeTypeStatement, CreateEnumTypeStatement, CreateFunctionStatement,
     CreateIndexStatement, CreateMapTypeStatement, CreateSinkStatement, CreateSourceStatement,
-    CreateTableStatement, CreateViewStatement, Function, FunctionArgs, Ident, IfExistsBehavior,
-    ObjectName, SqlOption, Statement, TableFactor, Value,
+    CreateTableStatement, CreateViewStatement, DataType, Function, FunctionArgs,
+    FunctionReturnType, Ident, IfExistsBehavior, ObjectName, SinkRelation, SqlOption, Statement,
+    TableFactor, Value,
 };
 
 use crate::names::{DatabaseSpecifier, FullName, PartialName};
 use crate::plan::error::PlanError;
+use crate::plan::func;
 use crate::plan::statement::StatementContext;
 
 pub fn ident(ident: Ident) -> String {
@@ -104,10 +107,23 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
         err: Option<PlanError>,
     }
 
+    impl<'a> QueryNormalizer<'a> {
+        /// Fully qualifies `name` if it refers to a user-defined function.
+        /// Builtin function names are left as written.
+        fn normalize_function_name(&mut self, name: &mut ObjectName) {
+            if let Ok(partial_name) = object_name(name.clone()) {
+                if let Some(item) = func::resolve_user_func_item(self.scx, &partial_name) {
+                    *name = unresolve(item.name().clone());
+                }
+            }
+        }
+    }
+
     impl<'a, 'ast> VisitMut<'ast> for QueryNormalizer<'a> {
         fn visit_function_mut(&mut self, func: &'ast mut Function) {
-            // Don't visit the function name, because function names are not
-            // (yet) object names we can resolve.
+            // Don't visit the function name as an object name, because
+            // builtin function names are not object names we can resolve.
+            self.normalize_function_name(&mut func.name);
             match &mut func.args {
                 FunctionArgs::Star => (),
                 FunctionArgs::Args(args) => {
@@ -129,11 +145,8 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
                         self.visit_table_alias_mut(alias);
                     }
                 }
-                TableFactor::Function {
-                    name: _,
-                    args,
-                    alias,
-                } => {
+                TableFactor::Function { name, args, alias } => {
+                    self.normalize_function_name(name);
                     match args {
                         FunctionArgs::Star => (),
                         FunctionArgs::Args(args) => {
@@ -159,6 +172,18 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
                 Err(e) => self.err = Some(e),
             };
         }
+
+        fn visit_data_type_mut(&mut self, data_type: &'ast mut DataType) {
+            match data_type {
+                DataType::Custom(name) => {
+                    // Leave types that do not exist for the planner to report.
+                    if let Ok(full_name) = self.scx.resolve_item(name.clone()) {
+                        *name = unresolve(full_name);
+                    }
+                }
+                _ => visit_mut::visit_data_type_mut(self, data_type),
+            }
+        }
     }
 
     // Think very hard before changing any of the branches in this match
@@ -189,12 +214,16 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
 
         Statement::CreateTable(CreateTableStatement {
             name,
-            columns: _,
+            columns,
             constraints: _,
             with_options: _,
             if_not_exists,
         }) => {
             *name = allocate_name(name)?;
+            let mut normalizer = QueryNormalizer { scx, err: None };
+            for column in columns {
+                normalizer.visit_data_type_mut(&mut column.data_type);
+            }
             *if_not_exists = false;
         }
 
@@ -204,12 +233,22 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
             connector: _,
             with_options: _,
             format: _,
+            envelope: _,
             with_snapshot: _,
             as_of: _,
             if_not_exists,
         }) => {
             *name = allocate_name(name)?;
-            *from = resolve_item(from)?;
+            match from {
+                SinkRelation::Name(from) => *from = resolve_item(from)?,
+                SinkRelation::Query(query) => {
+                    let mut normalizer = QueryNormalizer { scx, err: None };
+                    normalizer.visit_query_mut(query);
+                    if let Some(err) = normalizer.err {
+                        return Err(err);
+                    }
+                }
+            }
             *if_not_exists = false;
         }
 
@@ -278,6 +317,43 @@ pub fn create_statement(scx: &StatementContext, mut stmt: Statement) -> Result<S
             }
         }
 
+        Statement::CreateEnumType(CreateEnumTypeStatement { name, labels: _ }) => {
+            *name = allocate_name(name)?;
+        }
+
+        Statement::CreateCompositeType(CreateCompositeTypeStatement { name, fields }) => {
+            *name = allocate_name(name)?;
+            let mut normalizer = QueryNormalizer { scx, err: None };
+            for field in fields {
+                normalizer.visit_data_type_mut(&mut field.data_type);
+            }
+        }
+
+        Statement::CreateFunction(CreateFunctionStatement {
+            name,
+            params,
+            returns,
+            body,
+        }) => {
+            *name = allocate_name(name)?;
+            let mut normalizer = QueryNormalizer { scx, err: None };
+            for param in params {
+                normalizer.visit_data_type_mut(&mut param.data_type);
+            }
+            match returns {
+                FunctionReturnType::Scalar(data_type) => normalizer.visit_data_type_mut(data_type),
+                FunctionReturnType::Table(columns) => {
+                    for column in columns {
+                        normalizer.visit_data_type_mut(&mut column.data_type);
+                    }
+                }
+            }
+            normalizer.visit_query_mut(body);
+            if let Some(err) = normalizer.err {
+                return Err(err);
+            }
+        }
+
         _ => unreachable!(),
     }
 
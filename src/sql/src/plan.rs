@@ -32,10 +32,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use ::expr::{GlobalId, RowSetFinishing};
-use dataflow_types::{SinkConnectorBuilder, SourceConnector};
+use dataflow_types::{SinkConnectorBuilder, SinkEnvelope, SourceConnector};
 use repr::{ColumnName, RelationDesc, Row, ScalarType, Timestamp};
 
-use crate::ast::{ExplainOptions, ExplainStage, ObjectType, Statement};
+use crate::ast::{ExplainOptions, ExplainStage, FetchDirection, ObjectType, Statement};
 use crate::catalog::Catalog;
 use crate::names::{DatabaseSpecifier, FullName};
 
@@ -54,7 +54,7 @@ pub(crate) mod typeconv;
 pub use self::expr::RelationExpr;
 pub use error::PlanError;
 // This is used by sqllogictest to turn SQL values into `Datum`s.
-pub use query::scalar_type_from_sql;
+pub use query::{scalar_type_from_sql, user_defined_dependencies};
 pub use statement::{StatementContext, StatementDesc};
 
 /// Instructions for executing a SQL query.
@@ -105,14 +105,17 @@ pub enum Plan {
         name: FullName,
         typ: Type,
     },
+    CreateFunction {
+        name: FullName,
+        function: Function,
+    },
     DiscardTemp,
     DiscardAll,
-    DropDatabase {
-        name: String,
+    DropDatabases {
+        names: Vec<String>,
     },
-    DropSchema {
-        database_name: DatabaseSpecifier,
-        schema_name: String,
+    DropSchemas {
+        schemas: Vec<(DatabaseSpecifier, String)>,
     },
     DropItems {
         items: Vec<GlobalId>,
@@ -120,6 +123,7 @@ pub enum Plan {
     },
     EmptyQuery,
     ShowAllVariables,
+    ShowCreateAll,
     ShowVariable(String),
     SetVariable {
         name: String,
@@ -135,12 +139,14 @@ pub enum Plan {
         copy_to: Option<CopyFormat>,
     },
     Tail {
-        id: GlobalId,
+        from: SinkFrom,
         with_snapshot: bool,
         ts: Option<Timestamp>,
         copy_to: Option<CopyFormat>,
         emit_progress: bool,
         object_columns: usize,
+        envelope: SinkEnvelope,
+        key_indices: Option<Vec<usize>>,
     },
     SendRows(Vec<Row>),
     ExplainPlan {
@@ -166,12 +172,37 @@ pub enum Plan {
         object_type: ObjectType,
     },
     AlterIndexLogicalCompactionWindow(Option<AlterIndexLogicalCompactionWindow>),
+    AlterTable(Option<AlterTable>),
+    AlterSource(Option<AlterSource>),
+    Comment {
+        id: GlobalId,
+        /// The index of the commented column, if the comment is on a column
+        /// rather than on the item itself.
+        column: Option<usize>,
+        comment: Option<String>,
+    },
+    Declare {
+        name: String,
+        stmt: Statement,
+        params: Params,
+    },
+    Fetch {
+        name: String,
+        count: Option<FetchDirection>,
+        timeout: Option<Duration>,
+    },
+    Close {
+        name: String,
+    },
 }
 
 #[derive(Clone, Debug)]
 pub struct Table {
     pub create_sql: String,
     pub desc: RelationDesc,
+    /// The user-defined types and functions that the table's columns refer
+    /// to.
+    pub depends_on: Vec<GlobalId>,
 }
 
 #[derive(Clone, Debug)]
@@ -184,16 +215,31 @@ pub struct Source {
 #[derive(Clone, Debug)]
 pub struct Sink {
     pub create_sql: String,
-    pub from: GlobalId,
+    pub from: SinkFrom,
     pub connector_builder: SinkConnectorBuilder,
 }
 
+/// The relation that a sink or tail reads from.
+#[derive(Clone, Debug)]
+pub enum SinkFrom {
+    /// A named source, table, or view.
+    Id(GlobalId),
+    /// An anonymous query, which is built as part of the sink's dataflow.
+    Query {
+        expr: ::expr::RelationExpr,
+        desc: RelationDesc,
+    },
+}
+
 #[derive(Clone, Debug)]
 pub struct View {
     pub create_sql: String,
     pub expr: ::expr::RelationExpr,
     pub column_names: Vec<Option<ColumnName>>,
     pub temporary: bool,
+    /// The user-defined types and functions that the view's definition refers
+    /// to.
+    pub depends_on: Vec<GlobalId>,
 }
 
 #[derive(Clone, Debug)]
@@ -215,6 +261,35 @@ pub enum TypeInner {
         key_id: GlobalId,
         value_id: GlobalId,
     },
+    Enum {
+        labels: Vec<String>,
+    },
+    Record {
+        fields: Vec<(ColumnName, ScalarType)>,
+        /// The user-defined types that the fields refer to.
+        depends_on: Vec<GlobalId>,
+    },
+}
+
+/// A user-defined function.
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub create_sql: String,
+    /// The names, if any, and types of the function's parameters.
+    pub params: Vec<(Option<ColumnName>, ScalarType)>,
+    pub returns: FunctionReturns,
+    /// The relations, user-defined types, and user-defined functions that the
+    /// function refers to.
+    pub depends_on: Vec<GlobalId>,
+}
+
+/// What a user-defined function returns.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum FunctionReturns {
+    /// A single value of the given type.
+    Scalar(ScalarType),
+    /// A relation with the given columns.
+    Table(RelationDesc),
 }
 
 /// Specifies when a `Peek` should occur.
@@ -247,6 +322,37 @@ pub struct AlterIndexLogicalCompactionWindow {
     pub logical_compaction_window: LogicalCompactionWindow,
 }
 
+/// A change to the columns of a table.
+#[derive(Debug)]
+pub struct AlterTable {
+    pub id: GlobalId,
+    /// The new definition of the table.
+    pub table: Table,
+    /// How the existing rows of the table are to be rewritten.
+    pub migration: TableMigration,
+}
+
+/// A change to the options of a source.
+#[derive(Debug)]
+pub struct AlterSource {
+    pub id: GlobalId,
+    /// The new definition of the source.
+    pub source: Source,
+    /// Whether the change only affects how the source is read, rather than
+    /// what it contains, so that running instances of the source can be
+    /// restarted with the new definition in place.
+    pub restart_only: bool,
+}
+
+/// Specifies how the existing rows of an altered table are rewritten.
+#[derive(Debug)]
+pub enum TableMigration {
+    /// Append a column, whose value in each existing row is `default`.
+    AddColumn { default: ::expr::ScalarExpr },
+    /// Remove the column at index `column`.
+    DropColumn { column: usize },
+}
+
 /// Specifies what value the `logical_compaction_window` parameter should be set to.
 #[derive(Debug, PartialEq)]
 pub enum LogicalCompactionWindow {
@@ -302,6 +408,24 @@ pub fn plan(
     statement::handle_statement(pcx, catalog, stmt, params)
 }
 
+/// Produces a [`Plan`] from the purified statement `stmt` that can be reused
+/// with different values for the statement's parameters, whose types are
+/// `param_types`.
+///
+/// Parameters are planned as calls to [`::expr::NullaryFunc::Parameter`],
+/// which must be replaced with the values of the parameters before the plan
+/// is executed. Fails if the plan of `stmt` cannot be reused, as when `stmt`
+/// is not a `SELECT` statement or its plan depends on the time at which it is
+/// planned.
+pub fn plan_reusable(
+    pcx: &PlanContext,
+    catalog: &dyn Catalog,
+    stmt: Statement,
+    param_types: &[ScalarType],
+) -> Result<Plan, anyhow::Error> {
+    statement::handle_reusable_statement(pcx, catalog, stmt, param_types)
+}
+
 /// Creates a description of the purified statement `stmt`.
 ///
 /// See the documentation of [`StatementDesc`] for details.
@@ -723,51 +723,79 @@ impl RelationExpr {
     /// Replaces any parameter references in the expression with the
     /// corresponding datum from `params`.
     pub fn bind_parameters(&mut self, params: &Params) -> Result<(), anyhow::Error> {
+        self.replace_parameters(&mut |n| bind_parameter(params, n))
+    }
+
+    /// Replaces any parameter references in the expression with placeholders
+    /// for parameters of the types in `param_types`, so that the expression
+    /// can be optimized before the values of its parameters are known.
+    ///
+    /// The placeholders are calls to [`NullaryFunc::Parameter`], which must be
+    /// replaced with the values of the parameters before evaluation.
+    pub fn bind_parameter_placeholders(
+        &mut self,
+        param_types: &[ScalarType],
+    ) -> Result<(), anyhow::Error> {
+        self.replace_parameters(&mut |n| match param_types.get(n - 1) {
+            None => bail!("there is no parameter ${}", n),
+            Some(scalar_type) => Ok(ScalarExpr::CallNullary(NullaryFunc::Parameter(
+                n,
+                scalar_type.clone(),
+            ))),
+        })
+    }
+
+    /// Replaces any parameter references in the expression with the
+    /// expression that `f` produces for the parameter's number.
+    fn replace_parameters<F>(&mut self, f: &mut F) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(usize) -> Result<ScalarExpr, anyhow::Error>,
+    {
         match self {
             RelationExpr::Join {
                 on, left, right, ..
             } => {
-                on.bind_parameters(params)?;
-                left.bind_parameters(params)?;
-                right.bind_parameters(params)
+                on.replace_parameters(f)?;
+                left.replace_parameters(f)?;
+                right.replace_parameters(f)
             }
             RelationExpr::Map { scalars, input } => {
                 for scalar in scalars {
-                    scalar.bind_parameters(params)?;
+                    scalar.replace_parameters(f)?;
                 }
-                input.bind_parameters(params)
+                input.replace_parameters(f)
             }
             RelationExpr::CallTable { exprs, .. } => {
                 for expr in exprs {
-                    expr.bind_parameters(params)?;
+                    expr.replace_parameters(f)?;
                 }
                 Ok(())
             }
             RelationExpr::Filter { predicates, input } => {
                 for predicate in predicates {
-                    predicate.bind_parameters(params)?;
+                    predicate.replace_parameters(f)?;
                 }
-                input.bind_parameters(params)
+                input.replace_parameters(f)
             }
             RelationExpr::Reduce {
                 aggregates, input, ..
             } => {
                 for aggregate in aggregates {
-                    aggregate.bind_parameters(params)?;
+                    aggregate.replace_parameters(f)?;
                 }
-                input.bind_parameters(params)
+                input.replace_parameters(f)
             }
             RelationExpr::Union { base, inputs } => {
                 for input in inputs {
-                    input.bind_parameters(params)?;
+                    input.replace_parameters(f)?;
                 }
-                base.bind_parameters(params)
+                base.replace_parameters(f)
             }
             RelationExpr::Project { input, .. }
             | RelationExpr::Distinct { input, .. }
             | RelationExpr::TopK { input, .. }
             | RelationExpr::Negate { input, .. }
-            | RelationExpr::Threshold { input, .. } => input.bind_parameters(params),
+            | RelationExpr::Threshold { input, .. } => input.replace_parameters(f),
             RelationExpr::Constant { .. } | RelationExpr::Get { .. } => Ok(()),
         }
     }
@@ -861,45 +889,40 @@ impl RelationExpr {
 }
 
 impl ScalarExpr {
-    /// Replaces any parameter references in the expression with the
-    /// corresponding datum in `params`.
-    pub fn bind_parameters(&mut self, params: &Params) -> Result<(), anyhow::Error> {
+    /// See the documentation for [`RelationExpr::replace_parameters`].
+    fn replace_parameters<F>(&mut self, f: &mut F) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(usize) -> Result<ScalarExpr, anyhow::Error>,
+    {
         match self {
             ScalarExpr::Literal(_, _) | ScalarExpr::Column(_) | ScalarExpr::CallNullary(_) => {
                 Ok(())
             }
             ScalarExpr::Parameter(n) => {
-                let datum = match params.datums.iter().nth(*n - 1) {
-                    None => bail!("there is no parameter ${}", n),
-                    Some(datum) => datum,
-                };
-                let scalar_type = &params.types[*n - 1];
-                let row = Row::pack(&[datum]);
-                let column_type = scalar_type.clone().nullable(datum.is_null());
-                *self = ScalarExpr::Literal(row, column_type);
+                *self = f(*n)?;
                 Ok(())
             }
-            ScalarExpr::CallUnary { expr, .. } => expr.bind_parameters(params),
+            ScalarExpr::CallUnary { expr, .. } => expr.replace_parameters(f),
             ScalarExpr::CallBinary { expr1, expr2, .. } => {
-                expr1.bind_parameters(params)?;
-                expr2.bind_parameters(params)
+                expr1.replace_parameters(f)?;
+                expr2.replace_parameters(f)
             }
             ScalarExpr::CallVariadic { exprs, .. } => {
                 for expr in exprs {
-                    expr.bind_parameters(params)?;
+                    expr.replace_parameters(f)?;
                 }
                 Ok(())
             }
             ScalarExpr::If { cond, then, els } => {
-                cond.bind_parameters(params)?;
-                then.bind_parameters(params)?;
-                els.bind_parameters(params)
+                cond.replace_parameters(f)?;
+                then.replace_parameters(f)?;
+                els.replace_parameters(f)
             }
-            ScalarExpr::Exists(expr) | ScalarExpr::Select(expr) => expr.bind_parameters(params),
+            ScalarExpr::Exists(expr) | ScalarExpr::Select(expr) => expr.replace_parameters(f),
         }
     }
 
-    // Like [`ScalarExpr::bind_parameters`]`, except that parameters are
+    // Like [`RelationExpr::bind_parameters`]`, except that parameters are
     // replaced with the corresponding expression fragment from `params` rather
     // than a datum.
     ///
@@ -1135,6 +1158,18 @@ impl ScalarExpr {
     }
 }
 
+/// Returns a literal with the value of parameter `n` in `params`.
+fn bind_parameter(params: &Params, n: usize) -> Result<ScalarExpr, anyhow::Error> {
+    let datum = match params.datums.iter().nth(n - 1) {
+        None => bail!("there is no parameter ${}", n),
+        Some(datum) => datum,
+    };
+    let scalar_type = &params.types[n - 1];
+    let row = Row::pack(&[datum]);
+    let column_type = scalar_type.clone().nullable(datum.is_null());
+    Ok(ScalarExpr::Literal(row, column_type))
+}
+
 impl AbstractExpr for ScalarExpr {
     type Type = ColumnType;
 
@@ -1184,10 +1219,12 @@ impl AbstractExpr for ScalarExpr {
 }
 
 impl AggregateExpr {
-    /// Replaces any parameter references in the expression with the
-    /// corresponding datum from `parameters`.
-    pub fn bind_parameters(&mut self, params: &Params) -> Result<(), anyhow::Error> {
-        self.expr.bind_parameters(params)
+    /// See the documentation for [`RelationExpr::replace_parameters`].
+    fn replace_parameters<F>(&mut self, f: &mut F) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(usize) -> Result<ScalarExpr, anyhow::Error>,
+    {
+        self.expr.replace_parameters(f)
     }
 
     pub fn typ(
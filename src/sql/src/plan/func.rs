@@ -22,17 +22,17 @@ use lazy_static::lazy_static;
 
 use ore::collections::CollectionExt;
 use repr::{ColumnName, Datum, RelationType, ScalarType};
-use sql_parser::ast::{Expr, Ident, ObjectName};
+use sql_parser::ast::{Expr, Ident, ObjectName, Statement};
 
 use super::expr::{
-    AggregateFunc, BinaryFunc, CoercibleScalarExpr, NullaryFunc, ScalarExpr, TableFunc, UnaryFunc,
-    VariadicFunc,
+    AggregateFunc, BinaryFunc, CoercibleScalarExpr, NullaryFunc, RelationExpr, ScalarExpr,
+    TableFunc, UnaryFunc, VariadicFunc,
 };
 use super::query::{self, ExprContext, QueryContext, QueryLifetime};
 use super::scope::Scope;
 use super::typeconv::{self, rescale_decimal, CastContext};
-use super::StatementContext;
-use crate::catalog::CatalogItemType;
+use super::{FunctionReturns, StatementContext};
+use crate::catalog::{CatalogItem, CatalogItemType};
 use crate::names::PartialName;
 
 /// A specifier for a function or an operator.
@@ -65,6 +65,7 @@ pub enum TypeCategory {
     Array,
     Bool,
     DateTime,
+    Enum,
     List,
     Numeric,
     Pseudo,
@@ -104,6 +105,7 @@ impl TypeCategory {
             ScalarType::List(..) => Self::List,
             ScalarType::String => Self::String,
             ScalarType::Record { .. } => Self::Pseudo,
+            ScalarType::Enum { .. } => Self::Enum,
         }
     }
 
@@ -111,6 +113,7 @@ impl TypeCategory {
         match param {
             ParamType::Any
             | ParamType::ArrayAny
+            | ParamType::EnumAny
             | ParamType::ListAny
             | ParamType::ListElementAny
             | ParamType::NonVecAny => Self::Pseudo,
@@ -127,7 +130,7 @@ impl TypeCategory {
     /// ```
     fn preferred_type(&self) -> Option<ScalarType> {
         match self {
-            Self::Array | Self::List | Self::Pseudo | Self::UserDefined => None,
+            Self::Array | Self::Enum | Self::List | Self::Pseudo | Self::UserDefined => None,
             Self::Bool => Some(ScalarType::Bool),
             Self::DateTime => Some(ScalarType::TimestampTz),
             Self::Numeric => Some(ScalarType::Float64),
@@ -373,6 +376,7 @@ impl ParamList {
     /// - All arguments passed to `ListAny` must be `ScalarType::List`s with the
     ///   same types of elements. All arguments passed to `ListElementAny` must
     ///   also be of these elements' type.
+    /// - All arguments passed to `EnumAny` must be of the same enum type.
     ///
     /// # Errors
     /// - If `typs` is inconsistent with these constraints.
@@ -410,9 +414,9 @@ impl ParamList {
                 | (ParamType::ArrayAny, Some(ScalarType::Array(typ))) => {
                     set_or_check_constrained_type(typ)?
                 }
-                (ParamType::ListElementAny, Some(typ)) | (ParamType::NonVecAny, Some(typ)) => {
-                    set_or_check_constrained_type(typ)?
-                }
+                (ParamType::ListElementAny, Some(typ))
+                | (ParamType::NonVecAny, Some(typ))
+                | (ParamType::EnumAny, Some(typ)) => set_or_check_constrained_type(typ)?,
                 // These checks don't need to be more exhaustive (e.g. failing
                 // if arguments passed to `ListAny` are not `ScalartType::List`)
                 // because we've already done general type checking in
@@ -451,7 +455,7 @@ impl ParamList {
                         constrained_type.clone(),
                     ))));
                 }
-                (ParamType::ListElementAny, None) => {
+                (ParamType::ListElementAny, None) | (ParamType::EnumAny, None) => {
                     param_types.push(ParamType::Plain(constrained_type.clone()));
                 }
                 (ParamType::NonVecAny, None) => {
@@ -508,6 +512,9 @@ pub enum ParamType {
     /// A polymorphic psuedotype permitting any array type.  For more details,
     /// see [`resolve_polymorphic_types`].
     ArrayAny,
+    /// A polymorphic pseudotype permitting any enum type. For more details,
+    /// see [`resolve_polymorphic_types`].
+    EnumAny,
     /// A polymorphic pseudotype permitting a `ScalarType::List` of any element
     /// type.  For more details, see [`resolve_polymorphic_types`].
     ListAny,
@@ -536,6 +543,7 @@ impl ParamType {
             // https://github.com/MaterializeInc/materialize/issues/4627
             ArrayAny => matches!(t, Array(..) | String),
             ListAny => matches!(t, List(..) | String),
+            EnumAny => matches!(t, Enum { .. }),
             Any | ListElementAny => true,
             NonVecAny => !t.is_vec(),
             Plain(to) => typeconv::get_cast(CastContext::Implicit, t, to).is_some(),
@@ -564,7 +572,7 @@ impl ParamType {
     fn is_polymorphic(&self) -> bool {
         matches!(
             self,
-            Self::ArrayAny | Self::ListAny | Self::ListElementAny | Self::NonVecAny
+            Self::ArrayAny | Self::EnumAny | Self::ListAny | Self::ListElementAny | Self::NonVecAny
         )
     }
 }
@@ -907,7 +915,7 @@ fn coerce_arg_to_type(
 
         // Polymorphic pseudotypes. As in PostgreSQL, these bail on
         // uncoerced arguments.
-        ArrayAny | ListAny | ListElementAny | NonVecAny => match arg {
+        ArrayAny | EnumAny | ListAny | ListElementAny | NonVecAny => match arg {
             CoercibleScalarExpr::Coerced(arg) => Ok(arg),
             _ => bail!("could not determine polymorphic type because input has type unknown"),
         },
@@ -969,8 +977,7 @@ macro_rules! builtins {
 
 #[derive(Debug)]
 pub struct TableFuncPlan {
-    pub func: TableFunc,
-    pub exprs: Vec<ScalarExpr>,
+    pub expr: RelationExpr,
     pub column_names: Vec<Option<ColumnName>>,
 }
 
@@ -1026,6 +1033,12 @@ lazy_static! {
             "char_length" => Scalar {
                 params!(String) => UnaryFunc::CharLength
             },
+            "col_description" => Scalar {
+                params!(Oid, Int32) => sql_op!(
+                    "(SELECT description FROM pg_catalog.pg_description
+                     WHERE objoid = $1 AND classoid = 1259 AND objsubid = $2)"
+                )
+            },
             "concat" => Scalar {
                  params!((Any)...) => Operation::variadic(|ecx, cexprs| {
                     let mut exprs = vec![];
@@ -1133,12 +1146,13 @@ lazy_static! {
                 params!() => Operation::nullary(|ecx| plan_current_timestamp(ecx, "now"))
             },
             "obj_description" => Scalar {
-                params!(Oid, String) => Operation::binary(|_ecx, _oid, _catalog| {
-                    // This function is meant to return the comment on a
-                    // database object, but we don't presently support comments,
-                    // so stubbed out out to always return NULL.
-                    Ok(ScalarExpr::literal_null(ScalarType::String))
-                })
+                // All objects that can be commented on are described by
+                // pg_class (OID 1259), so comments in any other catalog do
+                // not exist.
+                params!(Oid, String) => sql_op!(
+                    "(SELECT description FROM pg_catalog.pg_description
+                     WHERE objoid = $1 AND classoid = 1259 AND objsubid = 0 AND $2 = 'pg_class')"
+                )
             },
             "pg_encoding_to_char" => Scalar {
                 // Materialize only supports UT8-encoded databases. Return 'UTF8' if Postgres'
@@ -1159,8 +1173,8 @@ lazy_static! {
                 params!(Any) => Operation::new(|ecx, spec, exprs, params| {
                     // pg_typeof reports the type *before* coercion.
                     let name = match ecx.scalar_type(&exprs[0]) {
-                        None => "unknown",
-                        Some(ty) => pgrepr::Type::from(&ty).name(),
+                        None => "unknown".into(),
+                        Some(ty) => pgrepr::Type::from(&ty).name().to_owned(),
                     };
 
                     // For consistency with other functions, verify that
@@ -1172,7 +1186,7 @@ lazy_static! {
                     // regtype, when we support that type. Document the function
                     // at that point. For now, it's useful enough to have this
                     // halfway version that returns a string.
-                    Ok(ScalarExpr::literal(Datum::String(name), ScalarType::String))
+                    Ok(ScalarExpr::literal(Datum::String(&name), ScalarType::String))
                 })
             },
             "regexp_match" => Scalar {
@@ -1322,15 +1336,19 @@ lazy_static! {
             "generate_series" => Table {
                 params!(Int32, Int32) => Operation::binary(move |_ecx, start, stop| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::GenerateSeriesInt32,
-                        exprs: vec![start, stop],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::GenerateSeriesInt32,
+                            exprs: vec![start, stop],
+                        },
                         column_names: vec![Some("generate_series".into())],
                     })
                 }),
                 params!(Int64, Int64) => Operation::binary(move |_ecx, start, stop| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::GenerateSeriesInt64,
-                        exprs: vec![start, stop],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::GenerateSeriesInt64,
+                            exprs: vec![start, stop],
+                        },
                         column_names: vec![Some("generate_series".into())],
                     })
                 })
@@ -1338,8 +1356,10 @@ lazy_static! {
             "jsonb_array_elements" => Table {
                 params!(Jsonb) => Operation::unary(move |_ecx, jsonb| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::JsonbArrayElements { stringify: false },
-                        exprs: vec![jsonb],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::JsonbArrayElements { stringify: false },
+                            exprs: vec![jsonb],
+                        },
                         column_names: vec![Some("value".into())],
                     })
                 })
@@ -1347,8 +1367,10 @@ lazy_static! {
             "jsonb_array_elements_text" => Table {
                 params!(Jsonb) => Operation::unary(move |_ecx, jsonb| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::JsonbArrayElements { stringify: true },
-                        exprs: vec![jsonb],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::JsonbArrayElements { stringify: true },
+                            exprs: vec![jsonb],
+                        },
                         column_names: vec![Some("value".into())],
                     })
                 })
@@ -1356,8 +1378,10 @@ lazy_static! {
             "jsonb_each" => Table {
                 params!(Jsonb) => Operation::unary(move |_ecx, jsonb| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::JsonbEach { stringify: false },
-                        exprs: vec![jsonb],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::JsonbEach { stringify: false },
+                            exprs: vec![jsonb],
+                        },
                         column_names: vec![Some("key".into()), Some("value".into())],
                     })
                 })
@@ -1365,8 +1389,10 @@ lazy_static! {
             "jsonb_each_text" => Table {
                 params!(Jsonb) => Operation::unary(move |_ecx, jsonb| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::JsonbEach { stringify: true },
-                        exprs: vec![jsonb],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::JsonbEach { stringify: true },
+                            exprs: vec![jsonb],
+                        },
                         column_names: vec![Some("key".into()), Some("value".into())],
                     })
                 })
@@ -1374,8 +1400,10 @@ lazy_static! {
             "jsonb_object_keys" => Table {
                 params!(Jsonb) => Operation::unary(move |_ecx, jsonb| {
                     Ok(TableFuncPlan {
-                        func: TableFunc::JsonbObjectKeys,
-                        exprs: vec![jsonb],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::JsonbObjectKeys,
+                            exprs: vec![jsonb],
+                        },
                         column_names: vec![Some("jsonb_object_keys".into())],
                     })
                 })
@@ -1397,11 +1425,13 @@ lazy_static! {
                         bail!("source persistence is currently disabled. Try rerunning Materialize with '--experimental'.");
                     }
                     Ok(TableFuncPlan {
-                        func: TableFunc::ReadPersistedData {
-                            source: entry.id(),
-                            persistence_directory: persistence_directory.expect("known to exist").to_path_buf(),
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::ReadPersistedData {
+                                source: entry.id(),
+                                persistence_directory: persistence_directory.expect("known to exist").to_path_buf(),
+                            },
+                            exprs: vec![],
                         },
-                        exprs: vec![],
                         column_names: vec!["filename", "offset", "key", "value"].iter().map(|c| Some(ColumnName::from(*c))).collect(),
                     })
                 })
@@ -1423,8 +1453,10 @@ lazy_static! {
                     };
                     let ncols = usize::try_from(ncols).expect("known to be greater than zero");
                     Ok(TableFuncPlan {
-                        func: TableFunc::CsvExtract(ncols),
-                        exprs: vec![input],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::CsvExtract(ncols),
+                            exprs: vec![input],
+                        },
                         column_names: (1..=ncols).map(|i| Some(format!("column{}", i).into())).collect(),
                     })
                 })
@@ -1475,8 +1507,10 @@ lazy_static! {
                         })
                         .collect();
                     Ok(TableFuncPlan {
-                        func: TableFunc::RegexpExtract(regex),
-                        exprs: vec![haystack],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::RegexpExtract(regex),
+                            exprs: vec![haystack],
+                        },
                         column_names,
                     })
                 })
@@ -1485,8 +1519,10 @@ lazy_static! {
                 params!(Int64) => Operation::unary(move |ecx, n| {
                     ecx.require_experimental_mode("repeat")?;
                     Ok(TableFuncPlan {
-                        func: TableFunc::Repeat,
-                        exprs: vec![n],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::Repeat,
+                            exprs: vec![n],
+                        },
                         column_names: vec![]
                     })
                 })
@@ -1495,8 +1531,10 @@ lazy_static! {
                 vec![ListAny] => Operation::unary(move |ecx, e| {
                     let el_typ =  ecx.scalar_type(&e).unwrap_list_element_type().clone();
                     Ok(TableFuncPlan {
-                        func: TableFunc::UnnestList{ el_typ },
-                        exprs: vec![e],
+                        expr: RelationExpr::CallTable {
+                            func: TableFunc::UnnestList{ el_typ },
+                            exprs: vec![e],
+                        },
                         column_names: vec![Some("unnest".into())],
                     })
                 })
@@ -1587,27 +1625,120 @@ pub fn resolve_func(
     // resolution here while all functions are builtins. This decision will
     // need to be revisited when either:
     //   * we support configuring the search path from its default, or
-    //   * we support user-defined functions.
+    //   * we support user-defined functions in languages other than SQL.
 
     if let Some(database) = &name.database {
         // If a database name is provided, we need only verify that the
-        // database exists, as presently functions can only exist in ambient
-        // schemas.
+        // database exists, as presently builtin functions can only exist in
+        // ambient schemas.
         let _ = scx.catalog.resolve_database(database)?;
     }
-    let search_path = match name.schema.as_deref() {
+    for builtins in builtin_search_path(name) {
+        if let Some(func) = builtins.get(&*name.item) {
+            return Ok(func);
+        }
+    }
+    bail!("function \"{}\" does not exist", name)
+}
+
+/// Resolves `name` to a user-defined function, if it does not refer to a
+/// builtin function.
+///
+/// Builtin functions take precedence over user-defined functions of the same
+/// name, as if the builtin schemas were always at the front of the search path.
+/// User-defined functions are written in SQL and are inlined into the calling
+/// query when planned.
+pub fn resolve_user_func(
+    scx: &StatementContext,
+    name: &PartialName,
+) -> Result<Option<Func>, anyhow::Error> {
+    let item = match resolve_user_func_item(scx, name) {
+        Some(item) => item,
+        None => return Ok(None),
+    };
+    let (params, returns) = match item.function_details() {
+        Some((params, returns)) => (params.to_vec(), returns.clone()),
+        None => unreachable!("resolve_user_func_item only returns functions"),
+    };
+    let body = match sql_parser::parser::parse_statements(item.create_sql())?.into_element() {
+        Statement::CreateFunction(stmt) => stmt.body,
+        _ => bail!(
+            "internal error: function {} has invalid definition",
+            item.name()
+        ),
+    };
+    let param_types = ParamList::Exact(
+        params
+            .iter()
+            .map(|(_name, typ)| ParamType::Plain(typ.clone()))
+            .collect(),
+    );
+    let func = match returns {
+        FunctionReturns::Scalar(return_type) => Func::Scalar(vec![FuncImpl {
+            params: param_types,
+            op: Operation::variadic(move |ecx, args| {
+                let mut expr = query::plan_scalar_function_body(
+                    ecx.qcx.scx,
+                    body.clone(),
+                    &params,
+                    &return_type,
+                    ecx.qcx.lifetime,
+                )?;
+                expr.splice_parameters(&args, 0);
+                Ok(expr)
+            }),
+        }]),
+        FunctionReturns::Table(desc) => Func::Table(vec![FuncImpl {
+            params: param_types,
+            op: Operation::variadic(move |ecx, args| {
+                let mut expr = query::plan_table_function_body(
+                    ecx.qcx.scx,
+                    body.clone(),
+                    &params,
+                    &desc,
+                    ecx.qcx.lifetime,
+                )?;
+                expr.splice_parameters(&args, 0);
+                Ok(TableFuncPlan {
+                    expr,
+                    column_names: desc.iter_names().map(|n| n.cloned()).collect(),
+                })
+            }),
+        }]),
+    };
+    Ok(Some(func))
+}
+
+/// Resolves `name` to the catalog item that describes a user-defined function,
+/// if it does not refer to a builtin function.
+pub fn resolve_user_func_item<'a>(
+    scx: &StatementContext<'a>,
+    name: &PartialName,
+) -> Option<&'a dyn CatalogItem> {
+    if builtin_search_path(name)
+        .iter()
+        .any(|builtins| builtins.contains_key(&*name.item))
+    {
+        return None;
+    }
+    let name = scx.catalog.resolve_item(name).ok()?;
+    let item = scx.catalog.get_item(&name);
+    match item.item_type() {
+        CatalogItemType::Func => Some(item),
+        _ => None,
+    }
+}
+
+/// Returns the maps of builtin functions that are searched, in order, when
+/// resolving `name`.
+fn builtin_search_path(name: &PartialName) -> Vec<&'static HashMap<&'static str, Func>> {
+    match name.schema.as_deref() {
         Some("pg_catalog") => vec![&*PG_CATALOG_BUILTINS],
         Some("mz_catalog") => vec![&*MZ_CATALOG_BUILTINS],
         Some("mz_internal") => vec![&*MZ_INTERNAL_BUILTINS],
         Some(_) => vec![],
         None => vec![&*MZ_CATALOG_BUILTINS, &*PG_CATALOG_BUILTINS],
-    };
-    for builtins in search_path {
-        if let Some(func) = builtins.get(&*name.item) {
-            return Ok(func);
-        }
     }
-    bail!("function \"{}\" does not exist", name)
 }
 
 lazy_static! {
@@ -1871,7 +2002,8 @@ lazy_static! {
                 params!(Interval, Interval) => BinaryFunc::Lt,
                 params!(Bytes, Bytes) => BinaryFunc::Lt,
                 params!(String, String) => BinaryFunc::Lt,
-                params!(Jsonb, Jsonb) => BinaryFunc::Lt
+                params!(Jsonb, Jsonb) => BinaryFunc::Lt,
+                params!(EnumAny, EnumAny) => BinaryFunc::Lt
             },
             "<=" => Scalar {
                 params!(Decimal(0, 0), Decimal(0, 0)) => {
@@ -1893,7 +2025,8 @@ lazy_static! {
                 params!(Interval, Interval) => BinaryFunc::Lte,
                 params!(Bytes, Bytes) => BinaryFunc::Lte,
                 params!(String, String) => BinaryFunc::Lte,
-                params!(Jsonb, Jsonb) => BinaryFunc::Lte
+                params!(Jsonb, Jsonb) => BinaryFunc::Lte,
+                params!(EnumAny, EnumAny) => BinaryFunc::Lte
             },
             ">" => Scalar {
                 params!(Decimal(0, 0), Decimal(0, 0)) => {
@@ -1915,7 +2048,8 @@ lazy_static! {
                 params!(Interval, Interval) => BinaryFunc::Gt,
                 params!(Bytes, Bytes) => BinaryFunc::Gt,
                 params!(String, String) => BinaryFunc::Gt,
-                params!(Jsonb, Jsonb) => BinaryFunc::Gt
+                params!(Jsonb, Jsonb) => BinaryFunc::Gt,
+                params!(EnumAny, EnumAny) => BinaryFunc::Gt
             },
             ">=" => Scalar {
                 params!(Decimal(0, 0), Decimal(0, 0)) => {
@@ -1937,7 +2071,8 @@ lazy_static! {
                 params!(Interval, Interval) => BinaryFunc::Gte,
                 params!(Bytes, Bytes) => BinaryFunc::Gte,
                 params!(String, String) => BinaryFunc::Gte,
-                params!(Jsonb, Jsonb) => BinaryFunc::Gte
+                params!(Jsonb, Jsonb) => BinaryFunc::Gte,
+                params!(EnumAny, EnumAny) => BinaryFunc::Gte
             },
             "=" => Scalar {
                 params!(Decimal(0, 0), Decimal(0, 0)) => {
@@ -1959,7 +2094,8 @@ lazy_static! {
                 params!(Interval, Interval) => BinaryFunc::Eq,
                 params!(Bytes, Bytes) => BinaryFunc::Eq,
                 params!(String, String) => BinaryFunc::Eq,
-                params!(Jsonb, Jsonb) => BinaryFunc::Eq
+                params!(Jsonb, Jsonb) => BinaryFunc::Eq,
+                params!(EnumAny, EnumAny) => BinaryFunc::Eq
             },
             "<>" => Scalar {
                 params!(Decimal(0, 0), Decimal(0, 0)) => {
@@ -1981,7 +2117,8 @@ lazy_static! {
                 params!(Interval, Interval) => BinaryFunc::NotEq,
                 params!(Bytes, Bytes) => BinaryFunc::NotEq,
                 params!(String, String) => BinaryFunc::NotEq,
-                params!(Jsonb, Jsonb) => BinaryFunc::NotEq
+                params!(Jsonb, Jsonb) => BinaryFunc::NotEq,
+                params!(EnumAny, EnumAny) => BinaryFunc::NotEq
             }
         }
     };
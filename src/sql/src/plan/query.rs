@@ -25,6 +25,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::iter;
 use std::mem;
+use std::rc::Rc;
 
 use anyhow::{anyhow, bail, ensure, Context};
 use itertools::Itertools;
@@ -33,7 +34,7 @@ use sql_parser::ast::visit::{self, Visit};
 use sql_parser::ast::{
     DataType, Distinct, Expr, Function, FunctionArgs, Ident, InsertSource, JoinConstraint,
     JoinOperator, Limit, ObjectName, OrderByExpr, Query, Select, SelectItem, SetExpr, SetOperator,
-    TableAlias, TableFactor, TableWithJoins, Value, Values,
+    Statement, TableAlias, TableFactor, TableWithJoins, Value, Values,
 };
 
 use ::expr::{GlobalId, Id, RowSetFinishing};
@@ -250,6 +251,161 @@ struct CastRelationError {
     target_type: ScalarType,
 }
 
+/// Plans the body of a SQL-language function that returns a single value.
+///
+/// Within the body, the function's parameters can be referred to positionally,
+/// as `$1`, `$2`, and so on, or by name, if they are named. The parameters are
+/// planned as `ScalarExpr::Parameter`s, which the caller is expected to splice
+/// away. A body of the form `SELECT <expr>` is planned as `<expr>` itself; any
+/// other body is planned as a scalar subquery.
+pub fn plan_scalar_function_body(
+    scx: &StatementContext,
+    mut body: Query,
+    params: &[(Option<ColumnName>, ScalarType)],
+    return_type: &ScalarType,
+    lifetime: QueryLifetime,
+) -> Result<ScalarExpr, anyhow::Error> {
+    let scx = function_body_context(scx, params);
+    transform_ast::transform_query(&scx, &mut body)?;
+    let mut qcx = QueryContext::root(&scx, lifetime);
+    qcx.param_names = params.iter().map(|(name, _)| name.clone()).collect();
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "function body",
+        scope: &Scope::empty(None),
+        relation_type: &RelationType::empty(),
+        allow_aggregates: false,
+        allow_subqueries: true,
+    };
+    let expr = match simple_select_expr(&body) {
+        Some(expr) => {
+            let expr = plan_expr(ecx, expr)?;
+            typeconv::plan_coerce(ecx, expr, return_type)?
+        }
+        None => {
+            let (expr, scope) = plan_subquery(&qcx, &body)?;
+            if scope.len() != 1 {
+                bail!(
+                    "return type mismatch in function declared to return {}",
+                    pgrepr::Type::from(return_type).name()
+                );
+            }
+            expr.select()
+        }
+    };
+    check_function_body_params(&scx, params)?;
+    typeconv::plan_cast(
+        "function body",
+        ecx,
+        CastContext::Assignment,
+        expr,
+        return_type,
+    )
+    .map_err(|_| {
+        anyhow!(
+            "return type mismatch in function declared to return {}",
+            pgrepr::Type::from(return_type).name()
+        )
+    })
+}
+
+/// Plans the body of a SQL-language function that returns a table.
+///
+/// Parameters are handled as in [`plan_scalar_function_body`]. The columns
+/// that the body produces are cast to the types of the columns in `returns`.
+pub fn plan_table_function_body(
+    scx: &StatementContext,
+    mut body: Query,
+    params: &[(Option<ColumnName>, ScalarType)],
+    returns: &RelationDesc,
+    lifetime: QueryLifetime,
+) -> Result<RelationExpr, anyhow::Error> {
+    let scx = function_body_context(scx, params);
+    transform_ast::transform_query(&scx, &mut body)?;
+    let mut qcx = QueryContext::root(&scx, lifetime);
+    qcx.param_names = params.iter().map(|(name, _)| name.clone()).collect();
+    let (expr, _scope) = plan_subquery(&qcx, &body)?;
+    check_function_body_params(&scx, params)?;
+    let mismatch = || {
+        anyhow!(
+            "return type mismatch in function declared to return TABLE ({})",
+            returns
+                .typ()
+                .column_types
+                .iter()
+                .map(|typ| pgrepr::Type::from(&typ.scalar_type).name().to_owned())
+                .join(", ")
+        )
+    };
+    if expr.arity() != returns.arity() {
+        return Err(mismatch());
+    }
+    let target_types = returns
+        .typ()
+        .column_types
+        .iter()
+        .map(|typ| &typ.scalar_type);
+    cast_relation(&qcx, CastContext::Assignment, expr, target_types).map_err(|_| mismatch())
+}
+
+/// Returns a copy of `scx` in which the parameter types are bound to the types
+/// of the parameters of a function whose body is to be planned.
+fn function_body_context<'a>(
+    scx: &StatementContext<'a>,
+    params: &[(Option<ColumnName>, ScalarType)],
+) -> StatementContext<'a> {
+    let mut scx = scx.clone();
+    scx.param_types = Rc::new(RefCell::new(
+        params
+            .iter()
+            .enumerate()
+            .map(|(i, (_name, typ))| (i + 1, typ.clone()))
+            .collect(),
+    ));
+    scx
+}
+
+/// Ensures that a function body planned in `scx` did not refer to any
+/// parameters beyond those that the function declares.
+fn check_function_body_params(
+    scx: &StatementContext,
+    params: &[(Option<ColumnName>, ScalarType)],
+) -> Result<(), anyhow::Error> {
+    if let Some(n) = scx.param_types.borrow().keys().find(|n| **n > params.len()) {
+        bail!("there is no parameter ${}", n);
+    }
+    Ok(())
+}
+
+/// Returns the expression that `query` selects, if `query` is of the simple
+/// form `SELECT <expr>`.
+fn simple_select_expr(query: &Query) -> Option<&Expr> {
+    if !query.ctes.is_empty()
+        || !query.order_by.is_empty()
+        || query.limit.is_some()
+        || query.offset.is_some()
+    {
+        return None;
+    }
+    match &query.body {
+        SetExpr::Select(select) => match &**select {
+            Select {
+                distinct: None,
+                projection,
+                from,
+                selection: None,
+                group_by,
+                having: None,
+            } if from.is_empty() && group_by.is_empty() => match &projection[..] {
+                [SelectItem::Expr { expr, alias: _ }] => Some(expr),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Cast a relation from one type to another using the specified type of cast.
 ///
 /// The length of `target_types` must match the arity of `expr`.
@@ -344,6 +500,31 @@ pub fn eval_as_of<'a>(
     })
 }
 
+/// Plans the `DEFAULT` expression of a column of type `ty`.
+///
+/// The expression cannot refer to any columns, and is cast to `ty` as if it
+/// were being assigned to the column.
+pub fn plan_default_expr(
+    scx: &StatementContext,
+    mut expr: Expr,
+    ty: &ScalarType,
+) -> Result<::expr::ScalarExpr, anyhow::Error> {
+    let scope = Scope::empty(None);
+    let desc = RelationDesc::empty();
+    let qcx = &QueryContext::root(scx, QueryLifetime::OneShot);
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "DEFAULT",
+        scope: &scope,
+        relation_type: &desc.typ(),
+        allow_aggregates: false,
+        allow_subqueries: false,
+    };
+    transform_ast::transform_expr(scx, &mut expr)?;
+    let expr = plan_expr(ecx, &expr)?.cast_to("DEFAULT", ecx, CastContext::Assignment, ty)?;
+    expr.lower_uncorrelated()
+}
+
 pub fn plan_index_exprs<'a>(
     scx: &'a StatementContext,
     on_desc: &RelationDesc,
@@ -1262,7 +1443,12 @@ fn plan_table_function(
         bail!("VALUES expression in FROM clause must be surrounded by parentheses");
     }
 
-    let impls = match func::resolve_func(&ecx.qcx.scx, &name)? {
+    let user_func = func::resolve_user_func(&ecx.qcx.scx, &name)?;
+    let func = match &user_func {
+        Some(func) => func,
+        None => func::resolve_func(&ecx.qcx.scx, &name)?,
+    };
+    let impls = match func {
         Func::Table(impls) => impls,
         _ => bail!("{} is not a table function", name),
     };
@@ -1271,10 +1457,6 @@ fn plan_table_function(
         FunctionArgs::Args(args) => plan_exprs(ecx, args)?,
     };
     let tf = func::select_impl(ecx, FuncSpec::Func(&name), impls, args)?;
-    let call = RelationExpr::CallTable {
-        func: tf.func,
-        exprs: tf.exprs,
-    };
     let scope = Scope::from_source(
         Some(PartialName {
             database: None,
@@ -1800,7 +1982,7 @@ pub fn plan_expr<'a>(ecx: &'a ExprContext, e: &Expr) -> Result<CoercibleScalarEx
         // Generalized functions, operators, and casts.
         Expr::Op { op, expr1, expr2 } => plan_op(ecx, op, expr1, expr2.as_deref())?.into(),
         Expr::Cast { expr, data_type } => {
-            let to_scalar_type = scalar_type_from_sql(data_type)?;
+            let to_scalar_type = scalar_type_from_sql(ecx.qcx.scx, data_type)?;
             let expr = match &**expr {
                 // Special case a direct cast of an ARRAY or LIST expression so
                 // we can pass in the target type as a type hint. This is
@@ -2271,6 +2453,17 @@ fn plan_identifier(ecx: &ExprContext, names: &[Ident]) -> Result<ScalarExpr, Pla
         Err(e) => return Err(e),
     }
 
+    // If the query is the body of a function, the name might refer to one of
+    // the function's named parameters.
+    if let Some(i) = ecx
+        .qcx
+        .param_names
+        .iter()
+        .position(|name| name.as_ref() == Some(&col_name))
+    {
+        return Ok(ScalarExpr::Parameter(i + 1));
+    }
+
     // The name doesn't refer to a column. Check if it refers to a table. If it
     // does, the expression is a record containing all the columns of the table.
     //
@@ -2330,7 +2523,12 @@ fn plan_function<'a>(
     sql_func: &'a Function,
 ) -> Result<ScalarExpr, anyhow::Error> {
     let name = normalize::object_name(sql_func.name.clone())?;
-    let impls = match func::resolve_func(&ecx.qcx.scx, &name)? {
+    let user_func = func::resolve_user_func(&ecx.qcx.scx, &name)?;
+    let func = match &user_func {
+        Some(func) => func,
+        None => func::resolve_func(&ecx.qcx.scx, &name)?,
+    };
+    let impls = match func {
         Func::Aggregate(_) if ecx.allow_aggregates => {
             // should already have been caught by `scope.resolve_expr` in `plan_expr`
             bail!(
@@ -2503,7 +2701,10 @@ fn find_trivial_column_equivalences(expr: &ScalarExpr) -> Vec<(usize, usize)> {
     equivalences
 }
 
-pub fn scalar_type_from_sql(data_type: &DataType) -> Result<ScalarType, anyhow::Error> {
+pub fn scalar_type_from_sql(
+    scx: &StatementContext,
+    data_type: &DataType,
+) -> Result<ScalarType, anyhow::Error> {
     // NOTE this needs to stay in sync with symbiosis::push_column
     Ok(match data_type {
         DataType::Boolean => ScalarType::Bool,
@@ -2534,16 +2735,33 @@ pub fn scalar_type_from_sql(data_type: &DataType) -> Result<ScalarType, anyhow::
         DataType::Bytea => ScalarType::Bytes,
         DataType::Jsonb => ScalarType::Jsonb,
         DataType::Uuid => ScalarType::Uuid,
-        DataType::Array(elem_type) => ScalarType::Array(Box::new(scalar_type_from_sql(elem_type)?)),
-        DataType::List(elem_type) => ScalarType::List(Box::new(scalar_type_from_sql(elem_type)?)),
+        DataType::Array(elem_type) => {
+            ScalarType::Array(Box::new(scalar_type_from_sql(scx, elem_type)?))
+        }
+        DataType::List(elem_type) => {
+            ScalarType::List(Box::new(scalar_type_from_sql(scx, elem_type)?))
+        }
         DataType::Oid => ScalarType::Oid,
+        DataType::Custom(name) => {
+            let full_name = match scx.resolve_item(name.clone()) {
+                Ok(full_name) => full_name,
+                Err(_) => bail!("type \"{}\" does not exist", name),
+            };
+            let item = scx.catalog.get_item(&full_name);
+            if item.item_type() != CatalogItemType::Type {
+                bail!("type \"{}\" does not exist", name);
+            }
+            match item.scalar_type() {
+                Some(ty) => ty,
+                None => unsupported!(format!("type {} in this position", full_name)),
+            }
+        }
         DataType::Binary(..)
         | DataType::Blob(_)
         | DataType::Clob(_)
         | DataType::Regclass
         | DataType::TimeTz
-        | DataType::Varbinary(_)
-        | DataType::Custom(_) => bail!("Unexpected SQL type: {:?}", data_type),
+        | DataType::Varbinary(_) => bail!("Unexpected SQL type: {:?}", data_type),
     })
 }
 
@@ -2570,6 +2788,68 @@ pub fn scalar_type_from_pg(ty: &pgrepr::Type) -> Result<ScalarType, anyhow::Erro
             bail!("internal error: can't convert from pg record to materialize record")
         }
         pgrepr::Type::Oid => Ok(ScalarType::Oid),
+        pgrepr::Type::Enum { oid, name, labels } => Ok(ScalarType::Enum {
+            oid: *oid,
+            name: name.clone(),
+            labels: labels.clone(),
+        }),
+    }
+}
+
+/// Returns the IDs of the user-defined types and functions that `stmt` refers
+/// to, so that they cannot be dropped while the item that `stmt` creates
+/// exists.
+pub fn user_defined_dependencies(scx: &StatementContext, stmt: &Statement) -> Vec<GlobalId> {
+    let mut visitor = UserDefinedDependencyVisitor {
+        scx,
+        ids: Vec::new(),
+    };
+    visitor.visit_statement(stmt);
+    let mut ids = visitor.ids;
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// This is used to collect the user-defined types and functions named within a
+/// `Statement`.
+struct UserDefinedDependencyVisitor<'a> {
+    scx: &'a StatementContext<'a>,
+    ids: Vec<GlobalId>,
+}
+
+impl<'a> UserDefinedDependencyVisitor<'a> {
+    fn add_function(&mut self, name: &ObjectName) {
+        // Names that do not resolve are reported when the function is planned.
+        if let Ok(name) = normalize::object_name(name.clone()) {
+            if let Some(item) = func::resolve_user_func_item(self.scx, &name) {
+                self.ids.push(item.id());
+            }
+        }
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for UserDefinedDependencyVisitor<'a> {
+    fn visit_data_type(&mut self, data_type: &'ast DataType) {
+        if let DataType::Custom(name) = data_type {
+            // Names that do not resolve are reported when the type is planned.
+            if let Ok(name) = self.scx.resolve_item(name.clone()) {
+                self.ids.push(self.scx.catalog.get_item(&name).id());
+            }
+        }
+        visit::visit_data_type(self, data_type);
+    }
+
+    fn visit_function(&mut self, func: &'ast Function) {
+        self.add_function(&func.name);
+        visit::visit_function(self, func);
+    }
+
+    fn visit_table_factor(&mut self, table_factor: &'ast TableFactor) {
+        if let TableFactor::Function { name, .. } = table_factor {
+            self.add_function(name);
+        }
+        visit::visit_table_factor(self, table_factor);
     }
 }
 
@@ -2665,6 +2945,9 @@ pub struct QueryContext<'a> {
     pub outer_scope: Scope,
     /// The type of the outer relation expressions.
     pub outer_relation_types: Vec<RelationType>,
+    /// The names of the parameters, if the query is the body of a function
+    /// with named parameters.
+    pub param_names: Vec<Option<ColumnName>>,
 }
 
 impl<'a> QueryContext<'a> {
@@ -2674,6 +2957,7 @@ impl<'a> QueryContext<'a> {
             lifetime,
             outer_scope: Scope::empty(None),
             outer_relation_types: vec![],
+            param_names: vec![],
         }
     }
 
@@ -2692,6 +2976,7 @@ impl<'a> QueryContext<'a> {
                 .chain(std::iter::once(relation_type))
                 .cloned()
                 .collect(),
+            param_names: self.param_names.clone(),
         }
     }
 }
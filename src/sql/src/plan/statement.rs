@@ -26,36 +26,42 @@ use url::Url;
 use dataflow_types::{
     AvroEncoding, AvroOcfEncoding, AvroOcfSinkConnectorBuilder, Consistency, CsvEncoding,
     DataEncoding, Envelope, ExternalSourceConnector, FileSourceConnector,
-    KafkaSinkConnectorBuilder, KafkaSourceConnector, KinesisSourceConnector, ProtobufEncoding,
-    RegexEncoding, SinkConnectorBuilder, SourceConnector,
+    KafkaSinkConnectorBuilder, KafkaSinkFormatBuilder, KafkaSourceConnector,
+    KinesisSourceConnector, ProtobufEncoding, RegexEncoding, SinkConnectorBuilder, SinkEnvelope,
+    SourceConnector,
 };
 use expr::{GlobalId, RowSetFinishing};
 use interchange::avro::{self, DebeziumDeduplicationStrategy, Encoder};
+use interchange::protobuf::{self, decode_descriptors};
 use ore::collections::CollectionExt;
 use ore::iter::IteratorExt;
-use repr::{strconv, RelationDesc, RelationType, ScalarType};
+use repr::{strconv, ColumnName, RelationDesc, RelationType, ScalarType};
 use sql_parser::ast::display::AstDisplay;
 use sql_parser::ast::{
-    AlterIndexOptionsList, AlterIndexOptionsStatement, AlterObjectRenameStatement, AvroSchema,
-    ColumnOption, Connector, CopyDirection, CopyRelation, CopyStatement, CopyTarget,
-    CreateDatabaseStatement, CreateIndexStatement, CreateMapTypeStatement, CreateSchemaStatement,
-    CreateSinkStatement, CreateSourceStatement, CreateTableStatement, CreateViewStatement,
-    DiscardStatement, DiscardTarget, DropDatabaseStatement, DropObjectsStatement, ExplainStage,
-    ExplainStatement, Explainee, Expr, Format, Ident, IfExistsBehavior, InsertStatement,
-    ObjectName, ObjectType, Query, SelectStatement, SetVariableStatement, SetVariableValue,
-    ShowVariableStatement, SqlOption, Statement, TailStatement, Value, WithOption, WithOptionValue,
+    AlterIndexOptionsList, AlterIndexOptionsStatement, AlterObjectRenameStatement,
+    AlterSourceStatement, AlterTableAction, AlterTableStatement, AvroSchema, CloseStatement,
+    ColumnDef, ColumnOption, CommentObject, CommentStatement, Connector, CopyDirection,
+    CopyRelation, CopyStatement, CopyTarget, CreateCompositeTypeStatement, CreateDatabaseStatement,
+    CreateEnumTypeStatement, CreateFunctionStatement, CreateIndexStatement, CreateMapTypeStatement,
+    CreateSchemaStatement, CreateSinkStatement, CreateSourceStatement, CreateTableStatement,
+    CreateViewStatement, DeclareStatement, DiscardStatement, DiscardTarget, DropDatabaseStatement,
+    DropObjectsStatement, ExplainStage, ExplainStatement, Explainee, Expr, FetchStatement, Format,
+    FunctionReturnType, Ident, IfExistsBehavior, InsertStatement, ObjectName, ObjectType, Query,
+    SelectStatement, SetVariableStatement, SetVariableValue, ShowVariableStatement, SinkRelation,
+    SqlOption, Statement, TailEnvelope, TailStatement, Value, WithOption, WithOptionValue,
 };
 
-use crate::catalog::{Catalog, CatalogItemType};
+use crate::catalog::{Catalog, CatalogItem, CatalogItemType};
 use crate::kafka_util;
 use crate::names::{DatabaseSpecifier, FullName, PartialName, SchemaSpecifier};
 use crate::normalize;
 use crate::plan::error::PlanError;
+use crate::plan::expr::{RelationExpr, ScalarExpr};
 use crate::plan::query::QueryLifetime;
 use crate::plan::{
-    query, scalar_type_from_sql, AlterIndexLogicalCompactionWindow, CopyFormat, Index,
-    LogicalCompactionWindow, Params, PeekWhen, Plan, PlanContext, Sink, Source, Table, Type,
-    TypeInner, View,
+    query, scalar_type_from_sql, AlterIndexLogicalCompactionWindow, AlterSource, AlterTable,
+    CopyFormat, Function, FunctionReturns, Index, LogicalCompactionWindow, Params, PeekWhen, Plan,
+    PlanContext, Sink, SinkFrom, Source, Table, TableMigration, Type, TypeInner, View,
 };
 use crate::pure::Schema;
 
@@ -132,6 +138,9 @@ pub fn describe_statement(
         | Statement::CreateSink(_)
         | Statement::CreateView(_)
         | Statement::CreateMapType(_)
+        | Statement::CreateEnumType(_)
+        | Statement::CreateCompositeType(_)
+        | Statement::CreateFunction(_)
         | Statement::DropDatabase(_)
         | Statement::DropObjects(_)
         | Statement::SetVariable(_)
@@ -140,7 +149,17 @@ pub fn describe_statement(
         | Statement::Rollback(_)
         | Statement::Commit(_)
         | Statement::AlterObjectRename(_)
-        | Statement::AlterIndexOptions(_) => StatementDesc::new(None),
+        | Statement::AlterIndexOptions(_)
+        | Statement::AlterTable(_)
+        | Statement::AlterSource(_)
+        | Statement::Comment(_) => StatementDesc::new(None),
+
+        Statement::Declare(DeclareStatement { stmt, .. }) => StatementDesc::new(None)
+            .with_pgrepr_params(describe_statement(catalog, *stmt, param_types_in)?.param_types),
+
+        // The shape of the rows produced by a `FETCH` is the shape of its
+        // cursor, which lives in the session and is filled in by the caller.
+        Statement::Fetch(_) | Statement::Close(_) => StatementDesc::new(None),
 
         Statement::Explain(ExplainStatement {
             stage, explainee, ..
@@ -197,6 +216,11 @@ pub fn describe_statement(
                 .with_column("Create Index", ScalarType::String.nullable(false)),
         )),
 
+        Statement::ShowCreateAll(_) => StatementDesc::new(Some(
+            RelationDesc::empty()
+                .with_column("Create Statement", ScalarType::String.nullable(false)),
+        )),
+
         Statement::ShowColumns(stmt) => show::show_columns(&scx, stmt)?.describe()?,
         Statement::ShowIndexes(stmt) => show::show_indexes(&scx, stmt)?.describe()?,
         Statement::ShowDatabases(stmt) => show::show_databases(&scx, stmt)?.describe()?,
@@ -214,9 +238,13 @@ pub fn describe_statement(
             }))
         }
 
-        Statement::Tail(TailStatement { name, options, .. }) => {
-            let name = scx.resolve_item(name)?;
-            let sql_object = scx.catalog.get_item(&name);
+        Statement::Tail(TailStatement {
+            relation,
+            options,
+            envelope,
+            ..
+        }) => {
+            let (_, relation_desc) = plan_sink_relation(&scx, relation, "tailed")?;
             let options = TailOptions::try_from(options)?;
             const MAX_U64_DIGITS: u8 = 20;
             let mut desc = RelationDesc::empty().with_column(
@@ -226,10 +254,15 @@ pub fn describe_statement(
             if options.progress.unwrap_or(false) {
                 desc = desc.with_column("progressed", ScalarType::Bool.nullable(false));
             }
-            let desc = desc
-                .with_column("diff", ScalarType::Int64.nullable(true))
-                .concat(sql_object.desc()?.clone());
-            StatementDesc::new(Some(desc))
+            // Upsert tails report whether each key was deleted in place of
+            // the diff.
+            let desc = match envelope {
+                None => desc.with_column("diff", ScalarType::Int64.nullable(true)),
+                Some(TailEnvelope::Upsert { .. }) => {
+                    desc.with_column("deleted", ScalarType::Bool.nullable(true))
+                }
+            };
+            StatementDesc::new(Some(desc.concat(relation_desc)))
         }
 
         Statement::Copy(CopyStatement { relation, .. }) => match relation {
@@ -295,10 +328,16 @@ pub fn handle_statement(
         Statement::CreateTable(stmt) => handle_create_table(scx, stmt),
         Statement::CreateView(stmt) => handle_create_view(scx, stmt, params),
         Statement::CreateMapType(stmt) => handle_create_map_type(scx, stmt),
+        Statement::CreateEnumType(stmt) => handle_create_enum_type(scx, stmt),
+        Statement::CreateCompositeType(stmt) => handle_create_composite_type(scx, stmt),
+        Statement::CreateFunction(stmt) => handle_create_function(scx, stmt),
         Statement::DropDatabase(stmt) => handle_drop_database(scx, stmt),
         Statement::DropObjects(stmt) => handle_drop_objects(scx, stmt),
         Statement::AlterObjectRename(stmt) => handle_alter_object_rename(scx, stmt),
         Statement::AlterIndexOptions(stmt) => handle_alter_index_options(scx, stmt),
+        Statement::AlterTable(stmt) => handle_alter_table(scx, stmt),
+        Statement::AlterSource(stmt) => handle_alter_source(scx, stmt),
+        Statement::Comment(stmt) => handle_comment(scx, stmt),
 
         Statement::ShowColumns(stmt) => show::show_columns(scx, stmt)?.handle(),
         Statement::ShowCreateTable(stmt) => show::handle_show_create_table(scx, stmt),
@@ -306,6 +345,7 @@ pub fn handle_statement(
         Statement::ShowCreateView(stmt) => show::handle_show_create_view(scx, stmt),
         Statement::ShowCreateSink(stmt) => show::handle_show_create_sink(scx, stmt),
         Statement::ShowCreateIndex(stmt) => show::handle_show_create_index(scx, stmt),
+        Statement::ShowCreateAll(_) => Ok(Plan::ShowCreateAll),
         Statement::ShowDatabases(stmt) => show::show_databases(scx, stmt)?.handle(),
         Statement::ShowObjects(stmt) => show::show_objects(scx, stmt)?.handle(),
         Statement::ShowIndexes(stmt) => show::show_indexes(scx, stmt)?.handle(),
@@ -319,6 +359,10 @@ pub fn handle_statement(
         Statement::Tail(stmt) => handle_tail(scx, stmt, None),
         Statement::Copy(stmt) => handle_copy(scx, stmt),
 
+        Statement::Declare(stmt) => handle_declare(scx, stmt, params),
+        Statement::Fetch(stmt) => handle_fetch(scx, stmt),
+        Statement::Close(stmt) => handle_close(scx, stmt),
+
         Statement::Insert(stmt) => handle_insert(scx, stmt, params),
 
         Statement::StartTransaction(_) => Ok(Plan::StartTransaction),
@@ -375,36 +419,153 @@ fn handle_discard(
     }
 }
 
+fn handle_declare(
+    _: &StatementContext,
+    DeclareStatement { name, stmt }: DeclareStatement,
+    params: &Params,
+) -> Result<Plan, anyhow::Error> {
+    match *stmt {
+        Statement::Select(_) | Statement::Tail(_) => (),
+        _ => bail!("DECLARE CURSOR may only be used with SELECT or TAIL statements"),
+    }
+    Ok(Plan::Declare {
+        name: normalize::ident(name),
+        stmt: *stmt,
+        params: params.clone(),
+    })
+}
+
+fn handle_fetch(
+    _: &StatementContext,
+    FetchStatement {
+        name,
+        count,
+        options,
+    }: FetchStatement,
+) -> Result<Plan, anyhow::Error> {
+    let options = FetchOptions::try_from(options)?;
+    let timeout = match options.timeout {
+        Some(timeout) => Some(parse_duration::parse(&timeout)?),
+        None => None,
+    };
+    Ok(Plan::Fetch {
+        name: normalize::ident(name),
+        count,
+        timeout,
+    })
+}
+
+fn handle_close(
+    _: &StatementContext,
+    CloseStatement { name }: CloseStatement,
+) -> Result<Plan, anyhow::Error> {
+    Ok(Plan::Close {
+        name: normalize::ident(name),
+    })
+}
+
 fn handle_tail(
     scx: &StatementContext,
     TailStatement {
-        name,
+        relation,
         options,
+        envelope,
         as_of,
     }: TailStatement,
     copy_to: Option<CopyFormat>,
 ) -> Result<Plan, anyhow::Error> {
-    let from = scx.resolve_item(name)?;
-    let entry = scx.catalog.get_item(&from);
+    let (from, desc) = plan_sink_relation(scx, relation, "tailed")?;
     let ts = as_of.map(|e| query::eval_as_of(scx, e)).transpose()?;
     let options = TailOptions::try_from(options)?;
+    let (envelope, key_indices) = match envelope {
+        None => (SinkEnvelope::Debezium, None),
+        Some(TailEnvelope::Upsert { key }) => {
+            (SinkEnvelope::Upsert, Some(plan_sink_key(&desc, key)?))
+        }
+    };
 
-    match entry.item_type() {
-        CatalogItemType::Table | CatalogItemType::Source | CatalogItemType::View => {
-            Ok(Plan::Tail {
-                id: entry.id(),
-                ts,
-                with_snapshot: options.snapshot.unwrap_or(true),
-                copy_to,
-                emit_progress: options.progress.unwrap_or(false),
-                object_columns: entry.desc()?.arity(),
-            })
+    Ok(Plan::Tail {
+        from,
+        ts,
+        with_snapshot: options.snapshot.unwrap_or(true),
+        copy_to,
+        emit_progress: options.progress.unwrap_or(false),
+        object_columns: desc.arity(),
+        envelope,
+        key_indices,
+    })
+}
+
+/// Resolves the names of the columns in the key of a sink or tail to their
+/// indices in `desc`.
+fn plan_sink_key(desc: &RelationDesc, key: Vec<Ident>) -> Result<Vec<usize>, anyhow::Error> {
+    let key = key
+        .into_iter()
+        .map(normalize::column_name)
+        .collect::<Vec<_>>();
+    let mut uniq = HashSet::new();
+    for col in key.iter() {
+        if !uniq.insert(col) {
+            bail!("Repeated column name in sink key: {}", col);
+        }
+    }
+    key.into_iter()
+        .map(|col| -> anyhow::Result<usize> {
+            let name_idx = desc
+                .get_by_name(&col)
+                .map(|(idx, _type)| idx)
+                .ok_or_else(|| anyhow!("No such column: {}", col))?;
+            if desc.get_unambiguous_name(name_idx).is_none() {
+                bail!("Ambiguous column: {}", col);
+            }
+            Ok(name_idx)
+        })
+        .collect()
+}
+
+/// Plans the relation that a sink or tail reads from, returning the relation
+/// and its description.
+///
+/// Queries are planned like the query of a view, as their results are
+/// maintained for as long as the sink or tail is running. `action` describes
+/// what is being done to the relation, for use in error messages.
+fn plan_sink_relation(
+    scx: &StatementContext,
+    relation: SinkRelation,
+    action: &str,
+) -> Result<(SinkFrom, RelationDesc), anyhow::Error> {
+    match relation {
+        SinkRelation::Name(name) => {
+            let name = scx.resolve_item(name)?;
+            let entry = scx.catalog.get_item(&name);
+            match entry.item_type() {
+                CatalogItemType::Table | CatalogItemType::Source | CatalogItemType::View => {
+                    Ok((SinkFrom::Id(entry.id()), entry.desc()?.clone()))
+                }
+                CatalogItemType::Index
+                | CatalogItemType::Sink
+                | CatalogItemType::Type
+                | CatalogItemType::Func => bail!(
+                    "'{}' cannot be {} because it is a {}",
+                    name,
+                    action,
+                    entry.item_type(),
+                ),
+            }
+        }
+        SinkRelation::Query(query) => {
+            let (mut expr, desc, finishing) =
+                query::plan_root_query(scx, query, QueryLifetime::Static)?;
+            expr.finish(finishing);
+            let expr = expr.decorrelate();
+            Ok((
+                SinkFrom::Query {
+                    expr,
+                    desc: desc.clone(),
+                },
+                desc,
+            ))
         }
-        CatalogItemType::Index | CatalogItemType::Sink | CatalogItemType::Type => bail!(
-            "'{}' cannot be tailed because it is a {}",
-            from,
-            entry.item_type(),
-        ),
     }
 }
 
@@ -547,8 +708,238 @@ fn handle_alter_index_options(
     Ok(Plan::AlterIndexLogicalCompactionWindow(alter_index))
 }
 
+fn handle_alter_table(
+    scx: &StatementContext,
+    AlterTableStatement {
+        name,
+        if_exists,
+        action,
+    }: AlterTableStatement,
+) -> Result<Plan, anyhow::Error> {
+    let entry = match scx.resolve_item(name.clone()) {
+        Ok(full_name) => scx.catalog.get_item(&full_name),
+        Err(_) if if_exists => {
+            // TODO(benesch): generate a notice indicating this
+            // item does not exist.
+            return Ok(Plan::AlterTable(None));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if entry.item_type() != CatalogItemType::Table {
+        bail!("{} is a {} not a table", name, entry.item_type())
+    }
+    if entry.id().is_system() {
+        bail!("cannot alter system table '{}'", entry.name());
+    }
+
+    // The new definition of the table is derived from the old one, so that it
+    // is planned exactly as a `CREATE TABLE` with the new columns would be.
+    let mut stmt = match crate::parse::parse(entry.create_sql())?.into_element() {
+        Statement::CreateTable(stmt) => stmt,
+        _ => unreachable!("table definition is not a CREATE TABLE statement"),
+    };
+    let position = |columns: &[ColumnDef], column_name: &ColumnName| {
+        columns
+            .iter()
+            .position(|c| normalize::column_name(c.name.clone()) == *column_name)
+    };
+
+    let migration = match action {
+        AlterTableAction::AddColumn {
+            if_not_exists,
+            mut column_def,
+        } => {
+            let column_name = normalize::column_name(column_def.name.clone());
+            if position(&stmt.columns, &column_name).is_some() {
+                if if_not_exists {
+                    // TODO(benesch): generate a notice indicating that the
+                    // column already exists.
+                    return Ok(Plan::AlterTable(None));
+                }
+                bail!(
+                    "column \"{}\" of relation \"{}\" already exists",
+                    column_name.as_str(),
+                    name
+                );
+            }
+            // The default only supplies the value of the column in the rows
+            // that already exist, so it is not part of the table definition.
+            let mut default = None;
+            column_def.options.retain(|option| match &option.option {
+                ColumnOption::Default(expr) => {
+                    default = Some(expr.clone());
+                    false
+                }
+                _ => true,
+            });
+            let ty = scalar_type_from_sql(scx, &column_def.data_type)?;
+            let default = match default {
+                Some(default) => query::plan_default_expr(scx, default, &ty)?,
+                None => expr::ScalarExpr::literal_null(ty.nullable(true)),
+            };
+            stmt.columns.push(column_def);
+            TableMigration::AddColumn { default }
+        }
+        AlterTableAction::DropColumn {
+            if_exists,
+            name: column_name,
+        } => {
+            let column_name = normalize::column_name(column_name);
+            match position(&stmt.columns, &column_name) {
+                Some(column) => {
+                    stmt.columns.remove(column);
+                    TableMigration::DropColumn { column }
+                }
+                None if if_exists => {
+                    // TODO(benesch): generate a notice indicating that the
+                    // column does not exist.
+                    return Ok(Plan::AlterTable(None));
+                }
+                None => bail!(
+                    "column \"{}\" of relation \"{}\" does not exist",
+                    column_name.as_str(),
+                    name
+                ),
+            }
+        }
+    };
+
+    let table = match handle_create_table(scx, stmt)? {
+        Plan::CreateTable { table, .. } => table,
+        _ => unreachable!("CREATE TABLE was not planned as a table"),
+    };
+    Ok(Plan::AlterTable(Some(AlterTable {
+        id: entry.id(),
+        table,
+        migration,
+    })))
+}
+
+fn handle_alter_source(
+    scx: &StatementContext,
+    AlterSourceStatement {
+        name,
+        if_exists,
+        options,
+    }: AlterSourceStatement,
+) -> Result<Plan, anyhow::Error> {
+    let entry = match scx.resolve_item(name.clone()) {
+        Ok(full_name) => scx.catalog.get_item(&full_name),
+        Err(_) if if_exists => {
+            // TODO(benesch): generate a notice indicating this
+            // item does not exist.
+            return Ok(Plan::AlterSource(None));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if entry.item_type() != CatalogItemType::Source {
+        bail!("{} is a {} not a source", name, entry.item_type())
+    }
+    if entry.id().is_system() {
+        bail!("cannot alter system source '{}'", entry.name());
+    }
+
+    // The new definition of the source is derived from the old one, so that it
+    // is planned exactly as a `CREATE SOURCE` with the new options would be.
+    let mut stmt = match crate::parse::parse(entry.create_sql())?.into_element() {
+        Statement::CreateSource(stmt) => stmt,
+        _ => unreachable!("source definition is not a CREATE SOURCE statement"),
+    };
+
+    // Options that only configure the Kafka client, and the frequency at
+    // which timestamps are closed, do not change the contents of the source.
+    // The Kafka client options also configure the schema registry client,
+    // however, which is baked into the decoding of the source.
+    let configures_client = match (&stmt.connector, &stmt.format) {
+        (_, Some(Format::Avro(AvroSchema::CsrUrl { .. }))) => false,
+        (Connector::Kafka { .. }, _) => true,
+        _ => false,
+    };
+    let mut restart_only = true;
+    for option in options {
+        let option_name = normalize::ident(option.name().clone());
+        if option_name != "timestamp_frequency_ms"
+            && !(configures_client && kafka_util::is_config_option(&option_name))
+        {
+            restart_only = false;
+        }
+        match stmt
+            .with_options
+            .iter_mut()
+            .find(|o| normalize::ident(o.name().clone()) == option_name)
+        {
+            Some(existing) => *existing = option,
+            None => stmt.with_options.push(option),
+        }
+    }
+
+    let source = match handle_create_source(scx, stmt)? {
+        Plan::CreateSource { source, .. } => source,
+        _ => unreachable!("CREATE SOURCE was not planned as a source"),
+    };
+    Ok(Plan::AlterSource(Some(AlterSource {
+        id: entry.id(),
+        source,
+        restart_only,
+    })))
+}
+
+fn handle_comment(
+    scx: &StatementContext,
+    CommentStatement { object, comment }: CommentStatement,
+) -> Result<Plan, anyhow::Error> {
+    let (entry, column) = match object {
+        CommentObject::Item { object_type, name } => {
+            let entry = scx.catalog.get_item(&scx.resolve_item(name.clone())?);
+            if entry.item_type() != object_type {
+                bail!("{} is a {} not a {}", name, entry.item_type(), object_type)
+            }
+            (entry, None)
+        }
+        CommentObject::Column { mut name } => {
+            if name.0.len() < 2 {
+                bail!(
+                    "column name {} must be qualified with a relation name",
+                    name
+                );
+            }
+            let column_name = normalize::column_name(name.0.pop().unwrap());
+            let entry = scx.catalog.get_item(&scx.resolve_item(name.clone())?);
+            let desc = match entry.desc() {
+                Ok(desc) => desc,
+                Err(_) => bail!(
+                    "{} is a {}, which does not have columns",
+                    name,
+                    entry.item_type()
+                ),
+            };
+            match desc.get_by_name(&column_name) {
+                Some((column, _)) => (entry, Some(column)),
+                None => bail!(
+                    "column \"{}\" of relation \"{}\" does not exist",
+                    column_name.as_str(),
+                    name
+                ),
+            }
+        }
+    };
+    if entry.id().is_system() {
+        bail!(
+            "cannot comment on system {} '{}'",
+            entry.item_type(),
+            entry.name()
+        );
+    }
+    Ok(Plan::Comment {
+        id: entry.id(),
+        column,
+        comment,
+    })
+}
+
 fn kafka_sink_builder(
     format: Option<Format>,
+    envelope: SinkEnvelope,
     with_options: Vec<SqlOption>,
     broker: String,
     topic_prefix: String,
@@ -556,20 +947,6 @@ fn kafka_sink_builder(
     topic_suffix: String,
     key_indices: Option<Vec<usize>>,
 ) -> Result<SinkConnectorBuilder, anyhow::Error> {
-    let (schema_registry_url, ccsr_with_options) = match format {
-        Some(Format::Avro(AvroSchema::CsrUrl {
-            url,
-            seed,
-            with_options,
-        })) => {
-            if seed.is_some() {
-                bail!("SEED option does not make sense with sinks");
-            }
-            (url.parse::<Url>()?, normalize::options(&with_options))
-        }
-        _ => unsupported!("non-confluent schema registry avro sinks"),
-    };
-
     let broker_addrs = broker.parse()?;
 
     let mut with_options = normalize::options(&with_options);
@@ -579,11 +956,34 @@ fn kafka_sink_builder(
         Some(_) => bail!("consistency must be a boolean"),
     };
 
-    let encoder = Encoder::new(desc, include_consistency, key_indices.clone());
-    let value_schema = encoder.writer_schema().canonical_form();
-    let key_schema = encoder
-        .key_writer_schema()
-        .map(|key_schema| key_schema.canonical_form());
+    let exactly_once = match with_options.remove("exactly_once") {
+        Some(Value::Boolean(b)) => b,
+        None => false,
+        Some(_) => bail!("exactly_once must be a boolean"),
+    };
+
+    if exactly_once && !include_consistency {
+        bail!("exactly_once requires consistency to be enabled");
+    }
+
+    let reuse_topic = match with_options.remove("reuse_topic") {
+        Some(Value::Boolean(b)) => b,
+        None => false,
+        Some(_) => bail!("reuse_topic must be a boolean"),
+    };
+
+    if reuse_topic && !include_consistency {
+        bail!("reuse_topic requires consistency to be enabled");
+    }
+
+    if envelope == SinkEnvelope::Upsert {
+        if key_indices.is_none() {
+            bail!("upsert sinks require a KEY");
+        }
+        if include_consistency {
+            bail!("upsert sinks do not support consistency");
+        }
+    }
 
     // Use the user supplied value for replication factor, or default to 1
     let replication_factor = match with_options.remove("replication_factor") {
@@ -596,32 +996,95 @@ fn kafka_sink_builder(
         bail!("replication factor for sink topics has to be greater than zero");
     }
 
-    let consistency_value_schema = if include_consistency {
-        Some(avro::get_debezium_transaction_schema().canonical_form())
-    } else {
-        None
+    let config_options = extract_config(&with_options)?;
+
+    let format = match format {
+        Some(Format::Avro(AvroSchema::CsrUrl {
+            url,
+            seed,
+            with_options: ccsr_with_options,
+        })) => {
+            if seed.is_some() {
+                bail!("SEED option does not make sense with sinks");
+            }
+            let schema_registry_url = url.parse::<Url>()?;
+            let ccsr_with_options = normalize::options(&ccsr_with_options);
+            let ccsr_config = generate_ccsr_client_config(
+                schema_registry_url.clone(),
+                &config_options,
+                &ccsr_with_options,
+            )?;
+
+            let encoder = match envelope {
+                SinkEnvelope::Debezium => {
+                    Encoder::new(desc, include_consistency, key_indices.clone())
+                }
+                SinkEnvelope::Upsert => {
+                    Encoder::new_upsert(desc, key_indices.clone().expect("upsert sinks have a key"))
+                }
+            };
+            let value_schema = encoder.writer_schema().canonical_form();
+            let key_schema = encoder
+                .key_writer_schema()
+                .map(|key_schema| key_schema.canonical_form());
+
+            KafkaSinkFormatBuilder::Avro {
+                schema_registry_url,
+                key_schema,
+                value_schema,
+                ccsr_config,
+            }
+        }
+        Some(Format::Json) => KafkaSinkFormatBuilder::Json,
+        Some(Format::Protobuf {
+            message_name,
+            schema,
+        }) => {
+            if key_indices.is_some() {
+                unsupported!("KEY with protobuf sinks");
+            }
+            let descriptors = match schema {
+                sql_parser::ast::Schema::Inline(bytes) => strconv::parse_bytes(&bytes)?,
+                sql_parser::ast::Schema::File(_) => {
+                    unreachable!("File schema should already have been inlined")
+                }
+            };
+            // Validate that the message can represent the sink's rows.
+            protobuf::Encoder::new(decode_descriptors(&descriptors)?, &message_name, &desc)?;
+            KafkaSinkFormatBuilder::Protobuf(ProtobufEncoding {
+                descriptors,
+                message_name,
+            })
+        }
+        Some(Format::Avro(_)) => unsupported!("non-confluent schema registry avro sinks"),
+        Some(_) | None => {
+            unsupported!("kafka sinks with formats other than AVRO, JSON or PROTOBUF")
+        }
     };
 
-    let config_options = extract_config(&with_options)?;
+    // The consistency topic is always written in Avro, and so needs a schema
+    // registry to publish its schema to.
+    let consistency_value_schema = match (&format, include_consistency) {
+        (KafkaSinkFormatBuilder::Avro { .. }, true) => {
+            Some(avro::get_debezium_transaction_schema().canonical_form())
+        }
+        (_, true) => bail!("consistency is only supported for Avro sinks"),
+        (_, false) => None,
+    };
 
-    let ccsr_config = generate_ccsr_client_config(
-        schema_registry_url.clone(),
-        &config_options,
-        &ccsr_with_options,
-    )?;
     Ok(SinkConnectorBuilder::Kafka(KafkaSinkConnectorBuilder {
         broker_addrs,
-        schema_registry_url,
-        value_schema,
+        format,
+        envelope,
         topic_prefix,
         topic_suffix,
         replication_factor,
         fuel: 10000,
         consistency_value_schema,
         config_options,
-        ccsr_config,
         key_indices,
-        key_schema,
+        exactly_once,
+        reuse_topic,
     }))
 }
 
@@ -662,12 +1125,13 @@ fn handle_create_sink(
         connector,
         with_options,
         format,
+        envelope,
         with_snapshot,
         as_of,
         if_not_exists,
     } = stmt;
     let name = scx.allocate_name(normalize::object_name(name)?);
-    let from = scx.catalog.get_item(&scx.resolve_item(from)?);
+    let (from, desc) = plan_sink_relation(scx, from, "written to a sink")?;
     let suffix = format!(
         "{}-{}",
         scx.catalog
@@ -677,41 +1141,24 @@ fn handle_create_sink(
         scx.catalog.nonce()
     );
 
+    let envelope = match envelope {
+        None | Some(sql_parser::ast::Envelope::Debezium) => SinkEnvelope::Debezium,
+        Some(sql_parser::ast::Envelope::Upsert(None)) => SinkEnvelope::Upsert,
+        Some(sql_parser::ast::Envelope::Upsert(Some(_))) => bail!(
+            "upsert sinks do not support a key FORMAT; the key format matches the value format"
+        ),
+        Some(sql_parser::ast::Envelope::None) => unsupported!("ENVELOPE NONE sinks"),
+        Some(sql_parser::ast::Envelope::CdcV2) => unsupported!("ENVELOPE MATERIALIZE sinks"),
+    };
+
     let as_of = as_of.map(|e| query::eval_as_of(scx, e)).transpose()?;
     let connector_builder = match connector {
         Connector::File { .. } => unsupported!("file sinks"),
         Connector::Kafka { broker, topic, key } => {
-            let desc = from.desc()?;
-            let key_indices = if let Some(key) = key {
-                let key = key
-                    .into_iter()
-                    .map(normalize::column_name)
-                    .collect::<Vec<_>>();
-                let mut uniq = HashSet::new();
-                for col in key.iter() {
-                    if !uniq.insert(col) {
-                        bail!("Repeated column name in sink key: {}", col);
-                    }
-                }
-                let indices = key
-                    .into_iter()
-                    .map(|col| -> anyhow::Result<usize> {
-                        let name_idx = desc
-                            .get_by_name(&col)
-                            .map(|(idx, _type)| idx)
-                            .ok_or_else(|| anyhow!("No such column: {}", col))?;
-                        if desc.get_unambiguous_name(name_idx).is_none() {
-                            bail!("Ambiguous column: {}", col);
-                        }
-                        Ok(name_idx)
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
-                Some(indices)
-            } else {
-                None
-            };
+            let key_indices = key.map(|key| plan_sink_key(&desc, key)).transpose()?;
             kafka_sink_builder(
                 format,
+                envelope,
                 with_options,
                 broker,
                 topic,
@@ -721,14 +1168,19 @@ fn handle_create_sink(
             )?
         }
         Connector::Kinesis { .. } => unsupported!("Kinesis sinks"),
-        Connector::AvroOcf { path } => avro_ocf_sink_builder(format, with_options, path, suffix)?,
+        Connector::AvroOcf { path } => {
+            if envelope != SinkEnvelope::Debezium {
+                bail!("avro ocf sinks cannot specify an envelope");
+            }
+            avro_ocf_sink_builder(format, with_options, path, suffix)?
+        }
     };
 
     Ok(Plan::CreateSink {
         name,
         sink: Sink {
             create_sql,
-            from: from.id(),
+            from,
             connector_builder,
         },
         with_snapshot,
@@ -889,6 +1341,7 @@ fn handle_create_view(
     params: &Params,
 ) -> Result<Plan, anyhow::Error> {
     let create_sql = normalize::create_statement(scx, Statement::CreateView(stmt.clone()))?;
+    let depends_on = query::user_defined_dependencies(scx, &Statement::CreateView(stmt.clone()));
     let CreateViewStatement {
         name,
         columns,
@@ -909,8 +1362,12 @@ fn handle_create_view(
     let replace = if *if_exists == IfExistsBehavior::Replace
         && scx.catalog.resolve_item(&name.clone().into()).is_ok()
     {
-        let cascade = false;
-        handle_drop_item(scx, ObjectType::View, &name, cascade)?
+        // The items that depend on the view are checked by the coordinator,
+        // which can often replace the view without disturbing them.
+        let cascade = true;
+        let entry = scx.catalog.get_item(&name);
+        handle_drop_item(scx, ObjectType::View, entry, &[], cascade)?;
+        Some(entry.id())
     } else {
         None
     };
@@ -931,6 +1388,7 @@ fn handle_create_view(
             expr: relation_expr,
             column_names: desc.iter_names().map(|n| n.cloned()).collect(),
             temporary,
+            depends_on,
         },
         replace,
         materialize,
@@ -998,6 +1456,177 @@ fn handle_create_map_type(
     })
 }
 
+fn handle_create_enum_type(
+    scx: &StatementContext,
+    stmt: CreateEnumTypeStatement,
+) -> Result<Plan, anyhow::Error> {
+    let create_sql = normalize::create_statement(scx, Statement::CreateEnumType(stmt.clone()))?;
+    let CreateEnumTypeStatement { name, labels } = stmt;
+
+    for (i, label) in labels.iter().enumerate() {
+        // Labels are limited to the length of an identifier, as in
+        // PostgreSQL.
+        if label.is_empty() || label.len() > 63 {
+            bail!(
+                "invalid enum label \"{}\": labels must be between 1 and 63 bytes long",
+                label
+            );
+        }
+        if labels[..i].contains(label) {
+            bail!("enum label \"{}\" used more than once", label);
+        }
+    }
+
+    let name = scx.allocate_name(normalize::object_name(name)?);
+    if scx.catalog.type_exists(&name) {
+        bail!("type \"{}\" already exists", name.to_string());
+    }
+
+    Ok(Plan::CreateType {
+        name,
+        typ: Type {
+            create_sql,
+            inner: TypeInner::Enum { labels },
+        },
+    })
+}
+
+fn handle_create_composite_type(
+    scx: &StatementContext,
+    stmt: CreateCompositeTypeStatement,
+) -> Result<Plan, anyhow::Error> {
+    let create_sql =
+        normalize::create_statement(scx, Statement::CreateCompositeType(stmt.clone()))?;
+    let depends_on =
+        query::user_defined_dependencies(scx, &Statement::CreateCompositeType(stmt.clone()));
+    let CreateCompositeTypeStatement { name, fields } = stmt;
+
+    let mut planned_fields: Vec<(ColumnName, ScalarType)> = vec![];
+    for field in fields {
+        if field.collation.is_some() {
+            unsupported!("CREATE TYPE with COLLATE");
+        }
+        let field_name = normalize::column_name(field.name);
+        if planned_fields.iter().any(|(name, _)| *name == field_name) {
+            bail!(
+                "column \"{}\" specified more than once",
+                field_name.as_str()
+            );
+        }
+        let ty = scalar_type_from_sql(scx, &field.data_type)?;
+        planned_fields.push((field_name, ty));
+    }
+
+    let name = scx.allocate_name(normalize::object_name(name)?);
+    if scx.catalog.type_exists(&name) {
+        bail!("type \"{}\" already exists", name.to_string());
+    }
+
+    Ok(Plan::CreateType {
+        name,
+        typ: Type {
+            create_sql,
+            inner: TypeInner::Record {
+                fields: planned_fields,
+                depends_on,
+            },
+        },
+    })
+}
+
+fn handle_create_function(
+    scx: &StatementContext,
+    stmt: CreateFunctionStatement,
+) -> Result<Plan, anyhow::Error> {
+    let create_sql = normalize::create_statement(scx, Statement::CreateFunction(stmt.clone()))?;
+    let mut depends_on =
+        query::user_defined_dependencies(scx, &Statement::CreateFunction(stmt.clone()));
+    let CreateFunctionStatement {
+        name,
+        params,
+        returns,
+        body,
+    } = stmt;
+
+    let mut planned_params: Vec<(Option<ColumnName>, ScalarType)> = vec![];
+    for param in params {
+        let param_name = param.name.map(normalize::column_name);
+        if let Some(param_name) = &param_name {
+            if planned_params
+                .iter()
+                .any(|(name, _)| name.as_ref() == Some(param_name))
+            {
+                bail!(
+                    "parameter name \"{}\" used more than once",
+                    param_name.as_str()
+                );
+            }
+        }
+        let ty = scalar_type_from_sql(scx, &param.data_type)?;
+        planned_params.push((param_name, ty));
+    }
+
+    // Plan the body now, both to report errors in it eagerly and to determine
+    // the relations that it depends upon. Parameters are replaced with nulls,
+    // as only the shape of the plan matters here.
+    let nulls: Vec<_> = planned_params
+        .iter()
+        .map(|(_name, ty)| ScalarExpr::literal_null(ty.clone()))
+        .collect();
+    let (returns, expr) = match returns {
+        FunctionReturnType::Scalar(data_type) => {
+            let return_type = scalar_type_from_sql(scx, &data_type)?;
+            let mut expr = query::plan_scalar_function_body(
+                scx,
+                body,
+                &planned_params,
+                &return_type,
+                QueryLifetime::OneShot,
+            )?;
+            expr.splice_parameters(&nulls, 0);
+            let expr = RelationExpr::constant(vec![vec![]], RelationType::empty()).map(vec![expr]);
+            (FunctionReturns::Scalar(return_type), expr)
+        }
+        FunctionReturnType::Table(columns) => {
+            let mut desc = RelationDesc::empty();
+            for column in columns {
+                let column_name = normalize::column_name(column.name);
+                if desc.iter_names().any(|name| name == Some(&column_name)) {
+                    bail!(
+                        "column \"{}\" specified more than once",
+                        column_name.as_str()
+                    );
+                }
+                let ty = scalar_type_from_sql(scx, &column.data_type)?;
+                desc = desc.with_column(column_name, ty.nullable(true));
+            }
+            let mut expr = query::plan_table_function_body(
+                scx,
+                body,
+                &planned_params,
+                &desc,
+                QueryLifetime::OneShot,
+            )?;
+            expr.splice_parameters(&nulls, 0);
+            (FunctionReturns::Table(desc), expr)
+        }
+    };
+    depends_on.extend(expr.decorrelate().global_uses());
+    depends_on.sort();
+    depends_on.dedup();
+
+    let name = scx.allocate_name(normalize::object_name(name)?);
+    Ok(Plan::CreateFunction {
+        name,
+        function: Function {
+            create_sql,
+            params: planned_params,
+            returns,
+            depends_on,
+        },
+    })
+}
+
 fn extract_timestamp_frequency_option(
     with_options: &mut HashMap<String, Value>,
 ) -> Result<Duration, anyhow::Error> {
@@ -1517,7 +2146,7 @@ fn handle_create_table(
         columns
             .iter()
             .map(|c| {
-                let ty = scalar_type_from_sql(&c.data_type)?;
+                let ty = scalar_type_from_sql(scx, &c.data_type)?;
                 let mut nullable = true;
                 for option in c.options.iter() {
                     match &option.option {
@@ -1535,8 +2164,13 @@ fn handle_create_table(
     let name = scx.allocate_name(normalize::object_name(name.clone())?);
     let desc = RelationDesc::new(typ, names);
 
+    let depends_on = query::user_defined_dependencies(scx, &Statement::CreateTable(stmt.clone()));
     let create_sql = normalize::create_statement(&scx, Statement::CreateTable(stmt.clone()))?;
-    let table = Table { create_sql, desc };
+    let table = Table {
+        create_sql,
+        desc,
+        depends_on,
+    };
     Ok(Plan::CreateTable {
         name,
         table,
@@ -1576,21 +2210,26 @@ fn maybe_rename_columns(
 
 fn handle_drop_database(
     scx: &StatementContext,
-    DropDatabaseStatement { name, if_exists }: DropDatabaseStatement,
+    DropDatabaseStatement { names, if_exists }: DropDatabaseStatement,
 ) -> Result<Plan, anyhow::Error> {
-    let name = match scx.resolve_database_ident(name) {
-        Ok((name, _id)) => name,
-        Err(_) if if_exists => {
-            // TODO(benesch): generate a notice indicating that the database
-            // does not exist.
-            //
-            // TODO(benesch): adjust the type here so we can more clearly
-            // indicate that we don't want to drop any database at all.
-            String::new()
+    let mut database_names = vec![];
+    for name in names {
+        match scx.resolve_database_ident(name) {
+            Ok((name, _id)) => {
+                if !database_names.contains(&name) {
+                    database_names.push(name);
+                }
+            }
+            Err(_) if if_exists => {
+                // TODO(benesch): generate a notice indicating that the
+                // database does not exist.
+            }
+            Err(err) => return Err(err.into()),
         }
-        Err(err) => return Err(err.into()),
-    };
-    Ok(Plan::DropDatabase { name })
+    }
+    Ok(Plan::DropDatabases {
+        names: database_names,
+    })
 }
 
 fn handle_drop_objects(
@@ -1603,58 +2242,64 @@ fn handle_drop_objects(
     }: DropObjectsStatement,
 ) -> Result<Plan, anyhow::Error> {
     match object_type {
-        ObjectType::Schema => handle_drop_schema(scx, if_exists, names, cascade),
+        ObjectType::Schema => handle_drop_schemas(scx, if_exists, names, cascade),
         ObjectType::Source
         | ObjectType::Table
         | ObjectType::View
         | ObjectType::Index
         | ObjectType::Sink
-        | ObjectType::Type => handle_drop_items(scx, object_type, if_exists, names, cascade),
+        | ObjectType::Type
+        | ObjectType::Function => handle_drop_items(scx, object_type, if_exists, names, cascade),
     }
 }
 
-fn handle_drop_schema(
+fn handle_drop_schemas(
     scx: &StatementContext,
     if_exists: bool,
     names: Vec<ObjectName>,
     cascade: bool,
 ) -> Result<Plan, anyhow::Error> {
-    if names.len() != 1 {
-        unsupported!("DROP SCHEMA with multiple schemas");
-    }
-    match scx.resolve_schema(names.into_element()) {
-        Ok((database_spec, schema_spec)) => {
-            if let DatabaseSpecifier::Ambient = database_spec {
-                bail!(
-                    "cannot drop schema {} because it is required by the database system",
-                    schema_spec.name
-                );
+    let mut schemas = vec![];
+    for name in names {
+        match scx.resolve_schema(name) {
+            Ok((database_spec, schema_spec)) => {
+                if let DatabaseSpecifier::Ambient = database_spec {
+                    bail!(
+                        "cannot drop schema {} because it is required by the database system",
+                        schema_spec.name
+                    );
+                }
+                let schema = (database_spec, schema_spec.name);
+                if !schemas.contains(&schema) {
+                    schemas.push(schema);
+                }
             }
-            let mut items = scx.catalog.list_items(&database_spec, &schema_spec.name);
-            if !cascade && items.next().is_some() {
+            Err(_) if if_exists => {
+                // TODO(benesch): generate a notice indicating that the
+                // schema does not exist.
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if !cascade {
+        for (database_spec, schema_name) in &schemas {
+            let mut items: Vec<_> = scx
+                .catalog
+                .list_items(database_spec, schema_name)
+                .map(|item| format!("'{}'", item.name()))
+                .collect();
+            if !items.is_empty() {
+                items.sort();
                 bail!(
-                    "schema '{}.{}' cannot be dropped without CASCADE while it contains objects",
+                    "schema '{}.{}' cannot be dropped without CASCADE while it contains objects: {}",
                     database_spec,
-                    schema_spec.name
+                    schema_name,
+                    items.join(", ")
                 );
             }
-            Ok(Plan::DropSchema {
-                database_name: database_spec,
-                schema_name: schema_spec.name,
-            })
-        }
-        Err(_) if if_exists => {
-            // TODO(benesch): generate a notice indicating that the
-            // database does not exist.
-            // TODO(benesch): adjust the types here properly, rather than making
-            // up a nonexistent database.
-            Ok(Plan::DropSchema {
-                database_name: DatabaseSpecifier::Ambient,
-                schema_name: "noexist".into(),
-            })
         }
-        Err(e) => Err(e.into()),
     }
+    Ok(Plan::DropSchemas { schemas })
 }
 
 fn handle_drop_items(
@@ -1664,14 +2309,10 @@ fn handle_drop_items(
     names: Vec<ObjectName>,
     cascade: bool,
 ) -> Result<Plan, anyhow::Error> {
-    let names = names
-        .into_iter()
-        .map(|n| scx.resolve_item(n))
-        .collect::<Vec<_>>();
-    let mut ids = vec![];
+    let mut entries = vec![];
     for name in names {
-        match name {
-            Ok(name) => ids.extend(handle_drop_item(scx, object_type, &name, cascade)?),
+        match scx.resolve_item(name) {
+            Ok(name) => entries.push(scx.catalog.get_item(&name)),
             Err(_) if if_exists => {
                 // TODO(benesch): generate a notice indicating this
                 // item does not exist.
@@ -1679,48 +2320,66 @@ fn handle_drop_items(
             Err(err) => return Err(err.into()),
         }
     }
+    let mut ids = vec![];
+    for entry in &entries {
+        let id = entry.id();
+        if !ids.contains(&id) {
+            handle_drop_item(scx, object_type, *entry, &entries, cascade)?;
+            ids.push(id);
+        }
+    }
     Ok(Plan::DropItems {
         items: ids,
         ty: object_type,
     })
 }
 
+/// Validates that `catalog_entry` can be dropped. Unless `cascade` is set, an
+/// item cannot be dropped while other items depend upon it, save for those
+/// items in `dropping` that are being dropped by the same statement.
 fn handle_drop_item(
     scx: &StatementContext,
     object_type: ObjectType,
-    name: &FullName,
+    catalog_entry: &dyn CatalogItem,
+    dropping: &[&dyn CatalogItem],
     cascade: bool,
-) -> Result<Option<GlobalId>, anyhow::Error> {
-    let catalog_entry = scx.catalog.get_item(name);
+) -> Result<(), anyhow::Error> {
     if catalog_entry.id().is_system() {
         bail!(
             "cannot drop item {} because it is required by the database system",
-            name
+            catalog_entry.name()
         );
     }
     if object_type != catalog_entry.item_type() {
-        bail!("{} is not of type {}", name, object_type);
+        bail!("{} is not of type {}", catalog_entry.name(), object_type);
     }
     if !cascade {
+        let mut blocking = vec![];
         for id in catalog_entry.used_by() {
+            if dropping.iter().any(|entry| entry.id() == *id) {
+                continue;
+            }
             let dep = scx.catalog.get_item_by_id(id);
             match dep.item_type() {
                 CatalogItemType::Table
                 | CatalogItemType::Source
                 | CatalogItemType::View
                 | CatalogItemType::Sink
-                | CatalogItemType::Type => {
-                    bail!(
-                        "cannot drop {}: still depended upon by catalog item '{}'",
-                        catalog_entry.name(),
-                        dep.name()
-                    );
-                }
+                | CatalogItemType::Type
+                | CatalogItemType::Func => blocking.push(format!("'{}'", dep.name())),
                 CatalogItemType::Index => (),
             }
         }
+        if !blocking.is_empty() {
+            bail!(
+                "cannot drop {}: still depended upon by catalog {} {}",
+                catalog_entry.name(),
+                if blocking.len() == 1 { "item" } else { "items" },
+                blocking.join(", ")
+            );
+        }
     }
-    Ok(Some(catalog_entry.id()))
+    Ok(())
 }
 
 fn handle_insert(
@@ -1739,6 +2398,47 @@ fn handle_insert(
     Ok(Plan::Insert { id, values: expr })
 }
 
+/// Plans `stmt` as in [`handle_statement`], but leaves its parameters, whose
+/// types are `param_types`, as placeholders, so that the plan can be reused
+/// with different values for the parameters.
+///
+/// Only `SELECT` statements without an `AS OF` clause can be planned this
+/// way. The query is planned with a static lifetime, so queries whose plans
+/// depend on the time at which they are planned cannot be planned this way
+/// either.
+pub fn handle_reusable_statement(
+    pcx: &PlanContext,
+    catalog: &dyn Catalog,
+    stmt: Statement,
+    param_types: &[ScalarType],
+) -> Result<Plan, anyhow::Error> {
+    let scx = &StatementContext {
+        pcx,
+        catalog,
+        param_types: Rc::new(RefCell::new(
+            param_types
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| (i + 1, ty.clone()))
+                .collect(),
+        )),
+    };
+    match stmt {
+        Statement::Select(SelectStatement { query, as_of: None }) => {
+            let (mut expr, _, finishing) =
+                query::plan_root_query(scx, query, QueryLifetime::Static)?;
+            expr.bind_parameter_placeholders(param_types)?;
+            Ok(Plan::Peek {
+                source: expr.decorrelate(),
+                when: PeekWhen::Immediately,
+                finishing,
+                copy_to: None,
+            })
+        }
+        _ => bail!("only SELECT statements without AS OF can be reused"),
+    }
+}
+
 fn handle_select(
     scx: &StatementContext,
     SelectStatement { query, as_of }: SelectStatement,
@@ -1846,7 +2546,8 @@ impl PartialEq<ObjectType> for CatalogItemType {
             | (CatalogItemType::Sink, ObjectType::Sink)
             | (CatalogItemType::View, ObjectType::View)
             | (CatalogItemType::Index, ObjectType::Index)
-            | (CatalogItemType::Type, ObjectType::Type) => true,
+            | (CatalogItemType::Type, ObjectType::Type)
+            | (CatalogItemType::Func, ObjectType::Function) => true,
             (_, _) => false,
         }
     }
@@ -2027,6 +2728,10 @@ with_options! { struct CopyOptions {
     format: String,
 } }
 
+with_options! { struct FetchOptions {
+    timeout: String,
+} }
+
 with_options! { struct TailOptions {
     snapshot: bool,
     progress: bool,
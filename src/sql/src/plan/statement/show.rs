@@ -132,6 +132,7 @@ pub fn show_objects<'a>(
         ObjectType::Sink => show_sinks(scx, full, from, filter),
         ObjectType::Type => show_types(scx, extended, full, from, filter),
         ObjectType::Index => unreachable!("SHOW INDEX handled separately"),
+        ObjectType::Function => unsupported!("SHOW FUNCTIONS"),
     }
 }
 
@@ -184,29 +185,29 @@ fn show_tables<'a>(
     from: Option<ObjectName>,
     filter: Option<ShowStatementFilter>,
 ) -> Result<ShowSelect<'a>, anyhow::Error> {
-    if extended {
-        unsupported!("SHOW EXTENDED TABLES");
-    }
-
     let schema_spec = if let Some(from) = from {
         scx.resolve_schema(from)?.1
     } else {
         scx.resolve_default_schema()?
     };
 
-    let query = if full {
-        format!(
-            "SELECT name, mz_internal.mz_classify_object_id(id) AS type
-            FROM mz_catalog.mz_tables
-            WHERE schema_id = {}",
-            schema_spec.id,
-        )
-    } else {
-        format!(
-            "SELECT name FROM mz_catalog.mz_tables WHERE schema_id = {}",
-            schema_spec.id
-        )
-    };
+    let mut query = format!(
+        "SELECT
+            t.name,
+            mz_internal.mz_classify_object_id(t.id) AS type,
+            mz_internal.mz_is_materialized(t.id) AS materialized
+        FROM mz_catalog.mz_tables t
+        JOIN mz_catalog.mz_schemas s ON t.schema_id = s.id
+        WHERE t.schema_id = {}",
+        schema_spec.id,
+    );
+    if extended {
+        query += " OR s.database_id IS NULL";
+    }
+    if !full {
+        query = format!("SELECT name FROM ({})", query);
+    }
+
     Ok(ShowSelect::new(scx, query, filter))
 }
 
@@ -368,9 +369,6 @@ pub fn show_indexes<'a>(
         filter,
     }: ShowIndexesStatement,
 ) -> Result<ShowSelect<'a>, anyhow::Error> {
-    if extended {
-        unsupported!("SHOW EXTENDED INDEXES")
-    }
     let from_name = scx.resolve_item(table_name)?;
     let from_entry = scx.catalog.get_item(&from_name);
     if from_entry.item_type() != CatalogItemType::View
@@ -384,6 +382,14 @@ pub fn show_indexes<'a>(
         );
     }
 
+    // The extended form also reports each key as a single expression, whether
+    // it is a plain column reference or an arbitrary expression.
+    let key_expression = if extended {
+        ",
+            coalesce(obj_cols.name, idx_cols.on_expression) AS key_expression"
+    } else {
+        ""
+    };
     let query = format!(
         "SELECT
             objs.name AS on_name,
@@ -391,7 +397,7 @@ pub fn show_indexes<'a>(
             idx_cols.index_position AS seq_in_index,
             obj_cols.name AS column_name,
             idx_cols.on_expression AS expression,
-            idx_cols.nullable AS nullable
+            idx_cols.nullable AS nullable{}
         FROM
             mz_catalog.mz_indexes AS idxs
             JOIN mz_catalog.mz_index_columns AS idx_cols ON idxs.id = idx_cols.index_id
@@ -400,6 +406,7 @@ pub fn show_indexes<'a>(
                 ON idxs.on_id = obj_cols.id AND idx_cols.on_position = obj_cols.position
         WHERE
             objs.id = '{}'",
+        key_expression,
         from_entry.id(),
     );
     Ok(ShowSelect::new(scx, query, filter))
@@ -414,23 +421,23 @@ pub fn show_columns<'a>(
         filter,
     }: ShowColumnsStatement,
 ) -> Result<ShowSelect<'a>, anyhow::Error> {
+    let name = scx.resolve_item(table_name)?;
+    let entry = scx.catalog.get_item(&name);
+
+    let mut select_list = "mz_columns.name, mz_columns.nullable, mz_columns.type".to_string();
     if extended {
-        unsupported!("SHOW EXTENDED COLUMNS");
+        select_list += ", mz_columns.position";
     }
     if full {
-        unsupported!("SHOW FULL COLUMNS");
+        select_list += ", mz_comments.comment";
     }
-
-    let name = scx.resolve_item(table_name)?;
-    let entry = scx.catalog.get_item(&name);
-
     let query = format!(
-        "SELECT
-            mz_columns.name,
-            mz_columns.nullable,
-            mz_columns.type
+        "SELECT {}
          FROM mz_catalog.mz_columns AS mz_columns
+         LEFT JOIN mz_catalog.mz_comments AS mz_comments
+            ON mz_columns.id = mz_comments.id AND mz_columns.position = mz_comments.position
          WHERE mz_columns.id = '{}'",
+        select_list,
         entry.id(),
     );
     Ok(ShowSelect::new(scx, query, filter))
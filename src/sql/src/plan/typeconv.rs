@@ -245,6 +245,10 @@ lazy_static! {
             (String, Bytes) => Explicit: CastStringToBytes,
             (String, Jsonb) => Explicit: CastStringToJsonb,
             (String, Uuid) => Explicit: CastStringToUuid,
+            (String, Enum { oid: 0, name: "".into(), labels: vec![] }) => Explicit: CastOp::new(|_ecx, e, to_type| {
+                Ok(e.call_unary(CastStringToEnum { return_ty: to_type.clone() }))
+            }),
+            (String, Record { fields: vec![] }) => Explicit: CastOp::new(plan_cast_string_to_record),
 
             // RECORD
             (Record { fields: vec![] }, String) => Assignment: CastOp::new(|ecx, e, _to_type| {
@@ -252,6 +256,12 @@ lazy_static! {
                 Ok(e.call_unary(CastRecordToString { ty }))
             }),
 
+            // ENUM
+            (Enum { oid: 0, name: "".into(), labels: vec![] }, String) => Assignment: CastOp::new(|ecx, e, _to_type| {
+                let ty = ecx.scalar_type(&e);
+                Ok(e.call_unary(CastEnumToString { ty }))
+            }),
+
             // ARRAY
             (Array(Box::new(String)), String) => Assignment: CastOp::new(|ecx, e, _to_type| {
                 let ty = ecx.scalar_type(&e);
@@ -472,22 +482,24 @@ pub fn plan_coerce<'a>(
 
         LiteralRecord(exprs) => {
             let arity = exprs.len();
-            let coercions = match coerce_to {
+            let (field_names, coercions) = match coerce_to {
+                // Coercing to a composite type adopts the names of its fields.
                 ScalarType::Record { fields, .. } if fields.len() == arity => {
-                    fields.iter().map(|(_name, ty)| ty).cloned().collect()
+                    fields.iter().cloned().unzip()
                 }
-                _ => vec![ScalarType::String; exprs.len()],
+                _ => (
+                    (0..arity)
+                        .map(|i| ColumnName::from(format!("f{}", i + 1)))
+                        .collect(),
+                    vec![ScalarType::String; exprs.len()],
+                ),
             };
             let mut out = vec![];
             for (e, coerce_to) in exprs.into_iter().zip(coercions) {
                 out.push(plan_coerce(ecx, e, &coerce_to)?);
             }
             ScalarExpr::CallVariadic {
-                func: VariadicFunc::RecordCreate {
-                    field_names: (0..arity)
-                        .map(|i| ColumnName::from(format!("f{}", i + 1)))
-                        .collect(),
-                },
+                func: VariadicFunc::RecordCreate { field_names },
                 exprs: out,
             }
         }
@@ -572,6 +584,68 @@ pub fn plan_cast_to_list(
     }))
 }
 
+/// Plans a cast from a [`ScalarType::String`] to a [`ScalarType::Record`],
+/// which parses the string as a record literal and casts each of its fields to
+/// the type of the corresponding field of the record.
+fn plan_cast_string_to_record(
+    ecx: &ExprContext,
+    expr: ScalarExpr,
+    to: &ScalarType,
+) -> Result<ScalarExpr, anyhow::Error> {
+    let fields = match to {
+        ScalarType::Record { fields } => fields,
+        _ => unreachable!(
+            "plan_cast_string_to_record called with non-record type {}",
+            to
+        ),
+    };
+
+    // Reconstruct an expression context where each field is evaluated on the
+    // "first column" of some imaginary row.
+    let mut scx = ecx.qcx.scx.clone();
+    scx.param_types = Rc::new(RefCell::new(BTreeMap::new()));
+    let qcx = QueryContext::root(&scx, ecx.qcx.lifetime);
+    let relation_type = RelationType {
+        column_types: vec![ColumnType {
+            nullable: true,
+            scalar_type: ScalarType::String,
+        }],
+        keys: vec![vec![0]],
+    };
+    let ecx = ExprContext {
+        qcx: &qcx,
+        name: "plan_cast_string_to_record",
+        scope: &Scope::empty(None),
+        relation_type: &relation_type,
+        allow_aggregates: false,
+        allow_subqueries: true,
+    };
+
+    let mut cast_exprs = vec![];
+    for (_name, typ) in fields {
+        let col_expr = ScalarExpr::Column(ColumnRef {
+            level: 0,
+            column: 0,
+        });
+        let cast_expr = plan_cast(
+            "plan_cast_string_to_record",
+            &ecx,
+            CastContext::Explicit,
+            col_expr,
+            typ,
+        )?;
+        cast_exprs.push(cast_expr.lower_uncorrelated().expect(
+            "lower_uncorrelated should not fail given that there is no correlation \
+            in the input col_expr",
+        ));
+    }
+
+    Ok(expr.call_unary(UnaryFunc::CastStringToRecord {
+        return_ty: to.clone(),
+        cast_exprs,
+    }))
+}
+
 /// Plans a cast between [`ScalarType`]s, specifying which types of casts are
 /// permitted using [`CastTo`].
 ///
@@ -16,7 +16,8 @@ use tokio::io::AsyncBufReadExt;
 
 use repr::strconv;
 use sql_parser::ast::{
-    AvroSchema, Connector, CreateSourceStatement, CsrSeed, Format, Ident, Statement,
+    AvroSchema, Connector, CreateSinkStatement, CreateSourceStatement, CsrSeed, Format, Ident,
+    Statement,
 };
 
 use crate::kafka_util;
@@ -80,9 +81,26 @@ pub async fn purify(mut stmt: Statement) -> Result<Statement, anyhow::Error> {
             purify_format(format, connector, col_names, None, &config_options).await?;
         }
     }
+    if let Statement::CreateSink(CreateSinkStatement {
+        format: Some(Format::Protobuf { schema, .. }),
+        ..
+    }) = &mut stmt
+    {
+        inline_protobuf_schema(schema).await?;
+    }
     Ok(stmt)
 }
 
+async fn inline_protobuf_schema(schema: &mut sql_parser::ast::Schema) -> Result<(), anyhow::Error> {
+    if let sql_parser::ast::Schema::File(path) = schema {
+        let descriptors = tokio::fs::read(path).await?;
+        let mut buf = String::new();
+        strconv::format_bytes(&mut buf, &descriptors);
+        *schema = sql_parser::ast::Schema::Inline(buf);
+    }
+    Ok(())
+}
+
 async fn purify_format(
     format: &mut Option<Format>,
     connector: &mut Connector,
@@ -128,14 +146,7 @@ async fn purify_format(
             }
             _ => {}
         },
-        Some(Format::Protobuf { schema, .. }) => {
-            if let sql_parser::ast::Schema::File(path) = schema {
-                let descriptors = tokio::fs::read(path).await?;
-                let mut buf = String::new();
-                strconv::format_bytes(&mut buf, &descriptors);
-                *schema = sql_parser::ast::Schema::Inline(buf);
-            }
-        }
+        Some(Format::Protobuf { schema, .. }) => inline_protobuf_schema(schema).await?,
         Some(Format::Csv {
             header_row,
             delimiter,
@@ -37,7 +37,7 @@ use uuid::Uuid;
 
 use pgrepr::Jsonb;
 use repr::adt::decimal::Significand;
-use repr::{Datum, RelationDesc, RelationType, Row, RowPacker, ScalarType};
+use repr::{Datum, RelationDesc, RelationType, Row, RowPacker};
 use sql::ast::{
     ColumnOption, CreateTableStatement, DataType, DeleteStatement, DropObjectsStatement,
     InsertStatement, ObjectType, Statement, TableConstraint, UpdateStatement,
@@ -45,7 +45,10 @@ use sql::ast::{
 use sql::catalog::Catalog;
 use sql::names::FullName;
 use sql::normalize;
-use sql::plan::{scalar_type_from_sql, MutationKind, Plan, PlanContext, StatementContext, Table};
+use sql::plan::{
+    scalar_type_from_sql, user_defined_dependencies, MutationKind, Plan, PlanContext,
+    StatementContext, Table,
+};
 
 pub struct Postgres {
     client: tokio_postgres::Client,
@@ -158,7 +161,7 @@ END $$;
                     columns
                         .iter()
                         .map(|c| {
-                            let ty = scalar_type_from_sql(&c.data_type)?;
+                            let ty = scalar_type_from_sql(&scx, &c.data_type)?;
                             let nullable =
                                 !c.options.iter().any(|o| o.option == ColumnOption::NotNull);
                             Ok(ty.nullable(nullable))
@@ -214,6 +217,7 @@ END $$;
                 let table = Table {
                     create_sql: stmt.to_string(),
                     desc,
+                    depends_on: user_defined_dependencies(&scx, stmt),
                 };
                 Plan::CreateTable {
                     name,
@@ -408,11 +412,8 @@ fn push_column(
             let iv = get_column_inner::<pgrepr::Interval>(postgres_row, i, nullable)?.unwrap();
             row.push(Datum::Interval(iv.0));
         }
-        DataType::Decimal(_, _) => {
-            let desired_scale = match scalar_type_from_sql(sql_type).unwrap() {
-                ScalarType::Decimal(_precision, desired_scale) => desired_scale,
-                _ => unreachable!(),
-            };
+        DataType::Decimal(_, scale) => {
+            let desired_scale = scale.unwrap_or(0) as u8;
             match get_column_inner::<pgrepr::Numeric>(postgres_row, i, nullable)? {
                 None => row.push(Datum::Null),
                 Some(d) => {
@@ -17,20 +17,35 @@ use tokio::stream::StreamExt;
 
 use crate::action::{Action, State};
 use crate::format::avro;
+use crate::format::protobuf;
 use crate::parser::BuiltinCommand;
 
 pub enum SinkConsistencyFormat {
     Debezium,
 }
 
+pub enum SinkFormat {
+    Avro,
+    Json,
+    Protobuf { message: protobuf::MessageType },
+}
+
 pub struct VerifyAction {
+    format: SinkFormat,
     sink: String,
     consistency: Option<SinkConsistencyFormat>,
     expected_messages: Vec<String>,
 }
 
 pub fn build_verify(mut cmd: BuiltinCommand) -> Result<VerifyAction, String> {
-    let _format = cmd.args.string("format")?;
+    let format = match cmd.args.string("format")?.as_str() {
+        "avro" => SinkFormat::Avro,
+        "json" => SinkFormat::Json,
+        "protobuf" => SinkFormat::Protobuf {
+            message: cmd.args.parse("message")?,
+        },
+        f => return Err(format!("unknown format: {}", f)),
+    };
     let sink = cmd.args.string("sink")?;
     let consistency = match cmd.args.opt_string("consistency").as_deref() {
         Some("debezium") => Some(SinkConsistencyFormat::Debezium),
@@ -41,6 +56,7 @@ pub fn build_verify(mut cmd: BuiltinCommand) -> Result<VerifyAction, String> {
     let expected_messages = cmd.input;
     cmd.args.done()?;
     Ok(VerifyAction {
+        format,
         sink,
         consistency,
         expected_messages,
@@ -73,6 +89,98 @@ fn avro_from_bytes(
     Ok(datum)
 }
 
+/// Decodes a protobuf sink's envelope around `message` into JSON.
+fn decode_envelope(
+    message: protobuf::MessageType,
+    bytes: &[u8],
+) -> Result<serde_json::Value, String> {
+    fn decode<M>(bytes: &[u8]) -> Result<serde_json::Value, String>
+    where
+        M: protobuf::Message + serde::Serialize,
+    {
+        let envelope = ::protobuf::parse_from_bytes::<M>(bytes)
+            .map_err(|e| format!("decoding protobuf: {}", e))?;
+        serde_json::to_value(envelope).map_err(|e| format!("converting protobuf to json: {}", e))
+    }
+
+    match message {
+        protobuf::MessageType::Struct => decode::<protobuf::gen::simple::StructEnvelope>(bytes),
+        protobuf::MessageType::Batch => {
+            Err("protobuf sinks of the batch message cannot be verified".into())
+        }
+    }
+}
+
+impl VerifyAction {
+    /// Verifies the messages in a JSON or Protobuf sink's topic.
+    ///
+    /// Each expected message is a line containing the JSON value, optionally
+    /// preceded by the JSON key. A message without a payload, like a tombstone
+    /// in an upsert sink, matches a `null` value. Since sink timestamps are not
+    /// deterministic, the `timestamp` field of each actual value is ignored.
+    ///
+    /// Protobuf values are decoded as the envelope message that protobuf sinks
+    /// write for the built-in message, and then compared as JSON.
+    async fn verify_json(&self, state: &mut State, topic: &str) -> Result<(), String> {
+        let consumer: StreamConsumer = state
+            .kafka_config
+            .create()
+            .map_err(|e| format!("creating kafka consumer: {}", e))?;
+        consumer.subscribe(&[topic]).map_err(|e| e.to_string())?;
+
+        let mut message_stream = consumer
+            .start()
+            .take(self.expected_messages.len())
+            .timeout(Duration::from_secs(15));
+
+        let parse = |bytes: &[u8]| -> Result<serde_json::Value, String> {
+            serde_json::from_slice(bytes).map_err(|e| format!("parsing json: {}", e))
+        };
+
+        let mut actual = vec![];
+        while let Some(Ok(message)) = message_stream.next().await {
+            let message = message.map_err(|e| e.to_string())?;
+            let key = message.key().map(parse).transpose()?;
+            // Tombstones in upsert sinks have no payload.
+            let mut value = match (message.payload(), &self.format) {
+                (None, _) => serde_json::Value::Null,
+                (Some(bytes), SinkFormat::Protobuf { message }) => {
+                    decode_envelope(*message, bytes)?
+                }
+                (Some(bytes), _) => parse(bytes)?,
+            };
+            if let Some(value) = value.as_object_mut() {
+                value.remove("timestamp");
+            }
+            actual.push((key, value));
+        }
+
+        let mut expected = vec![];
+        for line in &self.expected_messages {
+            let values = serde_json::Deserializer::from_str(line)
+                .into_iter::<serde_json::Value>()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("parsing json: {}", e))?;
+            match values.len() {
+                1 => expected.push((None, values.into_iter().next().unwrap())),
+                2 => {
+                    let mut values = values.into_iter();
+                    expected.push((values.next(), values.next().unwrap()));
+                }
+                _ => return Err(format!("expected a value and optional key: {}", line)),
+            }
+        }
+
+        if expected != actual {
+            return Err(format!(
+                "records did not match\nexpected:\n{:#?}\n\nactual:\n{:#?}",
+                expected, actual
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Action for VerifyAction {
     async fn undo(&self, _state: &mut State) -> Result<(), String> {
@@ -97,6 +205,10 @@ impl Action for VerifyAction {
 
         println!("Verifying results in Kafka topic {}", topic);
 
+        if let SinkFormat::Json | SinkFormat::Protobuf { .. } = self.format {
+            return self.verify_json(state, &topic).await;
+        }
+
         let value_schema = state
             .ccsr_client
             .get_schema_by_subject(&format!("{}-value", topic))
@@ -341,10 +341,7 @@ pub fn optimize(
                 nullable: row.unpack_first() == Datum::Null,
             }
         }
-        ScalarExpr::CallNullary(_) => {
-            expr.reduce(input_type);
-            optimize(expr, input_type, column_knowledge)?
-        }
+        ScalarExpr::CallNullary(func) => DatumKnowledge::from(&func.output_type()),
         ScalarExpr::CallUnary { func, expr: inner } => {
             let knowledge = optimize(inner, input_type, column_knowledge)?;
             if knowledge.value.is_some() {
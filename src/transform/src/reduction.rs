@@ -37,6 +37,9 @@ impl crate::Transform for FoldConstants {
 
 impl FoldConstants {
     /// Replace operators on constants collections with constant collections.
+    ///
+    /// Operators whose expressions call nullary functions are not replaced,
+    /// as nullary functions are placeholders for values that are not yet known.
     pub fn action(&self, relation: &mut RelationExpr) -> Result<(), TransformError> {
         let relation_type = relation.typ();
         match relation {
@@ -49,10 +52,14 @@ impl FoldConstants {
                 aggregates,
                 monotonic: _,
             } => {
+                let calls_nullary = group_key
+                    .iter()
+                    .chain(aggregates.iter().map(|a| &a.expr))
+                    .any(|e| e.calls_nullary());
                 for aggregate in aggregates.iter_mut() {
                     aggregate.expr.reduce(&input.typ());
                 }
-                if let RelationExpr::Constant { rows, .. } = &**input {
+                if let (RelationExpr::Constant { rows, .. }, false) = (&**input, calls_nullary) {
                     // Build a map from `group_key` to `Vec<Vec<an, ..., a1>>)`,
                     // where `an` is the input to the nth aggregate function in
                     // `aggregates`.
@@ -144,6 +151,7 @@ impl FoldConstants {
                 }
             }
             RelationExpr::Map { input, scalars } => {
+                let calls_nullary = scalars.iter().any(|e| e.calls_nullary());
                 // Before reducing the scalar expressions, we need to form an appropriate
                 // RelationType to provide to each. Each expression needs a different
                 // relation type; although we could in principle use `relation_type` here,
@@ -162,7 +170,7 @@ impl FoldConstants {
                     scalar.reduce(&current_type);
                 }
 
-                if let RelationExpr::Constant { rows, .. } = &**input {
+                if let (RelationExpr::Constant { rows, .. }, false) = (&**input, calls_nullary) {
                     let mut row_packer = repr::RowPacker::new();
                     let new_rows = rows
                         .iter()
@@ -188,11 +196,12 @@ impl FoldConstants {
                 exprs,
                 demand: _,
             } => {
+                let calls_nullary = exprs.iter().any(|e| e.calls_nullary());
                 for expr in exprs.iter_mut() {
                     expr.reduce(&input.typ());
                 }
 
-                if let RelationExpr::Constant { rows, .. } = &**input {
+                if let (RelationExpr::Constant { rows, .. }, false) = (&**input, calls_nullary) {
                     let mut new_rows = Vec::new();
                     let mut row_packer = repr::RowPacker::new();
                     for (input_row, diff) in rows {
@@ -218,6 +227,7 @@ impl FoldConstants {
                 }
             }
             RelationExpr::Filter { input, predicates } => {
+                let calls_nullary = predicates.iter().any(|p| p.calls_nullary());
                 for predicate in predicates.iter_mut() {
                     predicate.reduce(&input.typ());
                 }
@@ -229,7 +239,9 @@ impl FoldConstants {
                     .any(|p| p.is_literal_false() || p.is_literal_null())
                 {
                     relation.take_safely();
-                } else if let RelationExpr::Constant { rows, .. } = &**input {
+                } else if let (RelationExpr::Constant { rows, .. }, false) =
+                    (&**input, calls_nullary)
+                {
                     let mut new_rows = Vec::new();
                     'outer: for (row, diff) in rows {
                         let datums = row.unpack();
@@ -268,11 +280,13 @@ impl FoldConstants {
                 equivalences,
                 ..
             } => {
+                let calls_nullary = equivalences.iter().flatten().any(|e| e.calls_nullary());
                 if inputs.iter().any(|e| e.is_empty()) {
                     relation.take_safely();
-                } else if inputs
-                    .iter()
-                    .all(|i| matches!(i, RelationExpr::Constant { .. }))
+                } else if !calls_nullary
+                    && inputs
+                        .iter()
+                        .all(|i| matches!(i, RelationExpr::Constant { .. }))
                 {
                     // We can fold all constant inputs together, but must apply the constraints to restrict them.
                     // We start with a single 0-ary row.